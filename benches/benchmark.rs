@@ -4,7 +4,7 @@ extern crate laz;
 
 use criterion::Criterion;
 
-use laz::las::file::QuickHeader;
+use laz::las::file::LasHeader;
 use laz::las::v2;
 use laz::packers::Packable;
 use laz::record::{RecordCompressor, SequentialPointRecordCompressor};
@@ -15,7 +15,7 @@ use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
 fn point0_v2_compression_benchmark(c: &mut Criterion) {
     c.bench_function("point0_v2_compression", |b| {
         let mut test_file = std::io::BufReader::new(std::fs::File::open("tests/data/point10.laz").unwrap());
-        let hdr = QuickHeader::read_from(&mut test_file).unwrap();
+        let hdr = LasHeader::read_from(&mut test_file).unwrap();
         test_file.seek(SeekFrom::Start(hdr.offset_to_points as u64)).unwrap();
         let mut points_data = Vec::<u8>::new();
         test_file.read_to_end(&mut points_data).unwrap();
@@ -45,7 +45,7 @@ impl RawPointsData {
 
 fn get_raw_points_data(path: &str) -> RawPointsData {
     let mut test_file = BufReader::new(File::open(path).unwrap());
-    let hdr = QuickHeader::read_from(&mut test_file).unwrap();
+    let hdr = LasHeader::read_from(&mut test_file).unwrap();
     test_file
         .seek(SeekFrom::Start(hdr.offset_to_points as u64))
         .unwrap();
@@ -0,0 +1,222 @@
+//! Reading of COPC (Cloud Optimized Point Cloud) files.
+//!
+//! A COPC file is a LAZ 1.4 file (point formats 6, 7 or 8) with **variable-size** chunks,
+//! where each chunk holds the points of one node of an octree.
+//!
+//! - The `copc info` VLR ([CopcInfo]) gives the bounds of the octree
+//!   and the location of the root hierarchy page.
+//! - The hierarchy pages (stored in an EVLR) list the nodes of the octree,
+//!   with the location of their compressed points.
+//!
+//! See <https://copc.io> for the specification.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # fn main() -> laz::Result<()> {
+//! use laz::copc::{Bounds, CopcReader};
+//!
+//! let mut reader = CopcReader::new(std::fs::File::open("autzen.copc.laz")?)?;
+//! let bounds = Bounds {
+//!     min: [637_000.0, 851_000.0, 0.0],
+//!     max: [638_000.0, 852_000.0, 1_000.0],
+//! };
+//! for node in reader.query(&bounds, 3) {
+//!     let points = reader.decompress_node(&node)?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::io::{Read, Write};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+use crate::laszip::ChunkTableEntry;
+
+pub use reader::CopcReader;
+pub use writer::CopcWriter;
+
+mod reader;
+mod writer;
+
+/// Size in bytes of an entry of a hierarchy page.
+const HIERARCHY_ENTRY_SIZE: u64 = 32;
+
+/// Content of the `copc info` VLR.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CopcInfo {
+    /// Center of the root node
+    pub center_x: f64,
+    pub center_y: f64,
+    pub center_z: f64,
+    /// Half of the size of one side of the root node
+    pub halfsize: f64,
+    /// Space between points at the root node
+    pub spacing: f64,
+    /// Offset from the start of the file to the root hierarchy page
+    pub root_hier_offset: u64,
+    /// Size in bytes of the root hierarchy page
+    pub root_hier_size: u64,
+    pub gpstime_minimum: f64,
+    pub gpstime_maximum: f64,
+}
+
+impl CopcInfo {
+    /// The user id of the COPC info VLR.
+    pub const USER_ID: &'static str = "copc";
+    /// The record id of the COPC info VLR.
+    pub const RECORD_ID: u16 = 1;
+    /// Size in bytes of the COPC info VLR data.
+    pub const SIZE: usize = 160;
+
+    /// Reads the COPC info from the VLR data
+    pub fn read_from<R: Read>(mut src: R) -> std::io::Result<Self> {
+        let info = Self {
+            center_x: src.read_f64::<LittleEndian>()?,
+            center_y: src.read_f64::<LittleEndian>()?,
+            center_z: src.read_f64::<LittleEndian>()?,
+            halfsize: src.read_f64::<LittleEndian>()?,
+            spacing: src.read_f64::<LittleEndian>()?,
+            root_hier_offset: src.read_u64::<LittleEndian>()?,
+            root_hier_size: src.read_u64::<LittleEndian>()?,
+            gpstime_minimum: src.read_f64::<LittleEndian>()?,
+            gpstime_maximum: src.read_f64::<LittleEndian>()?,
+        };
+        // 11 reserved u64
+        let mut reserved = [0u8; 88];
+        src.read_exact(&mut reserved)?;
+        Ok(info)
+    }
+
+    /// Writes the COPC info as VLR data
+    pub fn write_to<W: Write>(&self, mut dst: W) -> std::io::Result<()> {
+        dst.write_f64::<LittleEndian>(self.center_x)?;
+        dst.write_f64::<LittleEndian>(self.center_y)?;
+        dst.write_f64::<LittleEndian>(self.center_z)?;
+        dst.write_f64::<LittleEndian>(self.halfsize)?;
+        dst.write_f64::<LittleEndian>(self.spacing)?;
+        dst.write_u64::<LittleEndian>(self.root_hier_offset)?;
+        dst.write_u64::<LittleEndian>(self.root_hier_size)?;
+        dst.write_f64::<LittleEndian>(self.gpstime_minimum)?;
+        dst.write_f64::<LittleEndian>(self.gpstime_maximum)?;
+        dst.write_all(&[0u8; 88])
+    }
+
+    /// Returns the bounds of the root node.
+    pub fn bounds(&self) -> Bounds {
+        self.bounds_of(&VoxelKey::ROOT)
+    }
+
+    /// Returns the bounds of the node designated by the `key`.
+    pub fn bounds_of(&self, key: &VoxelKey) -> Bounds {
+        let side = 2.0 * self.halfsize / 2f64.powi(key.level);
+        let min = [
+            self.center_x - self.halfsize + f64::from(key.x) * side,
+            self.center_y - self.halfsize + f64::from(key.y) * side,
+            self.center_z - self.halfsize + f64::from(key.z) * side,
+        ];
+        Bounds {
+            min,
+            max: [min[0] + side, min[1] + side, min[2] + side],
+        }
+    }
+}
+
+/// Axis aligned bounding box.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Bounds {
+    /// Minimum x, y, z
+    pub min: [f64; 3],
+    /// Maximum x, y, z
+    pub max: [f64; 3],
+}
+
+impl Bounds {
+    /// Returns whether the two bounds share at least one point.
+    pub fn intersects(&self, other: &Bounds) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+    }
+}
+
+/// Key of a node in the octree.
+///
+/// At `level` L, the octree is divided in 2^L voxels in each dimension,
+/// `x`, `y`, `z` are the indices of the voxel.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct VoxelKey {
+    pub level: i32,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl VoxelKey {
+    /// The key of the root node.
+    pub const ROOT: VoxelKey = VoxelKey {
+        level: 0,
+        x: 0,
+        y: 0,
+        z: 0,
+    };
+
+    /// Returns the key of the parent node, `None` for the root node.
+    pub fn parent(&self) -> Option<VoxelKey> {
+        if self.level <= 0 {
+            return None;
+        }
+        Some(VoxelKey {
+            level: self.level - 1,
+            x: self.x >> 1,
+            y: self.y >> 1,
+            z: self.z >> 1,
+        })
+    }
+
+    fn read_from<R: Read>(src: &mut R) -> std::io::Result<Self> {
+        Ok(Self {
+            level: src.read_i32::<LittleEndian>()?,
+            x: src.read_i32::<LittleEndian>()?,
+            y: src.read_i32::<LittleEndian>()?,
+            z: src.read_i32::<LittleEndian>()?,
+        })
+    }
+
+    fn write_to<W: Write>(&self, dst: &mut W) -> std::io::Result<()> {
+        dst.write_i32::<LittleEndian>(self.level)?;
+        dst.write_i32::<LittleEndian>(self.x)?;
+        dst.write_i32::<LittleEndian>(self.y)?;
+        dst.write_i32::<LittleEndian>(self.z)
+    }
+
+    /// Returns the key of the child in the octant (`dx`, `dy`, `dz` are 0 or 1).
+    fn child(&self, dx: i32, dy: i32, dz: i32) -> VoxelKey {
+        VoxelKey {
+            level: self.level + 1,
+            x: 2 * self.x + dx,
+            y: 2 * self.y + dy,
+            z: 2 * self.z + dz,
+        }
+    }
+}
+
+/// A node of the octree, and the location of its compressed points.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CopcNode {
+    pub key: VoxelKey,
+    /// Number of points in the node, can be 0.
+    pub point_count: u64,
+    /// Offset from the start of the file to the compressed points.
+    pub byte_offset: u64,
+    /// Number of compressed bytes.
+    pub byte_size: u64,
+}
+
+impl CopcNode {
+    /// Returns the chunk table entry of the node's chunk.
+    pub fn entry(&self) -> ChunkTableEntry {
+        ChunkTableEntry {
+            point_count: self.point_count,
+            byte_count: self.byte_size,
+        }
+    }
+}
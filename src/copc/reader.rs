@@ -0,0 +1,186 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::copc::{Bounds, CopcInfo, CopcNode, VoxelKey, HIERARCHY_ENTRY_SIZE};
+use crate::las::file::{LasHeader, Vlr};
+use crate::laszip::ChunkDecoder;
+use crate::{LasZipError, LazVlr};
+
+/// Reader of COPC files.
+///
+/// All the hierarchy pages are read when the reader is created,
+/// the points of a node are read and decompressed on demand.
+pub struct CopcReader<R> {
+    header: LasHeader,
+    info: CopcInfo,
+    decoder: ChunkDecoder,
+    /// Nodes sorted by level
+    nodes: Vec<CopcNode>,
+    /// Index of the node in `nodes` by key
+    node_index: HashMap<VoxelKey, usize>,
+    source: R,
+}
+
+impl<R: Read + Seek> CopcReader<R> {
+    /// Creates a new reader, the source must be at the start of the file.
+    ///
+    /// Fails with [LasZipError::MissingVlr] if the COPC info could not be found,
+    /// and [LasZipError::MissingLasZipVlr] if the LasZip VLR could not be found.
+    pub fn new(mut source: R) -> crate::Result<Self> {
+        let header = LasHeader::read_from(&mut source)?;
+        source.seek(SeekFrom::Start(u64::from(header.header_size)))?;
+        let mut info = None;
+        let mut laz_vlr = None;
+        for _ in 0..header.num_vlrs {
+            let vlr = Vlr::read_from(&mut source)?;
+            if vlr.user_id() == CopcInfo::USER_ID && vlr.record_id() == CopcInfo::RECORD_ID {
+                info = Some(CopcInfo::read_from(vlr.data())?);
+            } else if vlr.user_id() == LazVlr::USER_ID && vlr.record_id() == LazVlr::RECORD_ID {
+                laz_vlr = Some(LazVlr::read_from(vlr.data())?);
+            }
+        }
+        let info = info.ok_or(LasZipError::MissingVlr {
+            user_id: CopcInfo::USER_ID,
+            record_id: CopcInfo::RECORD_ID,
+        })?;
+        let laz_vlr = laz_vlr.ok_or(LasZipError::MissingLasZipVlr)?;
+        let decoder = ChunkDecoder::new(laz_vlr)?;
+
+        let mut nodes = Vec::<CopcNode>::new();
+        let mut pages = vec![(info.root_hier_offset, info.root_hier_size)];
+        // Offsets of the pages read, as a page referencing itself
+        // or one of its ancestors would make us loop forever
+        let mut visited_pages = HashSet::<u64>::new();
+        while let Some((page_offset, page_size)) = pages.pop() {
+            if !visited_pages.insert(page_offset) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "COPC hierarchy page at offset {} is referenced more than once",
+                        page_offset
+                    ),
+                )
+                .into());
+            }
+            source.seek(SeekFrom::Start(page_offset))?;
+            for _ in 0..page_size / HIERARCHY_ENTRY_SIZE {
+                let key = VoxelKey::read_from(&mut source)?;
+                let offset = source.read_u64::<LittleEndian>()?;
+                let byte_size = source.read_i32::<LittleEndian>()?;
+                let point_count = source.read_i32::<LittleEndian>()?;
+                if point_count == -1 {
+                    // The entry is a child hierarchy page
+                    pages.push((offset, byte_size as u64));
+                } else if point_count < 0 || byte_size < 0 {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "Invalid COPC hierarchy entry",
+                    )
+                    .into());
+                } else {
+                    nodes.push(CopcNode {
+                        key,
+                        point_count: point_count as u64,
+                        byte_offset: offset,
+                        byte_size: byte_size as u64,
+                    });
+                }
+            }
+        }
+        nodes.sort_by_key(|node| (node.key.level, node.key.x, node.key.y, node.key.z));
+        let node_index = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.key, i))
+            .collect();
+
+        Ok(Self {
+            header,
+            info,
+            decoder,
+            nodes,
+            node_index,
+            source,
+        })
+    }
+
+    /// Returns the header of the file.
+    pub fn header(&self) -> &LasHeader {
+        &self.header
+    }
+
+    /// Returns the content of the COPC info VLR.
+    pub fn info(&self) -> &CopcInfo {
+        &self.info
+    }
+
+    /// Returns the LasZip VLR of the file.
+    pub fn vlr(&self) -> &LazVlr {
+        self.decoder.vlr()
+    }
+
+    /// Returns all the nodes of the octree, sorted by level.
+    pub fn nodes(&self) -> &[CopcNode] {
+        &self.nodes
+    }
+
+    /// Returns the node with the `key`, if it exists.
+    pub fn node(&self, key: &VoxelKey) -> Option<&CopcNode> {
+        self.node_index.get(key).map(|&i| &self.nodes[i])
+    }
+
+    /// Returns the nodes up to `max_level` (included) whose bounds intersect the `bounds`.
+    ///
+    /// Lower levels hold a sparser subset of the points, so limiting the level
+    /// gives a coarser level of detail.
+    pub fn query(&self, bounds: &Bounds, max_level: i32) -> Vec<CopcNode> {
+        self.nodes
+            .iter()
+            .take_while(|node| node.key.level <= max_level)
+            .filter(|node| self.info.bounds_of(&node.key).intersects(bounds))
+            .copied()
+            .collect()
+    }
+
+    /// Reads and decompresses the points of the `node`.
+    pub fn decompress_node(&mut self, node: &CopcNode) -> crate::Result<Vec<u8>> {
+        let mut compressed = vec![0u8; node.byte_size as usize];
+        self.source.seek(SeekFrom::Start(node.byte_offset))?;
+        self.source.read_exact(&mut compressed)?;
+        self.decoder.decode(&node.entry(), &compressed)
+    }
+
+    /// Reads and decompresses the points of the `nodes` using multiple threads.
+    ///
+    /// The points are returned in the order of the `nodes`.
+    #[cfg(feature = "parallel")]
+    pub fn par_decompress_nodes(&mut self, nodes: &[CopcNode]) -> crate::Result<Vec<u8>> {
+        let mut compressed = Vec::<u8>::new();
+        let mut entries = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            let start = compressed.len();
+            compressed.resize(start + node.byte_size as usize, 0);
+            self.source.seek(SeekFrom::Start(node.byte_offset))?;
+            self.source.read_exact(&mut compressed[start..])?;
+            entries.push(node.entry());
+        }
+        let num_points = nodes.iter().map(|node| node.point_count).sum::<u64>();
+        let mut points = vec![0u8; (num_points * self.vlr().items_size()) as usize];
+        crate::par_decompress(&compressed, &mut points, self.vlr(), &entries)?;
+        Ok(points)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.source
+    }
+
+    pub fn get(&self) -> &R {
+        &self.source
+    }
+}
@@ -0,0 +1,375 @@
+use std::convert::TryFrom;
+use std::io::{Seek, SeekFrom, Write};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+
+use crate::copc::{CopcInfo, VoxelKey, HIERARCHY_ENTRY_SIZE};
+use crate::las::file::{Evlr, LasHeader, Vlr};
+use crate::laszip::{LasZipCompressor, LazItemRecordBuilder, LazVlrBuilder};
+use crate::packers::Packable;
+use crate::{LasZipError, LazVlr};
+
+/// Size of the LAS 1.4 header
+const HEADER_SIZE: u16 = 375;
+/// Record id of the hierarchy EVLR, its user id is the one of the COPC info
+const HIERARCHY_RECORD_ID: u16 = 1000;
+/// Number of cells of the root node in each dimension, used to compute the spacing
+const ROOT_RESOLUTION: f64 = 128.0;
+/// Nodes at this level keep all their points
+const MAX_LEVEL: i32 = 24;
+/// Offset of the gps time in point formats 6, 7 and 8
+const GPS_TIME_OFFSET: usize = 22;
+
+/// Writer of COPC files.
+///
+/// The points are kept in memory until [done](Self::done) is called,
+/// the octree is then built and each node is compressed as one **variable-size** chunk.
+///
+/// A node holds at most `max_points_per_node` points, evenly sampled from the points
+/// inside its bounds, the other points are given to its children.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> laz::Result<()> {
+/// use laz::copc::CopcWriter;
+/// # let points: Vec<u8> = vec![];
+///
+/// let file = std::fs::File::create("out.copc.laz")?;
+/// let mut writer = CopcWriter::new(file, 6, 0, [0.01, 0.01, 0.01], [0.0, 0.0, 0.0])?;
+/// writer.write_points(&points);
+/// writer.done()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct CopcWriter<W> {
+    dest: W,
+    vlr: LazVlr,
+    point_format_id: u8,
+    scales: [f64; 3],
+    offsets: [f64; 3],
+    max_points_per_node: usize,
+    // Points written so far
+    points: Vec<u8>,
+    // Whether done was called
+    is_done: bool,
+}
+
+impl<W: Write + Seek + Send> CopcWriter<W> {
+    /// Default maximum number of points in a node
+    pub const DEFAULT_MAX_POINTS_PER_NODE: usize = 100_000;
+
+    /// Creates a new writer, the `dest` position **must** be at the start of the file.
+    ///
+    /// The `point_format_id` must be 6, 7 or 8, `scales` and `offsets` are
+    /// the ones used to get the coordinates of the points.
+    pub fn new(
+        dest: W,
+        point_format_id: u8,
+        num_extra_bytes: u16,
+        scales: [f64; 3],
+        offsets: [f64; 3],
+    ) -> crate::Result<Self> {
+        if !(6..=8).contains(&point_format_id) {
+            return Err(LasZipError::UnsupportedPointFormat(point_format_id));
+        }
+        let items =
+            LazItemRecordBuilder::default_for_point_format_id(point_format_id, num_extra_bytes)?;
+        let vlr = LazVlrBuilder::new(items).with_variable_chunk_size().build();
+        Ok(Self {
+            dest,
+            vlr,
+            point_format_id,
+            scales,
+            offsets,
+            max_points_per_node: Self::DEFAULT_MAX_POINTS_PER_NODE,
+            points: vec![],
+            is_done: false,
+        })
+    }
+
+    /// Sets the maximum number of points a node can hold.
+    ///
+    /// # Panics
+    ///
+    /// If `max_points_per_node` is 0.
+    pub fn set_max_points_per_node(&mut self, max_points_per_node: usize) {
+        assert!(max_points_per_node > 0);
+        self.max_points_per_node = max_points_per_node;
+    }
+
+    /// Adds the points, they are written when [done](Self::done) is called.
+    pub fn write_points(&mut self, points: &[u8]) {
+        debug_assert_eq!(points.len() % self.vlr.items_size() as usize, 0);
+        self.points.extend_from_slice(points);
+    }
+
+    /// Builds the octree and writes the file
+    ///
+    /// - The header, the COPC info VLR and the LasZip VLR
+    /// - The compressed points of each node (and the chunk table)
+    /// - The hierarchy EVLR
+    ///
+    /// The file can only be written once, calling this function again returns an error.
+    pub fn done(&mut self) -> crate::Result<()> {
+        if self.is_done {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "The COPC file was already written",
+            )
+            .into());
+        }
+        self.is_done = true;
+        let point_size = self.vlr.items_size() as usize;
+        let coords = self
+            .points
+            .chunks_exact(point_size)
+            .map(|point| {
+                let mut coords = [0f64; 3];
+                for (i, coord) in coords.iter_mut().enumerate() {
+                    let value = i32::unpack_from(&point[i * 4..(i + 1) * 4]);
+                    *coord = f64::from(value) * self.scales[i] + self.offsets[i];
+                }
+                coords
+            })
+            .collect::<Vec<_>>();
+
+        let mut min = [f64::MAX; 3];
+        let mut max = [f64::MIN; 3];
+        for point_coords in &coords {
+            for i in 0..3 {
+                min[i] = min[i].min(point_coords[i]);
+                max[i] = max[i].max(point_coords[i]);
+            }
+        }
+        if coords.is_empty() {
+            min = [0.0; 3];
+            max = [0.0; 3];
+        }
+        // The cube is padded by one scale unit so that rounding errors
+        // in the computation of the bounds of the nodes do not leave points out
+        let padding = self.scales.iter().copied().fold(0.0, f64::max);
+        let halfsize = (0..3).map(|i| max[i] - min[i]).fold(0.0, f64::max) / 2.0 + padding;
+        let mut info = CopcInfo {
+            center_x: (min[0] + max[0]) / 2.0,
+            center_y: (min[1] + max[1]) / 2.0,
+            center_z: (min[2] + max[2]) / 2.0,
+            halfsize,
+            spacing: 2.0 * halfsize / ROOT_RESOLUTION,
+            ..CopcInfo::default()
+        };
+
+        let mut num_points_by_return = [0u64; 15];
+        let mut gps_time_range = None;
+        for point in self.points.chunks_exact(point_size) {
+            let return_number = usize::from(point[14] & 0b1111);
+            if (1..=15).contains(&return_number) {
+                num_points_by_return[return_number - 1] += 1;
+            }
+            let mut gps_time_bytes = [0u8; 8];
+            gps_time_bytes.copy_from_slice(&point[GPS_TIME_OFFSET..GPS_TIME_OFFSET + 8]);
+            let gps_time = f64::from_le_bytes(gps_time_bytes);
+            gps_time_range = match gps_time_range {
+                None => Some((gps_time, gps_time)),
+                Some((gps_min, gps_max)) => Some((gps_time.min(gps_min), gps_time.max(gps_max))),
+            };
+        }
+        if let Some((gps_min, gps_max)) = gps_time_range {
+            info.gpstime_minimum = gps_min;
+            info.gpstime_maximum = gps_max;
+        }
+
+        let nodes = self.build_octree(&info, &coords);
+
+        // The header and the COPC info are written once everything else is known
+        let start_of_file = self.dest.stream_position()?;
+        self.dest.write_all(&[0u8; HEADER_SIZE as usize])?;
+        let info_vlr_pos = self.dest.stream_position()?;
+        Vlr::new(
+            CopcInfo::USER_ID,
+            CopcInfo::RECORD_ID,
+            "copc info",
+            vec![0u8; CopcInfo::SIZE],
+        )
+        .write_to(&mut self.dest)?;
+        let mut laz_vlr_data = Vec::<u8>::new();
+        self.vlr.write_to(&mut laz_vlr_data)?;
+        Vlr::new(
+            LazVlr::USER_ID,
+            LazVlr::RECORD_ID,
+            LazVlr::DESCRIPTION,
+            laz_vlr_data,
+        )
+        .write_to(&mut self.dest)?;
+
+        let offset_to_points = self.dest.stream_position()?;
+        let chunk_table = {
+            let points = &self.points;
+            let mut compressor = LasZipCompressor::new(&mut self.dest, self.vlr.clone())?;
+            compressor.compress_chunks(nodes.iter().map(|(_, indices)| {
+                let mut node_points = Vec::<u8>::with_capacity(indices.len() * point_size);
+                for &i in indices {
+                    let i = i as usize;
+                    node_points.extend_from_slice(&points[i * point_size..(i + 1) * point_size]);
+                }
+                node_points
+            }))?;
+            compressor.done()?;
+            compressor.chunk_table().clone()
+        };
+
+        let start_of_evlrs = self.dest.stream_position()?;
+        let page_size = nodes.len() as u64 * HIERARCHY_ENTRY_SIZE;
+        info.root_hier_offset = start_of_evlrs + Evlr::HEADER_SIZE as u64;
+        info.root_hier_size = page_size;
+        let mut page = Vec::<u8>::with_capacity(page_size as usize);
+        let mut chunk_offset = offset_to_points + crate::laszip::ChunkTable::OFFSET_SIZE as u64;
+        // Sizes and point counts of hierarchy entries are i32
+        let to_i32 = |value: u64, what: &str| {
+            i32::try_from(value).map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("The {} of a COPC node is too big: {}", what, value),
+                )
+            })
+        };
+        for ((key, indices), entry) in nodes.iter().zip(chunk_table.as_ref()) {
+            key.write_to(&mut page)?;
+            page.write_u64::<LittleEndian>(chunk_offset)?;
+            page.write_i32::<LittleEndian>(to_i32(entry.byte_count, "byte size")?)?;
+            page.write_i32::<LittleEndian>(to_i32(indices.len() as u64, "point count")?)?;
+            chunk_offset += entry.byte_count;
+        }
+        Evlr::new(
+            CopcInfo::USER_ID,
+            HIERARCHY_RECORD_ID,
+            "EPT hierarchy",
+            page,
+        )
+        .write_to(&mut self.dest)?;
+        let end_of_file = self.dest.stream_position()?;
+
+        self.dest.seek(SeekFrom::Start(start_of_file))?;
+        self.write_header(
+            offset_to_points as u32,
+            coords.len() as u64,
+            &num_points_by_return,
+            min,
+            max,
+            start_of_evlrs,
+        )?;
+        self.dest.seek(SeekFrom::Start(info_vlr_pos))?;
+        let mut info_data = Vec::<u8>::with_capacity(CopcInfo::SIZE);
+        info.write_to(&mut info_data)?;
+        Vlr::new(
+            CopcInfo::USER_ID,
+            CopcInfo::RECORD_ID,
+            "copc info",
+            info_data,
+        )
+        .write_to(&mut self.dest)?;
+        // The LasZip VLR follows the COPC info, it now knows where the hierarchy EVLR is
+        let mut laz_vlr = self.vlr.clone();
+        laz_vlr.set_special_evlrs(1, start_of_evlrs);
+        let mut laz_vlr_data = Vec::<u8>::new();
+        laz_vlr.write_to(&mut laz_vlr_data)?;
+        Vlr::new(
+            LazVlr::USER_ID,
+            LazVlr::RECORD_ID,
+            LazVlr::DESCRIPTION,
+            laz_vlr_data,
+        )
+        .write_to(&mut self.dest)?;
+        self.dest.seek(SeekFrom::Start(end_of_file))?;
+        self.dest.flush()?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.dest
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.dest
+    }
+
+    pub fn get(&self) -> &W {
+        &self.dest
+    }
+
+    /// Distributes the points in the nodes of the octree.
+    ///
+    /// Returns the key of each non-empty node with the indices of its points.
+    fn build_octree(&self, info: &CopcInfo, coords: &[[f64; 3]]) -> Vec<(VoxelKey, Vec<u32>)> {
+        let mut nodes = Vec::new();
+        let mut to_process = vec![(VoxelKey::ROOT, (0..coords.len() as u32).collect::<Vec<_>>())];
+        while let Some((key, indices)) = to_process.pop() {
+            if indices.is_empty() {
+                continue;
+            }
+            if indices.len() <= self.max_points_per_node || key.level >= MAX_LEVEL {
+                nodes.push((key, indices));
+                continue;
+            }
+            let bounds = info.bounds_of(&key);
+            let center = [
+                (bounds.min[0] + bounds.max[0]) / 2.0,
+                (bounds.min[1] + bounds.max[1]) / 2.0,
+                (bounds.min[2] + bounds.max[2]) / 2.0,
+            ];
+            // Taking one point every `step` keeps at most max_points_per_node points
+            let step = indices.len().div_ceil(self.max_points_per_node);
+            let mut kept = Vec::with_capacity(self.max_points_per_node);
+            let mut children = vec![Vec::new(); 8];
+            for (n, index) in indices.into_iter().enumerate() {
+                if n % step == 0 {
+                    kept.push(index);
+                } else {
+                    let point_coords = &coords[index as usize];
+                    let octant = (0..3)
+                        .filter(|&i| point_coords[i] >= center[i])
+                        .map(|i| 1 << i)
+                        .sum::<usize>();
+                    children[octant].push(index);
+                }
+            }
+            nodes.push((key, kept));
+            for (octant, child_indices) in children.into_iter().enumerate() {
+                let octant = octant as i32;
+                let child_key = key.child(octant & 1, (octant >> 1) & 1, (octant >> 2) & 1);
+                to_process.push((child_key, child_indices));
+            }
+        }
+        nodes
+    }
+
+    /// Writes the LAS 1.4 header
+    fn write_header(
+        &mut self,
+        offset_to_points: u32,
+        num_points: u64,
+        num_points_by_return: &[u64; 15],
+        min: [f64; 3],
+        max: [f64; 3],
+        start_of_evlrs: u64,
+    ) -> crate::Result<()> {
+        let mut header = LasHeader {
+            global_encoding: 0b1_0000, // WKT
+            minor: 4,
+            header_size: HEADER_SIZE,
+            offset_to_points,
+            num_vlrs: 2,
+            point_format_id: self.point_format_id | 0x80,
+            point_size: self.vlr.items_size() as u16,
+            scales: self.scales,
+            offsets: self.offsets,
+            mins: min,
+            maxs: max,
+            start_of_first_evlr: start_of_evlrs,
+            num_evlrs: 1,
+            ..Default::default()
+        };
+        header.set_point_counts(num_points, *num_points_by_return)?;
+        header.write_to(&mut self.dest)
+    }
+}
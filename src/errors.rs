@@ -23,6 +23,74 @@ pub enum LasZipError {
     /// The chunk table could not be found in the file
     /// and it is required for the operation.
     MissingChunkTable,
+    /// The index of the point to seek to is past the last point
+    PointIndexOutOfBounds {
+        /// Index of the point that was requested
+        requested: u64,
+        /// Total number of points
+        total: u64,
+    },
+    /// A VLR required for the operation could not be found
+    MissingVlr {
+        /// User id of the VLR
+        user_id: &'static str,
+        /// Record id of the VLR
+        record_id: u16,
+    },
+    /// The index of the chunk is past the last chunk
+    ChunkIndexOutOfBounds {
+        /// Index of the chunk that was requested
+        requested: usize,
+        /// Total number of chunks
+        total: usize,
+    },
+    /// The LazVlr of a source to merge is not compatible with
+    /// the one of the first source (they do not have the same items).
+    IncompatibleLazVlr {
+        /// Index of the incompatible source
+        index: usize,
+    },
+    /// The file does not start with the `LASF` signature
+    InvalidFileSignature([u8; 4]),
+    /// The version of the LAS file is not one of 1.0 to 1.4
+    UnsupportedLasVersion {
+        /// Major version of the file
+        major: u8,
+        /// Minor version of the file
+        minor: u8,
+    },
+    /// The header size is smaller than the size of the header of its LAS version
+    InvalidHeaderSize {
+        /// Size of the header in the file
+        header_size: u16,
+        /// Size of the header of the LAS version
+        expected: u16,
+    },
+    /// The offset to the points is inside the header
+    InvalidOffsetToPoints {
+        /// Offset to the points in the file
+        offset_to_points: u32,
+        /// Size of the header in the file
+        header_size: u16,
+    },
+    /// The data type of an extra bytes descriptor is not one of 0 to 30
+    UnknownExtraBytesDataType(u8),
+    /// A LasZip VLR is required for the operation but could not be found
+    MissingLasZipVlr,
+    /// The point format has the compressed bit set but the file has no LasZip VLR
+    CompressedWithoutLasZipVlr {
+        /// Point format id of the header, with the compressed bit
+        point_format_id: u8,
+    },
+    /// The point size is smaller than the size of the point format
+    PointSizeTooSmall {
+        /// Point format id
+        point_format_id: u8,
+        /// Point size of the header
+        point_size: u16,
+        /// Size of the points of the point format
+        minimum: u16,
+    },
 }
 
 impl From<std::io::Error> for LasZipError {
@@ -52,6 +120,68 @@ impl fmt::Display for LasZipError {
                 write!(f, "Point format {} is not supported", id)
             }
             LasZipError::MissingChunkTable => write!(f, "The chunk table could not be found"),
+            LasZipError::PointIndexOutOfBounds { requested, total } => write!(
+                f,
+                "Point index {} is out of bounds, there are {} points",
+                requested, total
+            ),
+            LasZipError::MissingVlr { user_id, record_id } => write!(
+                f,
+                "The VLR with user id '{}' and record id {} could not be found",
+                user_id, record_id
+            ),
+            LasZipError::ChunkIndexOutOfBounds { requested, total } => write!(
+                f,
+                "Chunk index {} is out of bounds, there are {} chunks",
+                requested, total
+            ),
+            LasZipError::IncompatibleLazVlr { index } => write!(
+                f,
+                "The LazVlr of source {} is not compatible with the one of the first source",
+                index
+            ),
+            LasZipError::InvalidFileSignature(signature) => write!(
+                f,
+                "Invalid file signature '{}', expected 'LASF'",
+                String::from_utf8_lossy(signature)
+            ),
+            LasZipError::UnsupportedLasVersion { major, minor } => {
+                write!(f, "LAS version {}.{} is not supported", major, minor)
+            }
+            LasZipError::InvalidHeaderSize {
+                header_size,
+                expected,
+            } => write!(
+                f,
+                "The header size {} is smaller than the size of the header of its version ({})",
+                header_size, expected
+            ),
+            LasZipError::InvalidOffsetToPoints {
+                offset_to_points,
+                header_size,
+            } => write!(
+                f,
+                "The offset to the points {} is smaller than the header size {}",
+                offset_to_points, header_size
+            ),
+            LasZipError::UnknownExtraBytesDataType(data_type) => {
+                write!(f, "Extra bytes data type {} is not known", data_type)
+            }
+            LasZipError::MissingLasZipVlr => write!(f, "The LasZip VLR could not be found"),
+            LasZipError::CompressedWithoutLasZipVlr { point_format_id } => write!(
+                f,
+                "The point format {} is compressed but there is no LasZip VLR",
+                point_format_id
+            ),
+            LasZipError::PointSizeTooSmall {
+                point_format_id,
+                point_size,
+                minimum,
+            } => write!(
+                f,
+                "The point size {} is smaller than the size of point format {} ({})",
+                point_size, point_format_id, minimum
+            ),
         }
     }
 }
@@ -0,0 +1,202 @@
+//! Defines the columnar (structure of arrays) representation of points
+//! that decompressors can fill straight from their field decompressors.
+
+use crate::las::gps::GpsTime;
+use crate::las::point0::LasPoint0;
+use crate::las::point6::LasPoint6;
+use crate::las::rgb::RGB;
+use crate::laszip::{LazItem, LazItemType};
+use crate::packers::Packable;
+
+/// Points stored as one column per dimension.
+///
+/// Decompressors append the points they decompress at the end of each column,
+/// the columns for dimensions that the point format does not have are left untouched.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct PointColumns {
+    pub x: Vec<i32>,
+    pub y: Vec<i32>,
+    pub z: Vec<i32>,
+    pub intensity: Vec<u16>,
+    /// For point formats 0 to 5 only the 5 bits of the class are kept,
+    /// the synthetic, key-point and withheld flags are not.
+    pub classification: Vec<u8>,
+    pub gps_time: Vec<f64>,
+    pub rgb: Vec<RGB>,
+    pub nir: Vec<u16>,
+    /// The extra bytes of all the points one after the other,
+    /// each point has `num_extra_bytes` bytes.
+    pub extra_bytes: Vec<u8>,
+    pub num_extra_bytes: usize,
+}
+
+impl PointColumns {
+    /// Returns the number of points in the columns.
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+
+    /// Removes all the points, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.x.clear();
+        self.y.clear();
+        self.z.clear();
+        self.intensity.clear();
+        self.classification.clear();
+        self.gps_time.clear();
+        self.rgb.clear();
+        self.nir.clear();
+        self.extra_bytes.clear();
+    }
+
+    pub(crate) fn reserve(&mut self, layout: &ColumnsLayout, additional: usize) {
+        self.x.reserve(additional);
+        self.y.reserve(additional);
+        self.z.reserve(additional);
+        self.intensity.reserve(additional);
+        self.classification.reserve(additional);
+        if layout.gps_time.is_some() {
+            self.gps_time.reserve(additional);
+        }
+        if layout.rgb.is_some() {
+            self.rgb.reserve(additional);
+        }
+        if layout.nir.is_some() {
+            self.nir.reserve(additional);
+        }
+        if let Some((_, count)) = layout.extra_bytes {
+            self.extra_bytes.reserve(additional * count);
+        }
+    }
+
+    /// Appends the fields of a point of the formats 0 to 5.
+    pub(crate) fn push_point0<P: LasPoint0>(&mut self, point: &P) {
+        self.x.push(point.x());
+        self.y.push(point.y());
+        self.z.push(point.z());
+        self.intensity.push(point.intensity());
+        self.classification
+            .push(point.classification() & 0b0001_1111);
+    }
+
+    /// Appends the fields of a point of the formats 6 to 10, GPS time included.
+    pub(crate) fn push_point6<P: LasPoint6>(&mut self, point: &P) {
+        self.x.push(point.x());
+        self.y.push(point.y());
+        self.z.push(point.z());
+        self.intensity.push(point.intensity());
+        self.classification.push(point.classification());
+        self.gps_time.push(point.gps_time());
+    }
+
+    /// Appends the point record to the columns.
+    ///
+    /// Used for the points that are not compressed, like the first point of a chunk.
+    pub(crate) fn push_point(&mut self, layout: &ColumnsLayout, point: &[u8]) {
+        let p = &point[layout.point..];
+        self.x.push(i32::unpack_from(&p[0..4]));
+        self.y.push(i32::unpack_from(&p[4..8]));
+        self.z.push(i32::unpack_from(&p[8..12]));
+        self.intensity.push(u16::unpack_from(&p[12..14]));
+        if layout.is_extended_point {
+            self.classification.push(p[16]);
+        } else {
+            self.classification.push(p[15] & 0b0001_1111);
+        }
+        if let Some(offset) = layout.gps_time {
+            self.gps_time
+                .push(GpsTime::unpack_from(&point[offset..offset + 8]).into());
+        }
+        if let Some(offset) = layout.rgb {
+            self.rgb.push(RGB::unpack_from(&point[offset..offset + 6]));
+        }
+        if let Some(offset) = layout.nir {
+            self.nir.push(u16::unpack_from(&point[offset..offset + 2]));
+        }
+        if let Some((offset, count)) = layout.extra_bytes {
+            self.extra_bytes
+                .extend_from_slice(&point[offset..offset + count]);
+        }
+    }
+
+    /// Appends all the point records of `points` to the columns.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn extend_from_points(&mut self, layout: &ColumnsLayout, points: &[u8]) {
+        for point in points.chunks_exact(layout.point_size) {
+            self.push_point(layout, point);
+        }
+    }
+
+    /// Moves all the points of `other` at the end of the columns.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn append(&mut self, other: &mut PointColumns) {
+        self.x.append(&mut other.x);
+        self.y.append(&mut other.y);
+        self.z.append(&mut other.z);
+        self.intensity.append(&mut other.intensity);
+        self.classification.append(&mut other.classification);
+        self.gps_time.append(&mut other.gps_time);
+        self.rgb.append(&mut other.rgb);
+        self.nir.append(&mut other.nir);
+        self.extra_bytes.append(&mut other.extra_bytes);
+    }
+}
+
+/// Offsets of the dimensions in a point record described by LazItems.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ColumnsLayout {
+    pub(crate) point_size: usize,
+    // offset of the Point10 or Point14 item
+    point: usize,
+    is_extended_point: bool,
+    gps_time: Option<usize>,
+    rgb: Option<usize>,
+    nir: Option<usize>,
+    extra_bytes: Option<(usize, usize)>,
+}
+
+impl ColumnsLayout {
+    pub(crate) fn from_laz_items(items: &[LazItem]) -> Self {
+        let mut layout = Self {
+            point_size: 0,
+            point: 0,
+            is_extended_point: false,
+            gps_time: None,
+            rgb: None,
+            nir: None,
+            extra_bytes: None,
+        };
+        let mut offset = 0usize;
+        for item in items {
+            match item.item_type() {
+                LazItemType::Point10 => layout.point = offset,
+                LazItemType::Point14 => {
+                    layout.point = offset;
+                    layout.is_extended_point = true;
+                    layout.gps_time = Some(offset + 22);
+                }
+                LazItemType::GpsTime => layout.gps_time = Some(offset),
+                LazItemType::RGB12 | LazItemType::RGB14 => layout.rgb = Some(offset),
+                LazItemType::RGBNIR14 => {
+                    layout.rgb = Some(offset);
+                    layout.nir = Some(offset + 6);
+                }
+                LazItemType::Byte(count) | LazItemType::Byte14(count) => {
+                    layout.extra_bytes = Some((offset, usize::from(count)))
+                }
+                LazItemType::WavePacket13 | LazItemType::WavePacket14 => {}
+            }
+            offset += item.size() as usize;
+        }
+        layout.point_size = offset;
+        layout
+    }
+
+    pub(crate) fn num_extra_bytes(&self) -> usize {
+        self.extra_bytes.map_or(0, |(_, count)| count)
+    }
+}
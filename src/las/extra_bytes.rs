@@ -71,6 +71,7 @@ pub mod v1 {
 
     use crate::decoders::ArithmeticDecoder;
     use crate::encoders::ArithmeticEncoder;
+    use crate::las::PointColumns;
     use crate::models::{ArithmeticModel, ArithmeticModelBuilder};
     use crate::record::{FieldCompressor, FieldDecompressor};
 
@@ -166,6 +167,10 @@ pub mod v1 {
             buf.copy_from_slice(&self.last_bytes);
             Ok(())
         }
+
+        fn push_last_to_columns(&self, columns: &mut PointColumns) {
+            columns.extra_bytes.extend_from_slice(&self.last_bytes);
+        }
     }
 }
 
@@ -181,7 +186,9 @@ pub mod v3 {
     use crate::decoders::ArithmeticDecoder;
     use crate::encoders::ArithmeticEncoder;
     use crate::las::extra_bytes::ExtraBytes;
+    use crate::las::point6::DecompressionSelector;
     use crate::las::utils::{copy_bytes_into_decoder, copy_encoder_content_to};
+    use crate::las::PointColumns;
     use crate::models::{ArithmeticModel, ArithmeticModelBuilder};
     use crate::record::{LayeredFieldCompressor, LayeredFieldDecompressor};
 
@@ -206,25 +213,54 @@ pub mod v3 {
         decoders: Vec<ArithmeticDecoder<Cursor<Vec<u8>>>>,
         num_bytes_per_layer: Vec<u32>,
         has_byte_changed: Vec<bool>,
+        requested_bytes: Vec<bool>,
         contexts: Vec<ExtraBytesContext>,
         // Last & contexts are separated for the same reasons as in v3::RGB
         last_bytes: Vec<ExtraBytes>,
+        // The bytes of the last decompressed point, with the ones not requested set to 0
+        last_point_bytes: Vec<u8>,
         num_extra_bytes: usize,
         last_context_used: usize,
+        // true for the version 4, see crate::las::v4
+        last_item_follows_context: bool,
     }
 
     impl LasExtraByteDecompressor {
         pub fn new(count: usize) -> Self {
+            Self::selective(count, DecompressionSelector::decompress_all())
+        }
+
+        pub fn selective(count: usize, selector: DecompressionSelector) -> Self {
             Self {
                 decoders: (0..count)
                     .map(|_i| ArithmeticDecoder::new(Cursor::new(Vec::<u8>::new())))
                     .collect(),
                 num_bytes_per_layer: vec![0; count],
                 has_byte_changed: vec![false; count],
+                requested_bytes: (0..count)
+                    .map(|i| selector.extra_byte_requested(i))
+                    .collect(),
                 contexts: (0..4).map(|_i| ExtraBytesContext::new(count)).collect(),
                 last_bytes: (0..4).map(|_| ExtraBytes::new(count)).collect(),
+                last_point_bytes: vec![0u8; count],
                 num_extra_bytes: count,
                 last_context_used: 0,
+                last_item_follows_context: false,
+            }
+        }
+
+        pub(super) fn for_version_4(count: usize, selector: DecompressionSelector) -> Self {
+            Self {
+                last_item_follows_context: true,
+                ..Self::selective(count, selector)
+            }
+        }
+
+        fn zero_unrequested_bytes(&self, bytes: &mut [u8]) {
+            for (byte, requested) in bytes.iter_mut().zip(&self.requested_bytes) {
+                if !requested {
+                    *byte = 0;
+                }
             }
         }
     }
@@ -249,6 +285,7 @@ pub mod v3 {
 
             self.last_context_used = *context;
             self.contexts[*context].unused = false;
+            self.zero_unrequested_bytes(first_point);
             Ok(())
         }
 
@@ -270,6 +307,8 @@ pub mod v3 {
                         .bytes
                         .copy_from_slice(&last_bytes.bytes);
                     last_bytes_ptr = &mut self.last_bytes[*context] as &mut _;
+                } else if self.last_item_follows_context {
+                    last_bytes_ptr = &mut self.last_bytes[*context] as &mut _;
                 }
             }
 
@@ -284,6 +323,8 @@ pub mod v3 {
                 }
             }
             current_point.copy_from_slice(&last_bytes.bytes);
+            self.zero_unrequested_bytes(current_point);
+            self.last_point_bytes.copy_from_slice(current_point);
             Ok(())
         }
 
@@ -297,7 +338,7 @@ pub mod v3 {
         fn read_layers(&mut self, src: &mut R) -> std::io::Result<()> {
             for i in 0..self.num_extra_bytes {
                 self.has_byte_changed[i] = copy_bytes_into_decoder(
-                    true, // TODO requested bytes
+                    self.requested_bytes[i],
                     self.num_bytes_per_layer[i] as usize,
                     &mut self.decoders[i],
                     src,
@@ -305,6 +346,12 @@ pub mod v3 {
             }
             Ok(())
         }
+
+        fn push_last_to_columns(&self, columns: &mut PointColumns) {
+            columns
+                .extra_bytes
+                .extend_from_slice(&self.last_point_bytes);
+        }
     }
 
     pub struct LasExtraByteCompressor {
@@ -316,6 +363,8 @@ pub mod v3 {
         last_bytes: Vec<ExtraBytes>,
         num_extra_bytes: usize,
         last_context_used: usize,
+        // Same as in the decompressor
+        last_item_follows_context: bool,
     }
 
     impl LasExtraByteCompressor {
@@ -329,6 +378,14 @@ pub mod v3 {
                 last_bytes: (0..4).map(|_i| ExtraBytes::new(count)).collect(),
                 num_extra_bytes: count,
                 last_context_used: 0,
+                last_item_follows_context: false,
+            }
+        }
+
+        pub(super) fn for_version_4(count: usize) -> Self {
+            Self {
+                last_item_follows_context: true,
+                ..Self::new(count)
             }
         }
     }
@@ -373,6 +430,8 @@ pub mod v3 {
                         .bytes
                         .copy_from_slice(&last_bytes.bytes);
                     last_bytes_ptr = &mut self.last_bytes[*context] as &mut _;
+                } else if self.last_item_follows_context {
+                    last_bytes_ptr = &mut self.last_bytes[*context] as &mut _;
                 }
             }
 
@@ -407,3 +466,34 @@ pub mod v3 {
         }
     }
 }
+
+pub mod v4 {
+    //! Contains the implementation for the Version 4 of the ExtraBytes Compression / Decompression
+    use crate::las::point6::DecompressionSelector;
+
+    use super::v3;
+
+    pub struct LasExtraByteDecompressor(v3::LasExtraByteDecompressor);
+
+    impl LasExtraByteDecompressor {
+        pub fn new(count: usize) -> Self {
+            Self::selective(count, DecompressionSelector::decompress_all())
+        }
+
+        pub fn selective(count: usize, selector: DecompressionSelector) -> Self {
+            Self(v3::LasExtraByteDecompressor::for_version_4(count, selector))
+        }
+    }
+
+    forward_layered_field_decompressor_impl!(LasExtraByteDecompressor);
+
+    pub struct LasExtraByteCompressor(v3::LasExtraByteCompressor);
+
+    impl LasExtraByteCompressor {
+        pub fn new(count: usize) -> Self {
+            Self(v3::LasExtraByteCompressor::for_version_4(count))
+        }
+    }
+
+    forward_layered_field_compressor_impl!(LasExtraByteCompressor);
+}
@@ -4,14 +4,18 @@
 
 #![allow(dead_code)]
 
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom, Write};
 
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 
-use crate::laszip::{LasZipDecompressor, LazVlr};
+#[cfg(feature = "parallel")]
+use crate::laszip::parallel::ParLasZipCompressor;
+use crate::laszip::{LasZipCompressor, LasZipDecompressor, LazVlr};
+use crate::LasZipError;
 
 /// LAS header with only the minimum information
 /// to be able to read points contained in a LAS file.
+#[deprecated(since = "0.7.0", note = "Please use LasHeader")]
 #[derive(Debug)]
 pub struct QuickHeader {
     pub major: u8,
@@ -24,6 +28,7 @@ pub struct QuickHeader {
     pub header_size: u16,
 }
 
+#[allow(deprecated)]
 impl QuickHeader {
     pub fn read_from<R: Read + Seek>(src: &mut R) -> std::io::Result<Self> {
         src.seek(SeekFrom::Start(24))?;
@@ -56,19 +61,366 @@ impl QuickHeader {
         })
     }
 
+    /// Returns the number of extra bytes of the points,
+    /// 0 if the point format is not known or the point size is too small for it.
     pub fn num_extra_bytes(&self) -> u16 {
-        let point_size_wo_extra = match self.point_format_id {
-            0 => 20,
-            1 => 28,
-            2 => 26,
-            3 => 34,
-            6 => 30,
-            7 => 36,
-            8 => 38,
-            _ => panic!("Unknown fmt id"),
+        point_size_of_format(self.point_format_id).map_or(0, |point_size_wo_extra| {
+            self.point_size.saturating_sub(point_size_wo_extra)
+        })
+    }
+}
+
+/// The header of a LAS file, for all the versions from 1.0 to 1.4.
+///
+/// Fields that do not exist in the version of the header are 0 (e.g. the `num_evlrs` of a 1.2 header).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LasHeader {
+    pub file_signature: [u8; 4],
+    pub file_source_id: u16,
+    pub global_encoding: u16,
+    /// Project ID, the GUID of the project
+    pub guid: [u8; 16],
+    pub major: u8,
+    pub minor: u8,
+    pub system_identifier: [u8; 32],
+    pub generating_software: [u8; 32],
+    /// Day of the year the file was created, starting at 1
+    pub creation_day: u16,
+    pub creation_year: u16,
+    pub header_size: u16,
+    pub offset_to_points: u32,
+    pub num_vlrs: u32,
+    /// The point format id, as stored, with the compression bit if the points are compressed
+    pub point_format_id: u8,
+    pub point_size: u16,
+    /// Number of points on 32 bits, 0 for point formats >= 6 or if the count does not fit
+    pub legacy_num_points: u32,
+    /// Number of points by return on 32 bits, the same rules as for `legacy_num_points` apply
+    pub legacy_points_by_return: [u32; 5],
+    pub scales: [f64; 3],
+    pub offsets: [f64; 3],
+    pub mins: [f64; 3],
+    pub maxs: [f64; 3],
+    /// Offset to the start of the waveform data packet record (since LAS 1.3)
+    pub start_of_waveform: u64,
+    /// Offset to the first EVLR (since LAS 1.4)
+    pub start_of_first_evlr: u64,
+    /// Number of EVLRs (since LAS 1.4)
+    pub num_evlrs: u32,
+    /// Number of points, from the legacy field for versions < 1.4
+    pub num_points: u64,
+    /// Number of points by return, from the legacy fields for versions < 1.4
+    pub points_by_return: [u64; 15],
+}
+
+impl Default for LasHeader {
+    /// Returns a LAS 1.2 header, for point format 0, without VLRs nor points
+    fn default() -> Self {
+        let mut generating_software = [0u8; 32];
+        generating_software[..6].copy_from_slice(b"laz-rs");
+        Self {
+            file_signature: LasHeader::FILE_SIGNATURE,
+            file_source_id: 0,
+            global_encoding: 0,
+            guid: [0u8; 16],
+            major: 1,
+            minor: 2,
+            system_identifier: [0u8; 32],
+            generating_software,
+            creation_day: 0,
+            creation_year: 0,
+            header_size: LasHeader::size_of_version(2),
+            offset_to_points: u32::from(LasHeader::size_of_version(2)),
+            num_vlrs: 0,
+            point_format_id: 0,
+            point_size: 20,
+            legacy_num_points: 0,
+            legacy_points_by_return: [0; 5],
+            scales: [0.001; 3],
+            offsets: [0.0; 3],
+            mins: [0.0; 3],
+            maxs: [0.0; 3],
+            start_of_waveform: 0,
+            start_of_first_evlr: 0,
+            num_evlrs: 0,
+            num_points: 0,
+            points_by_return: [0; 15],
+        }
+    }
+}
+
+impl LasHeader {
+    pub const FILE_SIGNATURE: [u8; 4] = *b"LASF";
+
+    /// Returns the size of the header of the LAS 1.`minor` version.
+    pub fn size_of_version(minor: u8) -> u16 {
+        match minor {
+            0..=2 => 227,
+            3 => 235,
+            _ => 375,
+        }
+    }
+
+    /// Reads the header, the source must be at the start of the header.
+    ///
+    /// After the call, the source is positioned at the end of the header (`header_size`).
+    pub fn read_from<R: Read + Seek>(src: &mut R) -> crate::Result<Self> {
+        let mut file_signature = [0u8; 4];
+        src.read_exact(&mut file_signature)?;
+        if file_signature != Self::FILE_SIGNATURE {
+            return Err(LasZipError::InvalidFileSignature(file_signature));
+        }
+        let file_source_id = src.read_u16::<LittleEndian>()?;
+        let global_encoding = src.read_u16::<LittleEndian>()?;
+        let mut guid = [0u8; 16];
+        src.read_exact(&mut guid)?;
+        let major = src.read_u8()?;
+        let minor = src.read_u8()?;
+        if major != 1 || minor > 4 {
+            return Err(LasZipError::UnsupportedLasVersion { major, minor });
+        }
+        let mut system_identifier = [0u8; 32];
+        src.read_exact(&mut system_identifier)?;
+        let mut generating_software = [0u8; 32];
+        src.read_exact(&mut generating_software)?;
+        let creation_day = src.read_u16::<LittleEndian>()?;
+        let creation_year = src.read_u16::<LittleEndian>()?;
+        let header_size = src.read_u16::<LittleEndian>()?;
+        if header_size < Self::size_of_version(minor) {
+            return Err(LasZipError::InvalidHeaderSize {
+                header_size,
+                expected: Self::size_of_version(minor),
+            });
+        }
+        let offset_to_points = src.read_u32::<LittleEndian>()?;
+        if offset_to_points < u32::from(header_size) {
+            return Err(LasZipError::InvalidOffsetToPoints {
+                offset_to_points,
+                header_size,
+            });
+        }
+        let num_vlrs = src.read_u32::<LittleEndian>()?;
+        let point_format_id = src.read_u8()?;
+        let point_size = src.read_u16::<LittleEndian>()?;
+        let legacy_num_points = src.read_u32::<LittleEndian>()?;
+        let mut legacy_points_by_return = [0u32; 5];
+        src.read_u32_into::<LittleEndian>(&mut legacy_points_by_return)?;
+        let mut scales = [0f64; 3];
+        src.read_f64_into::<LittleEndian>(&mut scales)?;
+        let mut offsets = [0f64; 3];
+        src.read_f64_into::<LittleEndian>(&mut offsets)?;
+        let mut mins = [0f64; 3];
+        let mut maxs = [0f64; 3];
+        for i in 0..3 {
+            maxs[i] = src.read_f64::<LittleEndian>()?;
+            mins[i] = src.read_f64::<LittleEndian>()?;
+        }
+
+        let start_of_waveform = if minor >= 3 {
+            src.read_u64::<LittleEndian>()?
+        } else {
+            0
+        };
+        let mut num_points = u64::from(legacy_num_points);
+        let mut points_by_return = [0u64; 15];
+        for (count, legacy_count) in points_by_return.iter_mut().zip(&legacy_points_by_return) {
+            *count = u64::from(*legacy_count);
+        }
+        let (start_of_first_evlr, num_evlrs) = if minor >= 4 {
+            let start_of_first_evlr = src.read_u64::<LittleEndian>()?;
+            let num_evlrs = src.read_u32::<LittleEndian>()?;
+            num_points = src.read_u64::<LittleEndian>()?;
+            src.read_u64_into::<LittleEndian>(&mut points_by_return)?;
+            (start_of_first_evlr, num_evlrs)
+        } else {
+            (0, 0)
         };
 
-        self.point_size - point_size_wo_extra
+        let num_bytes_left = header_size - Self::size_of_version(minor);
+        src.seek(SeekFrom::Current(i64::from(num_bytes_left)))?;
+        Ok(Self {
+            file_signature,
+            file_source_id,
+            global_encoding,
+            guid,
+            major,
+            minor,
+            system_identifier,
+            generating_software,
+            creation_day,
+            creation_year,
+            header_size,
+            offset_to_points,
+            num_vlrs,
+            point_format_id,
+            point_size,
+            legacy_num_points,
+            legacy_points_by_return,
+            scales,
+            offsets,
+            mins,
+            maxs,
+            start_of_waveform,
+            start_of_first_evlr,
+            num_evlrs,
+            num_points,
+            points_by_return,
+        })
+    }
+
+    /// Writes the header, using the fields that exist in its version.
+    ///
+    /// If the `header_size` is bigger than the size of the version,
+    /// the remaining bytes are written as zeros.
+    pub fn write_to<W: Write>(&self, dst: &mut W) -> crate::Result<()> {
+        if self.file_signature != Self::FILE_SIGNATURE {
+            return Err(LasZipError::InvalidFileSignature(self.file_signature));
+        }
+        if self.major != 1 || self.minor > 4 {
+            return Err(LasZipError::UnsupportedLasVersion {
+                major: self.major,
+                minor: self.minor,
+            });
+        }
+        if self.header_size < Self::size_of_version(self.minor) {
+            return Err(LasZipError::InvalidHeaderSize {
+                header_size: self.header_size,
+                expected: Self::size_of_version(self.minor),
+            });
+        }
+        if self.offset_to_points < u32::from(self.header_size) {
+            return Err(LasZipError::InvalidOffsetToPoints {
+                offset_to_points: self.offset_to_points,
+                header_size: self.header_size,
+            });
+        }
+        dst.write_all(&self.file_signature)?;
+        dst.write_u16::<LittleEndian>(self.file_source_id)?;
+        dst.write_u16::<LittleEndian>(self.global_encoding)?;
+        dst.write_all(&self.guid)?;
+        dst.write_u8(self.major)?;
+        dst.write_u8(self.minor)?;
+        dst.write_all(&self.system_identifier)?;
+        dst.write_all(&self.generating_software)?;
+        dst.write_u16::<LittleEndian>(self.creation_day)?;
+        dst.write_u16::<LittleEndian>(self.creation_year)?;
+        dst.write_u16::<LittleEndian>(self.header_size)?;
+        dst.write_u32::<LittleEndian>(self.offset_to_points)?;
+        dst.write_u32::<LittleEndian>(self.num_vlrs)?;
+        dst.write_u8(self.point_format_id)?;
+        dst.write_u16::<LittleEndian>(self.point_size)?;
+        dst.write_u32::<LittleEndian>(self.legacy_num_points)?;
+        for count in &self.legacy_points_by_return {
+            dst.write_u32::<LittleEndian>(*count)?;
+        }
+        for scale in &self.scales {
+            dst.write_f64::<LittleEndian>(*scale)?;
+        }
+        for offset in &self.offsets {
+            dst.write_f64::<LittleEndian>(*offset)?;
+        }
+        for i in 0..3 {
+            dst.write_f64::<LittleEndian>(self.maxs[i])?;
+            dst.write_f64::<LittleEndian>(self.mins[i])?;
+        }
+        if self.minor >= 3 {
+            dst.write_u64::<LittleEndian>(self.start_of_waveform)?;
+        }
+        if self.minor >= 4 {
+            dst.write_u64::<LittleEndian>(self.start_of_first_evlr)?;
+            dst.write_u32::<LittleEndian>(self.num_evlrs)?;
+            dst.write_u64::<LittleEndian>(self.num_points)?;
+            for count in &self.points_by_return {
+                dst.write_u64::<LittleEndian>(*count)?;
+            }
+        }
+        let num_bytes_left = self.header_size - Self::size_of_version(self.minor);
+        dst.write_all(&vec![0u8; usize::from(num_bytes_left)])?;
+        Ok(())
+    }
+
+    /// Sets the number of points and the number of points by return,
+    /// the legacy fields are set following the rules of LAS 1.4:
+    /// they are 0 for point formats >= 6 and when the counts do not fit on 32 bits.
+    ///
+    /// For versions < 1.4, only the legacy fields exist: the counts of the returns
+    /// past the 5th are not stored and an error is returned if `num_points` does not fit on 32 bits.
+    pub fn set_point_counts(
+        &mut self,
+        num_points: u64,
+        points_by_return: [u64; 15],
+    ) -> crate::Result<()> {
+        let format_id = point_format_id_compressed_to_uncompressd(self.point_format_id);
+        let fits_legacy = if self.minor < 4 {
+            if num_points > u64::from(u32::MAX) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!(
+                        "LAS 1.{} cannot store more than {} points",
+                        self.minor,
+                        u32::MAX
+                    ),
+                )
+                .into());
+            }
+            true
+        } else {
+            format_id < 6
+                && num_points <= u64::from(u32::MAX)
+                && points_by_return[5..].iter().all(|&count| count == 0)
+        };
+        self.num_points = num_points;
+        self.points_by_return = points_by_return;
+        if fits_legacy {
+            self.legacy_num_points = num_points as u32;
+            for (legacy_count, count) in self
+                .legacy_points_by_return
+                .iter_mut()
+                .zip(&points_by_return)
+            {
+                *legacy_count = *count as u32;
+            }
+        } else {
+            self.legacy_num_points = 0;
+            self.legacy_points_by_return = [0; 5];
+        }
+        Ok(())
+    }
+
+    /// Returns the system identifier, without the trailing nul bytes.
+    ///
+    /// An empty string is returned if it is not valid utf8.
+    pub fn system_identifier(&self) -> &str {
+        std::str::from_utf8(&self.system_identifier)
+            .unwrap_or("")
+            .trim_end_matches('\0')
+    }
+
+    /// Returns the generating software, without the trailing nul bytes.
+    ///
+    /// An empty string is returned if it is not valid utf8.
+    pub fn generating_software(&self) -> &str {
+        std::str::from_utf8(&self.generating_software)
+            .unwrap_or("")
+            .trim_end_matches('\0')
+    }
+
+    /// Returns the number of extra bytes of the points.
+    ///
+    /// Fails with [LasZipError::UnsupportedPointFormat] if the point format is not known
+    /// and [LasZipError::PointSizeTooSmall] if the point size is smaller than the
+    /// size of the point format.
+    pub fn num_extra_bytes(&self) -> crate::Result<u16> {
+        let point_format_id = point_format_id_compressed_to_uncompressd(self.point_format_id);
+        let minimum = point_size_of_format(point_format_id)
+            .ok_or(LasZipError::UnsupportedPointFormat(point_format_id))?;
+        self.point_size
+            .checked_sub(minimum)
+            .ok_or(LasZipError::PointSizeTooSmall {
+                point_format_id,
+                point_size: self.point_size,
+                minimum,
+            })
     }
 }
 
@@ -82,6 +434,28 @@ pub struct Vlr {
 }
 
 impl Vlr {
+    /// Size in bytes of the header of a VLR
+    pub const HEADER_SIZE: usize = 54;
+
+    /// Creates a new VLR
+    ///
+    /// # Panics
+    ///
+    /// If the `user_id` is longer than 16 bytes, the `description` longer than 32 bytes
+    /// or the `data` longer than what a u16 can represent.
+    pub fn new(user_id: &str, record_id: u16, description: &str, data: Vec<u8>) -> Self {
+        assert!(data.len() <= usize::from(u16::MAX), "VLR data is too big");
+        let mut vlr = Self {
+            user_id: [0u8; 16],
+            record_id,
+            description: [0u8; 32],
+            data,
+        };
+        vlr.user_id[..user_id.len()].copy_from_slice(user_id.as_bytes());
+        vlr.description[..description.len()].copy_from_slice(description.as_bytes());
+        vlr
+    }
+
     pub fn read_from<R: Read>(src: &mut R) -> std::io::Result<Self> {
         src.read_u16::<LittleEndian>()?; // reserved
         let mut user_id = [0u8; 16];
@@ -104,28 +478,620 @@ impl Vlr {
             data,
         })
     }
+
+    /// Returns the user id, without the trailing nul bytes.
+    ///
+    /// An empty string is returned if the user id is not valid utf8.
+    pub fn user_id(&self) -> &str {
+        std::str::from_utf8(&self.user_id)
+            .unwrap_or("")
+            .trim_end_matches('\0')
+    }
+
+    pub fn record_id(&self) -> u16 {
+        self.record_id
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns whether this is the VLR holding the [LazVlr].
+    pub fn is_laszip_vlr(&self) -> bool {
+        self.user_id() == LazVlr::USER_ID && self.record_id == LazVlr::RECORD_ID
+    }
+
+    pub fn write_to<W: Write>(&self, dst: &mut W) -> std::io::Result<()> {
+        dst.write_u16::<LittleEndian>(0)?; // reserved
+        dst.write_all(&self.user_id)?;
+        dst.write_u16::<LittleEndian>(self.record_id)?;
+        dst.write_u16::<LittleEndian>(self.data.len() as u16)?;
+        dst.write_all(&self.description)?;
+        dst.write_all(&self.data)
+    }
+}
+
+/// Extended VLR, they are stored after the points in LAS 1.4 files
+/// and their data can be larger than what a VLR can hold.
+#[derive(Debug)]
+pub struct Evlr {
+    user_id: [u8; 16],
+    record_id: u16,
+    description: [u8; 32],
+    data: Vec<u8>,
 }
 
-pub fn read_vlrs_and_get_laszip_vlr<R: Read>(src: &mut R, header: &QuickHeader) -> Option<LazVlr> {
+impl Evlr {
+    /// Size in bytes of the header of an EVLR
+    pub const HEADER_SIZE: usize = 60;
+
+    /// Creates a new EVLR
+    ///
+    /// # Panics
+    ///
+    /// If the `user_id` is longer than 16 bytes or the `description` longer than 32 bytes.
+    pub fn new(user_id: &str, record_id: u16, description: &str, data: Vec<u8>) -> Self {
+        let mut evlr = Self {
+            user_id: [0u8; 16],
+            record_id,
+            description: [0u8; 32],
+            data,
+        };
+        evlr.user_id[..user_id.len()].copy_from_slice(user_id.as_bytes());
+        evlr.description[..description.len()].copy_from_slice(description.as_bytes());
+        evlr
+    }
+
+    pub fn read_from<R: Read>(src: &mut R) -> std::io::Result<Self> {
+        src.read_u16::<LittleEndian>()?; // reserved
+        let mut user_id = [0u8; 16];
+        src.read_exact(&mut user_id)?;
+
+        let record_id = src.read_u16::<LittleEndian>()?;
+        let record_length = src.read_u64::<LittleEndian>()?;
+
+        let mut description = [0u8; 32];
+        src.read_exact(&mut description)?;
+
+        // The length is not trusted to allocate the data
+        let mut data = Vec::<u8>::new();
+        src.take(record_length).read_to_end(&mut data)?;
+        if (data.len() as u64) < record_length {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "The data of the EVLR is truncated",
+            ));
+        }
+
+        Ok(Self {
+            user_id,
+            record_id,
+            description,
+            data,
+        })
+    }
+
+    /// Returns the user id, without the trailing nul bytes.
+    ///
+    /// An empty string is returned if the user id is not valid utf8.
+    pub fn user_id(&self) -> &str {
+        std::str::from_utf8(&self.user_id)
+            .unwrap_or("")
+            .trim_end_matches('\0')
+    }
+
+    pub fn record_id(&self) -> u16 {
+        self.record_id
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn write_to<W: Write>(&self, dst: &mut W) -> std::io::Result<()> {
+        dst.write_u16::<LittleEndian>(0)?; // reserved
+        dst.write_all(&self.user_id)?;
+        dst.write_u16::<LittleEndian>(self.record_id)?;
+        dst.write_u64::<LittleEndian>(self.data.len() as u64)?;
+        dst.write_all(&self.description)?;
+        dst.write_all(&self.data)
+    }
+}
+
+/// Reads the EVLRs of the file described by the header.
+///
+/// The source is moved to the first EVLR, its position is the end of the last one after the call.
+pub fn read_evlrs<R: Read + Seek>(src: &mut R, header: &LasHeader) -> crate::Result<Vec<Evlr>> {
+    if header.num_evlrs == 0 {
+        return Ok(Vec::new());
+    }
+    src.seek(SeekFrom::Start(header.start_of_first_evlr))?;
+    let mut evlrs = Vec::with_capacity(header.num_evlrs.min(1024) as usize);
+    for _ in 0..header.num_evlrs {
+        evlrs.push(Evlr::read_from(src)?);
+    }
+    Ok(evlrs)
+}
+
+/// Reads all the VLRs of the file described by the header.
+///
+/// The source is moved to the first VLR, its position is the end of the last one after the call.
+pub fn read_vlrs<R: Read + Seek>(src: &mut R, header: &LasHeader) -> crate::Result<Vec<Vlr>> {
+    src.seek(SeekFrom::Start(u64::from(header.header_size)))?;
+    let mut vlrs = Vec::with_capacity(header.num_vlrs.min(1024) as usize);
+    for _ in 0..header.num_vlrs {
+        vlrs.push(Vlr::read_from(src)?);
+    }
+    Ok(vlrs)
+}
+
+/// Size in bytes of the points of the point formats, without extra bytes.
+pub(crate) fn point_size_of_format(point_format_id: u8) -> Option<u16> {
+    match point_format_id_compressed_to_uncompressd(point_format_id) {
+        0 => Some(20),
+        1 => Some(28),
+        2 => Some(26),
+        3 => Some(34),
+        4 => Some(57),
+        5 => Some(63),
+        6 => Some(30),
+        7 => Some(36),
+        8 => Some(38),
+        9 => Some(59),
+        10 => Some(67),
+        _ => None,
+    }
+}
+
+/// Type of the values of an extra bytes dimension
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ExtraBytesDataType {
+    /// Bytes without type, the value is the number of bytes
+    Undocumented(u8),
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    F32,
+    F64,
+}
+
+impl ExtraBytesDataType {
+    /// Returns the size in bytes of one value
+    pub fn size(&self) -> usize {
+        match self {
+            ExtraBytesDataType::Undocumented(size) => usize::from(*size),
+            ExtraBytesDataType::U8 | ExtraBytesDataType::I8 => 1,
+            ExtraBytesDataType::U16 | ExtraBytesDataType::I16 => 2,
+            ExtraBytesDataType::U32 | ExtraBytesDataType::I32 | ExtraBytesDataType::F32 => 4,
+            ExtraBytesDataType::U64 | ExtraBytesDataType::I64 | ExtraBytesDataType::F64 => 8,
+        }
+    }
+
+    /// Code of the type in the descriptor (for one element)
+    fn code(&self) -> u8 {
+        match self {
+            ExtraBytesDataType::Undocumented(_) => 0,
+            ExtraBytesDataType::U8 => 1,
+            ExtraBytesDataType::I8 => 2,
+            ExtraBytesDataType::U16 => 3,
+            ExtraBytesDataType::I16 => 4,
+            ExtraBytesDataType::U32 => 5,
+            ExtraBytesDataType::I32 => 6,
+            ExtraBytesDataType::U64 => 7,
+            ExtraBytesDataType::I64 => 8,
+            ExtraBytesDataType::F32 => 9,
+            ExtraBytesDataType::F64 => 10,
+        }
+    }
+
+    /// Reads the value at the start of `bytes`, `None` for undocumented bytes
+    fn read_value(&self, bytes: &[u8]) -> Option<f64> {
+        let mut src = bytes;
+        let value = match self {
+            ExtraBytesDataType::Undocumented(_) => return None,
+            ExtraBytesDataType::U8 => f64::from(src.read_u8().ok()?),
+            ExtraBytesDataType::I8 => f64::from(src.read_i8().ok()?),
+            ExtraBytesDataType::U16 => f64::from(src.read_u16::<LittleEndian>().ok()?),
+            ExtraBytesDataType::I16 => f64::from(src.read_i16::<LittleEndian>().ok()?),
+            ExtraBytesDataType::U32 => f64::from(src.read_u32::<LittleEndian>().ok()?),
+            ExtraBytesDataType::I32 => f64::from(src.read_i32::<LittleEndian>().ok()?),
+            ExtraBytesDataType::U64 => src.read_u64::<LittleEndian>().ok()? as f64,
+            ExtraBytesDataType::I64 => src.read_i64::<LittleEndian>().ok()? as f64,
+            ExtraBytesDataType::F32 => f64::from(src.read_f32::<LittleEndian>().ok()?),
+            ExtraBytesDataType::F64 => src.read_f64::<LittleEndian>().ok()?,
+        };
+        Some(value)
+    }
+
+    /// Reads the 8 bytes of a no_data, min or max field of the descriptor.
+    ///
+    /// They are stored as u64 for unsigned types, i64 for signed types and f64 for floats.
+    fn read_descriptor_value(&self, bytes: [u8; 8]) -> f64 {
+        match self {
+            ExtraBytesDataType::Undocumented(_)
+            | ExtraBytesDataType::U8
+            | ExtraBytesDataType::U16
+            | ExtraBytesDataType::U32
+            | ExtraBytesDataType::U64 => u64::from_le_bytes(bytes) as f64,
+            ExtraBytesDataType::I8
+            | ExtraBytesDataType::I16
+            | ExtraBytesDataType::I32
+            | ExtraBytesDataType::I64 => i64::from_le_bytes(bytes) as f64,
+            ExtraBytesDataType::F32 | ExtraBytesDataType::F64 => f64::from_le_bytes(bytes),
+        }
+    }
+
+    fn descriptor_value_bytes(&self, value: f64) -> [u8; 8] {
+        match self {
+            ExtraBytesDataType::Undocumented(_)
+            | ExtraBytesDataType::U8
+            | ExtraBytesDataType::U16
+            | ExtraBytesDataType::U32
+            | ExtraBytesDataType::U64 => (value as u64).to_le_bytes(),
+            ExtraBytesDataType::I8
+            | ExtraBytesDataType::I16
+            | ExtraBytesDataType::I32
+            | ExtraBytesDataType::I64 => (value as i64).to_le_bytes(),
+            ExtraBytesDataType::F32 | ExtraBytesDataType::F64 => value.to_le_bytes(),
+        }
+    }
+}
+
+/// Describes one dimension stored in the extra bytes of the points.
+///
+/// The deprecated data types (11 to 30) are arrays of 2 or 3 values,
+/// their `no_data`, `min`, `max`, `scale` and `offset` are the ones of the first element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtraBytesDescriptor {
+    pub data_type: ExtraBytesDataType,
+    /// Number of values (1, or 2 and 3 for the deprecated array types)
+    pub num_elements: u8,
+    pub name: String,
+    pub description: String,
+    /// Raw value meaning that there is no data
+    pub no_data: Option<f64>,
+    /// Minimum raw value
+    pub min: Option<f64>,
+    /// Maximum raw value
+    pub max: Option<f64>,
+    pub scale: Option<f64>,
+    pub offset: Option<f64>,
+}
+
+impl ExtraBytesDescriptor {
+    /// Size in bytes of a descriptor in the VLR
+    pub const SIZE: usize = 192;
+
+    const NO_DATA_BIT: u8 = 1;
+    const MIN_BIT: u8 = 1 << 1;
+    const MAX_BIT: u8 = 1 << 2;
+    const SCALE_BIT: u8 = 1 << 3;
+    const OFFSET_BIT: u8 = 1 << 4;
+
+    /// Creates a descriptor of a single value dimension without no_data, min, max, scale and offset
+    pub fn new(name: &str, data_type: ExtraBytesDataType) -> Self {
+        Self {
+            data_type,
+            num_elements: 1,
+            name: name.to_string(),
+            description: String::new(),
+            no_data: None,
+            min: None,
+            max: None,
+            scale: None,
+            offset: None,
+        }
+    }
+
+    /// Returns the number of bytes the dimension takes in a point
+    pub fn size(&self) -> usize {
+        self.data_type.size() * usize::from(self.num_elements)
+    }
+
+    /// Returns the real value, applying the scale and offset to the raw value
+    pub fn scaled(&self, raw_value: f64) -> f64 {
+        raw_value * self.scale.unwrap_or(1.0) + self.offset.unwrap_or(0.0)
+    }
+
+    pub fn read_from<R: Read>(src: &mut R) -> crate::Result<Self> {
+        src.read_u16::<LittleEndian>()?; // reserved
+        let code = src.read_u8()?;
+        let options = src.read_u8()?;
+        let (data_type, num_elements) = match code {
+            0 => (ExtraBytesDataType::Undocumented(options), 1),
+            1..=30 => {
+                let data_type = match (code - 1) % 10 {
+                    0 => ExtraBytesDataType::U8,
+                    1 => ExtraBytesDataType::I8,
+                    2 => ExtraBytesDataType::U16,
+                    3 => ExtraBytesDataType::I16,
+                    4 => ExtraBytesDataType::U32,
+                    5 => ExtraBytesDataType::I32,
+                    6 => ExtraBytesDataType::U64,
+                    7 => ExtraBytesDataType::I64,
+                    8 => ExtraBytesDataType::F32,
+                    _ => ExtraBytesDataType::F64,
+                };
+                (data_type, (code - 1) / 10 + 1)
+            }
+            _ => return Err(LasZipError::UnknownExtraBytesDataType(code)),
+        };
+        let mut name = [0u8; 32];
+        src.read_exact(&mut name)?;
+        src.read_u32::<LittleEndian>()?; // unused
+
+        // Each field is 8 bytes followed by 16 deprecated bytes
+        let mut fields = [[0u8; 8]; 5];
+        for field in &mut fields {
+            src.read_exact(field)?;
+            let mut deprecated = [0u8; 16];
+            src.read_exact(&mut deprecated)?;
+        }
+        let mut description = [0u8; 32];
+        src.read_exact(&mut description)?;
+
+        // The options of undocumented bytes are their size
+        let is_set = |bit: u8| code != 0 && options & bit != 0;
+        let [no_data, min, max, scale, offset] = fields;
+        Ok(Self {
+            data_type,
+            num_elements,
+            name: string_from_nul_padded(&name),
+            description: string_from_nul_padded(&description),
+            no_data: Some(data_type.read_descriptor_value(no_data))
+                .filter(|_| is_set(Self::NO_DATA_BIT)),
+            min: Some(data_type.read_descriptor_value(min)).filter(|_| is_set(Self::MIN_BIT)),
+            max: Some(data_type.read_descriptor_value(max)).filter(|_| is_set(Self::MAX_BIT)),
+            scale: Some(f64::from_le_bytes(scale)).filter(|_| is_set(Self::SCALE_BIT)),
+            offset: Some(f64::from_le_bytes(offset)).filter(|_| is_set(Self::OFFSET_BIT)),
+        })
+    }
+
+    /// # Panics
+    ///
+    /// If the `name` or the `description` is longer than 32 bytes,
+    /// or if `num_elements` is not 1 for undocumented bytes and not in 1..=3 otherwise.
+    pub fn write_to<W: Write>(&self, dst: &mut W) -> std::io::Result<()> {
+        let (code, options) = match self.data_type {
+            ExtraBytesDataType::Undocumented(size) => {
+                assert_eq!(self.num_elements, 1, "Undocumented bytes can't be an array");
+                (0, size)
+            }
+            data_type => {
+                assert!(
+                    (1..=3).contains(&self.num_elements),
+                    "Arrays of more than 3 elements are not supported"
+                );
+                let options = [
+                    (self.no_data, Self::NO_DATA_BIT),
+                    (self.min, Self::MIN_BIT),
+                    (self.max, Self::MAX_BIT),
+                    (self.scale, Self::SCALE_BIT),
+                    (self.offset, Self::OFFSET_BIT),
+                ]
+                .iter()
+                .filter(|(value, _)| value.is_some())
+                .fold(0, |options, (_, bit)| options | bit);
+                (data_type.code() + 10 * (self.num_elements - 1), options)
+            }
+        };
+        let mut name = [0u8; 32];
+        name[..self.name.len()].copy_from_slice(self.name.as_bytes());
+        let mut description = [0u8; 32];
+        description[..self.description.len()].copy_from_slice(self.description.as_bytes());
+
+        dst.write_u16::<LittleEndian>(0)?; // reserved
+        dst.write_u8(code)?;
+        dst.write_u8(options)?;
+        dst.write_all(&name)?;
+        dst.write_u32::<LittleEndian>(0)?; // unused
+        let data_type = self.data_type;
+        let fields = [
+            self.no_data.map(|v| data_type.descriptor_value_bytes(v)),
+            self.min.map(|v| data_type.descriptor_value_bytes(v)),
+            self.max.map(|v| data_type.descriptor_value_bytes(v)),
+            self.scale.map(f64::to_le_bytes),
+            self.offset.map(f64::to_le_bytes),
+        ];
+        for field in &fields {
+            dst.write_all(&field.unwrap_or([0u8; 8]))?;
+            dst.write_all(&[0u8; 16])?; // deprecated
+        }
+        dst.write_all(&description)
+    }
+}
+
+fn string_from_nul_padded(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+/// The Extra Bytes VLR, it describes the dimensions stored in the extra bytes of the points,
+/// in the order they are stored.
+///
+/// # Example
+///
+/// ```
+/// use laz::las::file::{ExtraBytesDataType, ExtraBytesDescriptor, ExtraBytesVlr};
+///
+/// let mut reflectance = ExtraBytesDescriptor::new("reflectance", ExtraBytesDataType::I16);
+/// reflectance.scale = Some(0.01);
+/// let vlr = ExtraBytesVlr::new(vec![
+///     ExtraBytesDescriptor::new("flags", ExtraBytesDataType::U8),
+///     reflectance,
+/// ]);
+///
+/// // A point of format 0 (20 bytes) followed by the 3 extra bytes
+/// let mut point = vec![0u8; 23];
+/// point[21..23].copy_from_slice(&(-1234i16).to_le_bytes());
+/// let field = vlr.field("reflectance", 0).unwrap();
+/// assert_eq!(field.value(&point), Some(-12.34));
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ExtraBytesVlr {
+    descriptors: Vec<ExtraBytesDescriptor>,
+}
+
+impl ExtraBytesVlr {
+    pub const USER_ID: &'static str = "LASF_Spec";
+    pub const RECORD_ID: u16 = 4;
+    pub const DESCRIPTION: &'static str = "Extra Bytes";
+
+    pub fn new(descriptors: Vec<ExtraBytesDescriptor>) -> Self {
+        Self { descriptors }
+    }
+
+    /// Reads the descriptors from the data of the VLR
+    pub fn read_from(data: &[u8]) -> crate::Result<Self> {
+        let chunks = data.chunks_exact(ExtraBytesDescriptor::SIZE);
+        if !chunks.remainder().is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "The size of the Extra Bytes VLR is not a multiple of the descriptor size",
+            )
+            .into());
+        }
+        let descriptors = chunks
+            .map(|mut descriptor| ExtraBytesDescriptor::read_from(&mut descriptor))
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(Self { descriptors })
+    }
+
+    /// Returns the Extra Bytes VLR of the VLRs, if any.
+    pub fn from_vlrs(vlrs: &[Vlr]) -> Option<crate::Result<Self>> {
+        vlrs.iter()
+            .find(|vlr| vlr.user_id() == Self::USER_ID && vlr.record_id() == Self::RECORD_ID)
+            .map(|vlr| Self::read_from(vlr.data()))
+    }
+
+    pub fn write_to<W: Write>(&self, dst: &mut W) -> std::io::Result<()> {
+        for descriptor in &self.descriptors {
+            descriptor.write_to(dst)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the VLR to write in a LAS file
+    pub fn to_vlr(&self) -> Vlr {
+        let mut data = Vec::with_capacity(self.descriptors.len() * ExtraBytesDescriptor::SIZE);
+        self.write_to(&mut data).unwrap();
+        Vlr::new(Self::USER_ID, Self::RECORD_ID, Self::DESCRIPTION, data)
+    }
+
+    pub fn descriptors(&self) -> &[ExtraBytesDescriptor] {
+        &self.descriptors
+    }
+
+    /// Returns the number of extra bytes described
+    pub fn num_extra_bytes(&self) -> usize {
+        self.descriptors
+            .iter()
+            .map(ExtraBytesDescriptor::size)
+            .sum()
+    }
+
+    /// Returns the fields of all the dimensions, for points of the given format.
+    ///
+    /// Returns `None` if the point format is not known.
+    pub fn fields(&self, point_format_id: u8) -> Option<Vec<ExtraBytesField<'_>>> {
+        let mut offset = usize::from(point_size_of_format(point_format_id)?);
+        let fields = self
+            .descriptors
+            .iter()
+            .map(|descriptor| {
+                let field = ExtraBytesField { descriptor, offset };
+                offset += descriptor.size();
+                field
+            })
+            .collect();
+        Some(fields)
+    }
+
+    /// Returns the field of the dimension named `name`, for points of the given format.
+    ///
+    /// Returns `None` if there is no such dimension or if the point format is not known.
+    pub fn field(&self, name: &str, point_format_id: u8) -> Option<ExtraBytesField<'_>> {
+        self.fields(point_format_id)?
+            .into_iter()
+            .find(|field| field.descriptor.name == name)
+    }
+}
+
+/// Gives access to the values of an extra bytes dimension in the points
+#[derive(Debug, Copy, Clone)]
+pub struct ExtraBytesField<'a> {
+    descriptor: &'a ExtraBytesDescriptor,
+    offset: usize,
+}
+
+impl<'a> ExtraBytesField<'a> {
+    pub fn descriptor(&self) -> &'a ExtraBytesDescriptor {
+        self.descriptor
+    }
+
+    /// Returns the offset of the dimension in the points
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the raw value of the element (0 for single value dimensions), as stored in the point.
+    ///
+    /// Returns `None` for undocumented bytes, if the element does not exist
+    /// or if the point is too small.
+    pub fn raw_value_at(&self, point: &[u8], element: usize) -> Option<f64> {
+        if element >= usize::from(self.descriptor.num_elements) {
+            return None;
+        }
+        let start = self.offset + element * self.descriptor.data_type.size();
+        self.descriptor.data_type.read_value(point.get(start..)?)
+    }
+
+    /// Returns the real value of the element (0 for single value dimensions),
+    /// the scale and offset are applied.
+    ///
+    /// Returns `None` if the raw value is the no_data value, see [ExtraBytesField::raw_value_at]
+    /// for the other cases.
+    pub fn value_at(&self, point: &[u8], element: usize) -> Option<f64> {
+        let raw_value = self.raw_value_at(point, element)?;
+        if self.descriptor.no_data == Some(raw_value) {
+            return None;
+        }
+        Some(self.descriptor.scaled(raw_value))
+    }
+
+    /// Returns the real value of the first element, see [ExtraBytesField::value_at].
+    pub fn value(&self, point: &[u8]) -> Option<f64> {
+        self.value_at(point, 0)
+    }
+}
+
+/// Reads the VLRs from the current position of the source and returns the LasZip VLR, if any.
+pub fn read_vlrs_and_get_laszip_vlr<R: Read>(
+    src: &mut R,
+    header: &LasHeader,
+) -> crate::Result<Option<LazVlr>> {
     let mut laszip_vlr = None;
     for _i in 0..header.num_vlrs {
-        let vlr = Vlr::read_from(src).unwrap();
-        if vlr.record_id == 22204
-            && String::from_utf8_lossy(&vlr.user_id).trim_end_matches(|c| c as u8 == 0)
-                == "laszip encoded"
-        {
-            laszip_vlr = Some(LazVlr::read_from(vlr.data.as_slice()).unwrap());
+        let vlr = Vlr::read_from(src)?;
+        if vlr.is_laszip_vlr() {
+            laszip_vlr = Some(LazVlr::read_from(vlr.data.as_slice())?);
         }
     }
-    laszip_vlr
+    Ok(laszip_vlr)
 }
 
 pub fn read_header_and_vlrs<R: Read + Seek>(
     src: &mut R,
-) -> std::io::Result<(QuickHeader, Option<LazVlr>)> {
-    let hdr = QuickHeader::read_from(src)?;
+) -> crate::Result<(LasHeader, Option<LazVlr>)> {
+    let hdr = LasHeader::read_from(src)?;
     src.seek(SeekFrom::Start(hdr.header_size as u64))?;
-    let laz_vlr = read_vlrs_and_get_laszip_vlr(src, &hdr);
+    let laz_vlr = read_vlrs_and_get_laszip_vlr(src, &hdr)?;
     src.seek(SeekFrom::Start(hdr.offset_to_points as u64))?;
     Ok((hdr, laz_vlr))
 }
@@ -164,27 +1130,37 @@ impl<'a, R: Read + Seek + Send> LasPointReader for LasZipDecompressor<'a, R> {
 
 /// Reader, that knows just enough things to be able to read LAS and LAZ data
 pub struct SimpleReader<'a> {
-    pub header: QuickHeader,
+    pub header: LasHeader,
     point_reader: Box<dyn LasPointReader + 'a>,
     internal_buffer: Vec<u8>,
     current_index: u64,
 }
 
 impl<'a> SimpleReader<'a> {
-    pub fn new<R: Read + Seek + Send + 'a>(mut src: R) -> std::io::Result<Self> {
-        let mut header = QuickHeader::read_from(&mut src)?;
+    /// Reads the header and the VLRs of the source, which must be at the start of the file.
+    ///
+    /// Fails with [LasZipError::CompressedWithoutLasZipVlr] if the point format has the
+    /// compressed bit set but no LasZip VLR is found, and with the errors of
+    /// [LasHeader::num_extra_bytes] if the point size is not valid.
+    pub fn new<R: Read + Seek + Send + 'a>(mut src: R) -> crate::Result<Self> {
+        let mut header = LasHeader::read_from(&mut src)?;
+        header.num_extra_bytes()?;
         src.seek(SeekFrom::Start(header.header_size as u64))?;
-        let laszip_vlr = read_vlrs_and_get_laszip_vlr(&mut src, &header);
+        let laszip_vlr = read_vlrs_and_get_laszip_vlr(&mut src, &header)?;
         src.seek(SeekFrom::Start(header.offset_to_points as u64))?;
         let point_reader: Box<dyn LasPointReader> =
             if is_point_format_compressed(header.point_format_id) {
-                Box::new(
-                    LasZipDecompressor::new(
-                        src,
-                        laszip_vlr.expect("Compressed data, but no Laszip Vlr found"),
+                let laszip_vlr = laszip_vlr.ok_or(LasZipError::CompressedWithoutLasZipVlr {
+                    point_format_id: header.point_format_id,
+                })?;
+                if laszip_vlr.items_size() != u64::from(header.point_size) {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "The point size of the header is not the size of the LasZip VLR items",
                     )
-                    .unwrap(),
-                )
+                    .into());
+                }
+                Box::new(LasZipDecompressor::new(src, laszip_vlr)?)
             } else {
                 Box::new(RawPointReader { src })
             };
@@ -211,19 +1187,392 @@ impl<'a> SimpleReader<'a> {
         }
     }
 
+    /// Reads all the points left and appends them to `buf`,
+    /// returns the number of points read.
     pub fn read_to_end(&mut self, buf: &mut Vec<u8>) -> std::io::Result<usize> {
-        assert!(buf.is_empty());
+        // The number of points is not trusted to allocate the whole buffer
+        let num_points_left = self.header.num_points - self.current_index;
+        buf.reserve(num_points_left.min(1_000_000) as usize * self.header.point_size as usize);
         let mut num_read = 0usize;
-        buf.resize(
-            self.header.num_points as usize * self.header.point_size as usize,
-            0u8,
-        );
-        let slc = buf.as_mut_slice();
         while let Some(point) = self.read_next() {
-            let point = point?;
-            slc[num_read * point.len()..(num_read + 1) * point.len()].copy_from_slice(point);
+            buf.extend_from_slice(point?);
             num_read += 1;
         }
         Ok(num_read)
     }
 }
+
+/// Writes the header and the VLRs (followed by the LasZip VLR if the points are compressed),
+/// the fields of the header that depend on the VLRs are updated.
+///
+/// Returns the position of the header.
+fn write_header_and_vlrs<W: Write + Seek>(
+    dest: &mut W,
+    header: &mut LasHeader,
+    vlrs: &[Vlr],
+    laz_vlr: Option<&LazVlr>,
+) -> crate::Result<u64> {
+    let format_id = point_format_id_compressed_to_uncompressd(header.point_format_id);
+    let laszip_vlr = match laz_vlr {
+        Some(laz_vlr) => {
+            header.point_format_id = point_format_id_uncompressed_to_compressed(format_id);
+            header.point_size = laz_vlr.items_size() as u16;
+            let mut laz_vlr_data = Vec::<u8>::new();
+            laz_vlr.write_to(&mut laz_vlr_data)?;
+            Some(Vlr::new(
+                LazVlr::USER_ID,
+                LazVlr::RECORD_ID,
+                LazVlr::DESCRIPTION,
+                laz_vlr_data,
+            ))
+        }
+        None => {
+            header.point_format_id = format_id;
+            None
+        }
+    };
+    // The writer reads the coordinates and the return number of the points
+    header.num_extra_bytes()?;
+    // The LasZip VLR is the one of the laz_vlr, not one that could be in the given VLRs
+    let vlrs = vlrs
+        .iter()
+        .filter(|vlr| !vlr.is_laszip_vlr())
+        .chain(laszip_vlr.as_ref())
+        .collect::<Vec<_>>();
+
+    header.num_vlrs = vlrs.len() as u32;
+    let vlrs_size: usize = vlrs
+        .iter()
+        .map(|vlr| Vlr::HEADER_SIZE + vlr.data().len())
+        .sum();
+    header.offset_to_points = u32::from(header.header_size) + vlrs_size as u32;
+    header.set_point_counts(0, [0; 15])?;
+
+    let start_pos = dest.stream_position()?;
+    header.write_to(dest)?;
+    for vlr in vlrs {
+        vlr.write_to(dest)?;
+    }
+    Ok(start_pos)
+}
+
+/// Where the points written by a [LasWriter] go.
+enum PointWriter<'a, W: Write + Seek + Send + 'a> {
+    Raw(W),
+    Compressed(LasZipCompressor<'a, W>),
+    #[cfg(feature = "parallel")]
+    ParCompressed(ParLasZipCompressor<W>),
+}
+
+impl<'a, W: Write + Seek + Send + 'a> PointWriter<'a, W> {
+    fn write_many(&mut self, points: &[u8]) -> crate::Result<()> {
+        match self {
+            PointWriter::Raw(dest) => dest.write_all(points)?,
+            PointWriter::Compressed(compressor) => compressor.compress_many(points)?,
+            #[cfg(feature = "parallel")]
+            PointWriter::ParCompressed(compressor) => compressor.compress_many(points)?,
+        }
+        Ok(())
+    }
+
+    fn done(&mut self) -> crate::Result<()> {
+        match self {
+            PointWriter::Raw(dest) => dest.flush()?,
+            PointWriter::Compressed(compressor) => compressor.done()?,
+            #[cfg(feature = "parallel")]
+            PointWriter::ParCompressed(compressor) => compressor.done()?,
+        }
+        Ok(())
+    }
+
+    fn laz_vlr(&self) -> Option<&LazVlr> {
+        match self {
+            PointWriter::Raw(_) => None,
+            PointWriter::Compressed(compressor) => Some(compressor.vlr()),
+            #[cfg(feature = "parallel")]
+            PointWriter::ParCompressed(compressor) => Some(compressor.vlr()),
+        }
+    }
+
+    fn get_mut(&mut self) -> &mut W {
+        match self {
+            PointWriter::Raw(dest) => dest,
+            PointWriter::Compressed(compressor) => compressor.get_mut(),
+            #[cfg(feature = "parallel")]
+            PointWriter::ParCompressed(compressor) => compressor.get_mut(),
+        }
+    }
+
+    fn get(&self) -> &W {
+        match self {
+            PointWriter::Raw(dest) => dest,
+            PointWriter::Compressed(compressor) => compressor.get(),
+            #[cfg(feature = "parallel")]
+            PointWriter::ParCompressed(compressor) => compressor.get(),
+        }
+    }
+
+    fn into_inner(self) -> W {
+        match self {
+            PointWriter::Raw(dest) => dest,
+            PointWriter::Compressed(compressor) => compressor.into_inner(),
+            #[cfg(feature = "parallel")]
+            PointWriter::ParCompressed(compressor) => compressor.into_inner(),
+        }
+    }
+}
+
+/// Writer of LAS and LAZ files.
+///
+/// The header, the VLRs and, when compressing, the LasZip VLR are written when the writer is created.
+/// When [done](Self::done) is called, the EVLRs are written after the points
+/// (and after the chunk table when compressing), then the header is written again
+/// with the point counts, the point counts by return and the bounds of the points that were written.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> laz::Result<()> {
+/// use laz::las::file::{LasHeader, LasWriter};
+/// use laz::{LazItemRecordBuilder, LazVlr};
+///
+/// let header = LasHeader {
+///     point_format_id: 1,
+///     point_size: 28,
+///     ..Default::default()
+/// };
+/// let laz_vlr = LazVlr::from_laz_items(LazItemRecordBuilder::default_for_point_format_id(1, 0)?);
+/// let file = std::fs::File::create("points.laz")?;
+/// let mut writer = LasWriter::new_compressed(file, header, &[], laz_vlr)?;
+/// writer.write_point(&[0u8; 28])?;
+/// writer.done()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct LasWriter<'a, W: Write + Seek + Send + 'a> {
+    header: LasHeader,
+    /// Position of the header in the destination
+    start_pos: u64,
+    point_writer: PointWriter<'a, W>,
+    evlrs: Vec<Evlr>,
+    num_points: u64,
+    points_by_return: [u64; 15],
+    mins: [f64; 3],
+    maxs: [f64; 3],
+    /// Whether done was called
+    is_done: bool,
+}
+
+impl<'a, W: Write + Seek + Send + 'a> LasWriter<'a, W> {
+    /// Creates a writer of uncompressed points.
+    ///
+    /// The `header` gives the version, the point format & size and the scales & offsets,
+    /// the fields that depend on what is written (offsets, counts, bounds) are computed by the writer.
+    ///
+    /// Fails with [LasZipError::UnsupportedPointFormat] or [LasZipError::PointSizeTooSmall]
+    /// if the point format or the point size of the `header` are not valid.
+    pub fn new(mut dest: W, mut header: LasHeader, vlrs: &[Vlr]) -> crate::Result<Self> {
+        let start_pos = write_header_and_vlrs(&mut dest, &mut header, vlrs, None)?;
+        Ok(Self::with_point_writer(
+            header,
+            start_pos,
+            PointWriter::Raw(dest),
+        ))
+    }
+
+    /// Creates a writer that compresses the points using the `laz_vlr`.
+    pub fn new_compressed(
+        mut dest: W,
+        mut header: LasHeader,
+        vlrs: &[Vlr],
+        laz_vlr: LazVlr,
+    ) -> crate::Result<Self> {
+        let start_pos = write_header_and_vlrs(&mut dest, &mut header, vlrs, Some(&laz_vlr))?;
+        let compressor = LasZipCompressor::new(dest, laz_vlr)?;
+        Ok(Self::with_point_writer(
+            header,
+            start_pos,
+            PointWriter::Compressed(compressor),
+        ))
+    }
+
+    /// Creates a writer that compresses the points using multiple threads.
+    ///
+    /// The `laz_vlr` must use fixed-size chunks, to actually use multiple threads
+    /// points should be written using [write_points](Self::write_points)
+    /// with more points than the chunk size.
+    #[cfg(feature = "parallel")]
+    pub fn new_par_compressed(
+        mut dest: W,
+        mut header: LasHeader,
+        vlrs: &[Vlr],
+        laz_vlr: LazVlr,
+    ) -> crate::Result<Self> {
+        if laz_vlr.uses_variable_size_chunks() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "The parallel compression requires fixed-size chunks",
+            )
+            .into());
+        }
+        let start_pos = write_header_and_vlrs(&mut dest, &mut header, vlrs, Some(&laz_vlr))?;
+        let compressor = ParLasZipCompressor::new(dest, laz_vlr)?;
+        Ok(Self::with_point_writer(
+            header,
+            start_pos,
+            PointWriter::ParCompressed(compressor),
+        ))
+    }
+
+    fn with_point_writer(
+        header: LasHeader,
+        start_pos: u64,
+        point_writer: PointWriter<'a, W>,
+    ) -> Self {
+        Self {
+            header,
+            start_pos,
+            point_writer,
+            evlrs: Vec::new(),
+            num_points: 0,
+            points_by_return: [0; 15],
+            mins: [f64::MAX; 3],
+            maxs: [f64::MIN; 3],
+            is_done: false,
+        }
+    }
+
+    /// Writes one point, its size must be the `point_size` of the header.
+    pub fn write_point(&mut self, point: &[u8]) -> crate::Result<()> {
+        self.write_points(point)
+    }
+
+    /// Writes many points, the size of the buffer must be a multiple of the `point_size` of the header.
+    pub fn write_points(&mut self, points: &[u8]) -> crate::Result<()> {
+        let point_size = usize::from(self.header.point_size);
+        let point_records = points.chunks_exact(point_size);
+        if !point_records.remainder().is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "The size of the points buffer is not a multiple of the point size",
+            )
+            .into());
+        }
+        let format_id = point_format_id_compressed_to_uncompressd(self.header.point_format_id);
+        let return_number_mask = if format_id >= 6 { 0b1111 } else { 0b111 };
+        for point in point_records {
+            for i in 0..3 {
+                let raw = i32::from_le_bytes([
+                    point[4 * i],
+                    point[4 * i + 1],
+                    point[4 * i + 2],
+                    point[4 * i + 3],
+                ]);
+                let coord = f64::from(raw) * self.header.scales[i] + self.header.offsets[i];
+                self.mins[i] = self.mins[i].min(coord);
+                self.maxs[i] = self.maxs[i].max(coord);
+            }
+            let return_number = usize::from(point[14] & return_number_mask);
+            if return_number > 0 {
+                self.points_by_return[return_number - 1] += 1;
+            }
+        }
+        self.num_points += (points.len() / point_size) as u64;
+        self.point_writer.write_many(points)
+    }
+
+    /// Adds an EVLR, that will be written when [done](Self::done) is called.
+    ///
+    /// EVLRs only exist since LAS 1.4, an error is returned for older versions.
+    pub fn push_evlr(&mut self, evlr: Evlr) -> crate::Result<()> {
+        if self.header.minor < 4 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "EVLRs can only be written in LAS 1.4 files",
+            )
+            .into());
+        }
+        self.evlrs.push(evlr);
+        Ok(())
+    }
+
+    /// Must be called once all the points are written,
+    /// finishes writing the points, writes the EVLRs
+    /// and writes the header again with the final counts & bounds.
+    ///
+    /// When compressing and EVLRs are written, the LasZip VLR is also written again
+    /// with the number of EVLRs and the offset to the first one.
+    ///
+    /// Calling this function again returns an error.
+    pub fn done(&mut self) -> crate::Result<()> {
+        if self.is_done {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "The writer is already done",
+            )
+            .into());
+        }
+        self.is_done = true;
+        self.point_writer.done()?;
+        self.header
+            .set_point_counts(self.num_points, self.points_by_return)?;
+        if self.num_points > 0 {
+            self.header.mins = self.mins;
+            self.header.maxs = self.maxs;
+        }
+
+        let dest = self.point_writer.get_mut();
+        if !self.evlrs.is_empty() {
+            self.header.start_of_first_evlr = dest.stream_position()? - self.start_pos;
+            self.header.num_evlrs = self.evlrs.len() as u32;
+            for evlr in &self.evlrs {
+                evlr.write_to(dest)?;
+            }
+        }
+        let end_pos = dest.stream_position()?;
+        dest.seek(SeekFrom::Start(self.start_pos))?;
+        self.header.write_to(dest)?;
+
+        if let Some(laz_vlr) = self
+            .point_writer
+            .laz_vlr()
+            .filter(|_| !self.evlrs.is_empty())
+        {
+            let mut laz_vlr = laz_vlr.clone();
+            laz_vlr.set_special_evlrs(self.evlrs.len() as u64, self.header.start_of_first_evlr);
+            let mut laz_vlr_data = Vec::<u8>::new();
+            laz_vlr.write_to(&mut laz_vlr_data)?;
+            // The LasZip VLR is the last one, just before the points
+            let laz_vlr_data_pos = self.start_pos + u64::from(self.header.offset_to_points)
+                - laz_vlr_data.len() as u64;
+            let dest = self.point_writer.get_mut();
+            dest.seek(SeekFrom::Start(laz_vlr_data_pos))?;
+            dest.write_all(&laz_vlr_data)?;
+        }
+
+        let dest = self.point_writer.get_mut();
+        dest.seek(SeekFrom::Start(end_pos))?;
+        dest.flush()?;
+        Ok(())
+    }
+
+    /// Returns the header, it is only complete after [done](Self::done) is called.
+    pub fn header(&self) -> &LasHeader {
+        &self.header
+    }
+
+    /// Consumes the writer and returns the destination.
+    pub fn into_inner(self) -> W {
+        self.point_writer.into_inner()
+    }
+
+    /// Returns a mutable reference to the destination.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.point_writer.get_mut()
+    }
+
+    /// Returns a reference to the destination.
+    pub fn get(&self) -> &W {
+        self.point_writer.get()
+    }
+}
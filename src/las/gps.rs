@@ -183,6 +183,7 @@ pub mod v1 {
     use crate::encoders::ArithmeticEncoder;
     use crate::las::gps::LasGpsTime;
     use crate::las::utils::read_and_unpack;
+    use crate::las::PointColumns;
     use crate::models::{ArithmeticModel, ArithmeticModelBuilder};
     use crate::packers::Packable;
     use crate::record::{FieldCompressor, FieldDecompressor};
@@ -456,6 +457,10 @@ pub mod v1 {
             GpsTime::from(self.last_gps).pack_into(buf);
             Ok(())
         }
+
+        fn push_last_to_columns(&self, columns: &mut PointColumns) {
+            columns.gps_time.push(GpsTime::from(self.last_gps).into());
+        }
     }
 }
 
@@ -467,6 +472,7 @@ pub mod v2 {
     use crate::decompressors::{IntegerDecompressor, IntegerDecompressorBuilder};
     use crate::encoders::ArithmeticEncoder;
     use crate::las::utils::{i32_quantize, read_and_unpack};
+    use crate::las::PointColumns;
     use crate::models::{ArithmeticModel, ArithmeticModelBuilder};
     use crate::packers::Packable;
     use crate::record::{FieldCompressor, FieldDecompressor};
@@ -1142,5 +1148,11 @@ pub mod v2 {
                 Ok(())
             }
         }
+
+        fn push_last_to_columns(&self, columns: &mut PointColumns) {
+            columns
+                .gps_time
+                .push(self.common.last_gps_times[self.common.last].into());
+        }
     }
 }
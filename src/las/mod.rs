@@ -2,7 +2,10 @@
 //! LAS data compressors & decompressors as well as
 //! the definition of the point types.
 
-pub use pointtypes::{Point0, Point1, Point2, Point3, Point6, Point7, Point8};
+pub use columns::PointColumns;
+pub use pointtypes::{
+    Point0, Point1, Point10, Point2, Point3, Point4, Point5, Point6, Point7, Point8, Point9,
+};
 
 #[macro_use]
 mod utils;
@@ -17,11 +20,13 @@ pub use crate::laszip;
 pub mod point0;
 pub mod point6;
 
+pub mod columns;
 pub mod extra_bytes;
 pub mod file;
 pub mod gps;
 pub mod nir;
 pub mod rgb;
+pub mod wavepacket;
 
 pub mod v1 {
     //! This module only contains re exports of compressors / decompressors
@@ -30,6 +35,7 @@ pub mod v1 {
     pub use crate::las::gps::v1::{LasGpsTimeCompressor, LasGpsTimeDecompressor};
     pub use crate::las::point0::v1::{LasPoint0Compressor, LasPoint0Decompressor};
     pub use crate::las::rgb::v1::{LasRGBCompressor, LasRGBDecompressor};
+    pub use crate::las::wavepacket::v1::{LasWavepacketCompressor, LasWavepacketDecompressor};
 }
 
 pub mod v2 {
@@ -39,6 +45,7 @@ pub mod v2 {
     pub use crate::las::gps::v2::{GpsTimeCompressor, GpsTimeDecompressor};
     pub use crate::las::point0::v2::{LasPoint0Compressor, LasPoint0Decompressor};
     pub use crate::las::rgb::v2::{LasRGBCompressor, LasRGBDecompressor};
+    pub use crate::las::wavepacket::v2::{LasWavepacketCompressor, LasWavepacketDecompressor};
 }
 
 pub mod v3 {
@@ -48,4 +55,22 @@ pub mod v3 {
     pub use crate::las::nir::v3::{LasNIRCompressor, LasNIRDecompressor};
     pub use crate::las::point6::v3::{LasPoint6Compressor, LasPoint6Decompressor};
     pub use crate::las::rgb::v3::{LasRGBCompressor, LasRGBDecompressor};
+    pub use crate::las::wavepacket::v3::{LasWavepacketCompressor, LasWavepacketDecompressor};
+}
+
+pub mod v4 {
+    //! This module only contains re exports of compressors / decompressors
+    //! of the corresponding version for easier access
+    //!
+    //! The version 4 of the layered items is the same as the version 3,
+    //! except when the point switches to a context (scanner channel) that was already used:
+    //! LASzip's version 3 keeps predicting the RGB, NIR, wave packet and extra bytes
+    //! from the last item of the previous context, whereas the version 4 predicts them
+    //! from the last item of that context.
+    //! The Point14 is predicted from the last point of the context in both versions.
+    pub use crate::las::extra_bytes::v4::{LasExtraByteCompressor, LasExtraByteDecompressor};
+    pub use crate::las::nir::v4::{LasNIRCompressor, LasNIRDecompressor};
+    pub use crate::las::point6::v4::{LasPoint6Compressor, LasPoint6Decompressor};
+    pub use crate::las::rgb::v4::{LasRGBCompressor, LasRGBDecompressor};
+    pub use crate::las::wavepacket::v4::{LasWavepacketCompressor, LasWavepacketDecompressor};
 }
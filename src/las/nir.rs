@@ -29,11 +29,13 @@ pub mod v3 {
 
     use crate::decoders::ArithmeticDecoder;
     use crate::encoders::ArithmeticEncoder;
+    use crate::las::point6::DecompressionSelector;
     use crate::las::utils::copy_bytes_into_decoder;
     use crate::las::utils::{
         copy_encoder_content_to, lower_byte, lower_byte_changed, read_and_unpack, upper_byte,
         upper_byte_changed,
     };
+    use crate::las::PointColumns;
     use crate::models::{ArithmeticModel, ArithmeticModelBuilder};
     use crate::packers::Packable;
     use crate::record::{LayeredFieldCompressor, LayeredFieldDecompressor};
@@ -56,15 +58,35 @@ pub mod v3 {
         }
     }
 
-    //TODO Selective
     pub struct LasNIRDecompressor {
         decoder: ArithmeticDecoder<Cursor<Vec<u8>>>,
         changed_nir: bool,
+        requested_nir: bool,
         layer_size: u32,
         last_context_used: usize,
         // Last & contexts are separated for the same reasons as in v3::RGB
         contexts: [NirContext; 4],
         last_nirs: [u16; 4],
+        // The NIR of the last decompressed point, like v3::RGB::last_rgb
+        last_nir: u16,
+        // true for the version 4, see crate::las::v4
+        last_item_follows_context: bool,
+    }
+
+    impl LasNIRDecompressor {
+        pub fn selective(selector: DecompressionSelector) -> Self {
+            Self {
+                requested_nir: selector.nir_requested(),
+                ..Self::default()
+            }
+        }
+
+        pub(super) fn for_version_4(selector: DecompressionSelector) -> Self {
+            Self {
+                last_item_follows_context: true,
+                ..Self::selective(selector)
+            }
+        }
     }
 
     impl Default for LasNIRDecompressor {
@@ -78,9 +100,12 @@ pub mod v3 {
                     NirContext::default(),
                 ],
                 changed_nir: false,
+                requested_nir: true,
                 layer_size: 0,
                 last_context_used: 0,
                 last_nirs: [0u16; 4],
+                last_nir: 0,
+                last_item_follows_context: false,
             }
         }
     }
@@ -103,6 +128,9 @@ pub mod v3 {
             self.last_nirs[*context] = read_and_unpack::<_, u16>(src, first_point)?;
             self.contexts[*context].unused = false;
             self.last_context_used = *context;
+            if !self.requested_nir {
+                first_point.fill(0);
+            }
             Ok(())
         }
 
@@ -111,6 +139,11 @@ pub mod v3 {
             current_point: &mut [u8],
             context: &mut usize,
         ) -> std::io::Result<()> {
+            if !self.requested_nir {
+                current_point.fill(0);
+                return Ok(());
+            }
+
             let mut last_nir = &mut self.last_nirs[self.last_context_used];
             if self.last_context_used != *context {
                 self.last_context_used = *context;
@@ -118,6 +151,8 @@ pub mod v3 {
                     self.last_nirs[*context] = *last_nir;
                     self.contexts[*context].unused = false;
                     last_nir = &mut self.last_nirs[*context];
+                } else if self.last_item_follows_context {
+                    last_nir = &mut self.last_nirs[*context];
                 }
             }
 
@@ -151,6 +186,7 @@ pub mod v3 {
                 *last_nir = new_nir;
             }
             last_nir.pack_into(current_point);
+            self.last_nir = *last_nir;
             Ok(())
         }
 
@@ -161,13 +197,19 @@ pub mod v3 {
 
         fn read_layers(&mut self, src: &mut R) -> std::io::Result<()> {
             self.changed_nir = copy_bytes_into_decoder(
-                true, //TODO
+                self.requested_nir,
                 self.layer_size as usize,
                 &mut self.decoder,
                 src,
             )?;
             Ok(())
         }
+
+        fn push_last_to_columns(&self, columns: &mut PointColumns) {
+            columns
+                .nir
+                .push(if self.requested_nir { self.last_nir } else { 0 });
+        }
     }
 
     pub struct LasNIRCompressor {
@@ -176,6 +218,17 @@ pub mod v3 {
         last_context_used: usize,
         contexts: [NirContext; 4],
         last_nirs: [u16; 4],
+        // Same as in the decompressor
+        last_item_follows_context: bool,
+    }
+
+    impl LasNIRCompressor {
+        pub(super) fn for_version_4() -> Self {
+            Self {
+                last_item_follows_context: true,
+                ..Self::default()
+            }
+        }
     }
 
     impl Default for LasNIRCompressor {
@@ -191,6 +244,7 @@ pub mod v3 {
                 has_nir_changed: false,
                 last_context_used: 0,
                 last_nirs: [0u16; 4],
+                last_item_follows_context: false,
             }
         }
     }
@@ -229,6 +283,8 @@ pub mod v3 {
                     self.last_nirs[*context] = *last_nir;
                     self.contexts[*context].unused = false;
                     last_nir = &mut self.last_nirs[*context];
+                } else if self.last_item_follows_context {
+                    last_nir = &mut self.last_nirs[*context];
                 }
             };
             let the_context = &mut self.contexts[self.last_context_used];
@@ -273,3 +329,36 @@ pub mod v3 {
         }
     }
 }
+
+pub mod v4 {
+    //! Contains the implementation for the Version 4 of the NIR Compression / Decompression
+    use crate::las::point6::DecompressionSelector;
+
+    use super::v3;
+
+    pub struct LasNIRDecompressor(v3::LasNIRDecompressor);
+
+    impl LasNIRDecompressor {
+        pub fn selective(selector: DecompressionSelector) -> Self {
+            Self(v3::LasNIRDecompressor::for_version_4(selector))
+        }
+    }
+
+    impl Default for LasNIRDecompressor {
+        fn default() -> Self {
+            Self::selective(DecompressionSelector::decompress_all())
+        }
+    }
+
+    forward_layered_field_decompressor_impl!(LasNIRDecompressor);
+
+    pub struct LasNIRCompressor(v3::LasNIRCompressor);
+
+    impl Default for LasNIRCompressor {
+        fn default() -> Self {
+            Self(v3::LasNIRCompressor::for_version_4())
+        }
+    }
+
+    forward_layered_field_compressor_impl!(LasNIRCompressor);
+}
@@ -266,6 +266,7 @@ pub mod v1 {
     };
     use crate::encoders::ArithmeticEncoder;
     use crate::las::point0::LasPoint0;
+    use crate::las::PointColumns;
     use crate::models::{ArithmeticModel, ArithmeticModelBuilder};
     use crate::packers::Packable;
     use crate::record::{FieldCompressor, FieldDecompressor};
@@ -621,6 +622,10 @@ pub mod v1 {
             self.last_point.pack_into(buf);
             Ok(())
         }
+
+        fn push_last_to_columns(&self, columns: &mut PointColumns) {
+            columns.push_point0(&self.last_point);
+        }
     }
 
     #[cfg(test)]
@@ -665,6 +670,7 @@ pub mod v2 {
     use crate::encoders::ArithmeticEncoder;
     use crate::las::point0::LasPoint0;
     use crate::las::utils;
+    use crate::las::PointColumns;
     use crate::models::{ArithmeticModel, ArithmeticModelBuilder};
     use crate::packers::Packable;
     use crate::record::{FieldCompressor, FieldDecompressor};
@@ -1162,5 +1168,9 @@ pub mod v2 {
             self.last_point.pack_into(buf);
             Ok(())
         }
+
+        fn push_last_to_columns(&self, columns: &mut PointColumns) {
+            columns.push_point0(&self.last_point);
+        }
     }
 }
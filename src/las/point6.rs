@@ -3,51 +3,182 @@
 use crate::las::gps::GpsTime;
 use crate::packers::Packable;
 
+/// The version 4 of the Point14 compression is the same as the version 3
+pub use v3 as v4;
+
 fn u32_zero_bit_0(n: u32) -> u32 {
     n & 0xFFFF_FFFE
 }
 
+/// Selects which layers of layered compressed points (point formats 6 to 10)
+/// are decompressed.
+///
+/// X, Y, the return numbers and the scanner channel are always decompressed.
+///
+/// The bytes of the layers that are not selected are skipped and not decoded,
+/// the corresponding fields are set to zero in the decompressed points.
+///
+/// # Example
+///
+/// ```
+/// use laz::las::point6::DecompressionSelector;
+///
+/// let selection = DecompressionSelector::xy_returns_channel()
+///     .z()
+///     .classification();
+///
+/// assert!(selection.z_requested());
+/// assert!(selection.classification_requested());
+/// assert!(!selection.gps_time_requested());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct DecompressionSelector(u32);
 
 impl DecompressionSelector {
+    const CHANNEL_RETURNS_XY: u32 = 0x0000_0000;
+    const Z: u32 = 0x0000_0001;
+    const CLASSIFICATION: u32 = 0x0000_0002;
+    const FLAGS: u32 = 0x0000_0004;
+    const INTENSITY: u32 = 0x0000_0008;
+    const SCAN_ANGLE: u32 = 0x0000_0010;
+    const USER_DATA: u32 = 0x0000_0020;
+    const POINT_SOURCE: u32 = 0x0000_0040;
+    const GPS_TIME: u32 = 0x0000_0080;
+    const RGB: u32 = 0x0000_0100;
+    const NIR: u32 = 0x0000_0200;
+    const WAVEPACKET: u32 = 0x0000_0400;
+    const BYTE0: u32 = 0x0001_0000;
+    const EXTRA_BYTES: u32 = 0xFFFF_0000;
+
+    /// Selects all the layers.
     pub fn decompress_all() -> Self {
-        Self { 0: 0xFFFF_FFFF }
+        Self(0xFFFF_FFFF)
+    }
+
+    /// Selects only the layer that is always decompressed:
+    /// X, Y, the return numbers and the scanner channel.
+    pub fn xy_returns_channel() -> Self {
+        Self(Self::CHANNEL_RETURNS_XY)
+    }
+
+    /// Also selects the Z layer.
+    pub fn z(self) -> Self {
+        self.with(Self::Z)
+    }
+
+    /// Also selects the classification layer.
+    pub fn classification(self) -> Self {
+        self.with(Self::CLASSIFICATION)
+    }
+
+    /// Also selects the flags layer
+    /// (classification flags, scan direction flag, edge of flight line).
+    pub fn flags(self) -> Self {
+        self.with(Self::FLAGS)
+    }
+
+    /// Also selects the intensity layer.
+    pub fn intensity(self) -> Self {
+        self.with(Self::INTENSITY)
+    }
+
+    /// Also selects the scan angle layer.
+    pub fn scan_angle(self) -> Self {
+        self.with(Self::SCAN_ANGLE)
+    }
+
+    /// Also selects the user data layer.
+    pub fn user_data(self) -> Self {
+        self.with(Self::USER_DATA)
+    }
+
+    /// Also selects the point source id layer.
+    pub fn point_source(self) -> Self {
+        self.with(Self::POINT_SOURCE)
+    }
+
+    /// Also selects the gps time layer.
+    pub fn gps_time(self) -> Self {
+        self.with(Self::GPS_TIME)
+    }
+
+    /// Also selects the RGB layer.
+    pub fn rgb(self) -> Self {
+        self.with(Self::RGB)
+    }
+
+    /// Also selects the NIR layer.
+    pub fn nir(self) -> Self {
+        self.with(Self::NIR)
+    }
+
+    /// Also selects the wave packet layer.
+    pub fn wavepacket(self) -> Self {
+        self.with(Self::WAVEPACKET)
+    }
+
+    /// Also selects the layers of all the extra bytes.
+    pub fn extra_bytes(self) -> Self {
+        self.with(Self::EXTRA_BYTES)
     }
 
     pub fn channel_returns_xy_requested(&self) -> bool {
-        self.is_set(0x0000_0000)
+        true
     }
 
     pub fn z_requested(&self) -> bool {
-        self.is_set(0x0000_0001)
+        self.is_set(Self::Z)
     }
 
     pub fn classification_requested(&self) -> bool {
-        self.is_set(0x0000_0002)
+        self.is_set(Self::CLASSIFICATION)
     }
 
     pub fn flags_requested(&self) -> bool {
-        self.is_set(0x0000_0004)
+        self.is_set(Self::FLAGS)
     }
 
     pub fn intensity_requested(&self) -> bool {
-        self.is_set(0x0000_0008)
+        self.is_set(Self::INTENSITY)
     }
 
     pub fn scan_angle_requested(&self) -> bool {
-        self.is_set(0x0000_0010)
+        self.is_set(Self::SCAN_ANGLE)
     }
 
     pub fn user_data_requested(&self) -> bool {
-        self.is_set(0x0000_0020)
+        self.is_set(Self::USER_DATA)
     }
 
     pub fn point_source_requested(&self) -> bool {
-        self.is_set(0x0000_0040)
+        self.is_set(Self::POINT_SOURCE)
     }
 
     pub fn gps_time_requested(&self) -> bool {
-        self.is_set(0x0000_0080)
+        self.is_set(Self::GPS_TIME)
+    }
+
+    pub fn rgb_requested(&self) -> bool {
+        self.is_set(Self::RGB)
+    }
+
+    pub fn nir_requested(&self) -> bool {
+        self.is_set(Self::NIR)
+    }
+
+    pub fn wavepacket_requested(&self) -> bool {
+        self.is_set(Self::WAVEPACKET)
+    }
+
+    /// Returns whether the extra byte at `index` is requested.
+    ///
+    /// As in LASzip, extra bytes past the 16th one follow the selection of the 16th.
+    pub fn extra_byte_requested(&self, index: usize) -> bool {
+        self.is_set(Self::BYTE0 << index.min(15))
+    }
+
+    fn with(self, mask: u32) -> Self {
+        Self(self.0 | mask)
     }
 
     fn is_set(&self, mask: u32) -> bool {
@@ -55,6 +186,12 @@ impl DecompressionSelector {
     }
 }
 
+impl Default for DecompressionSelector {
+    fn default() -> Self {
+        Self::decompress_all()
+    }
+}
+
 //TODO cleanup
 pub trait LasPoint6 {
     // Non mutable accessors
@@ -376,10 +513,48 @@ pub mod v3 {
         copy_bytes_into_decoder, copy_encoder_content_to, i32_quantize, read_and_unpack,
         StreamingMedian, NUMBER_RETURN_LEVEL_8CT, NUMBER_RETURN_MAP_6CTX,
     };
+    use crate::las::PointColumns;
     use crate::models::{ArithmeticModel, ArithmeticModelBuilder};
     use crate::packers::Packable;
     use crate::record::{LayeredFieldCompressor, LayeredFieldDecompressor};
 
+    /// Writes the point into the output buffer, with the fields
+    /// of the layers that were not requested set to zero.
+    fn pack_requested_into(selector: &DecompressionSelector, point: &Point6, output: &mut [u8]) {
+        requested_fields_of(selector, point).pack_into(output);
+    }
+
+    /// Returns the point with the fields of the layers that were not requested set to zero.
+    fn requested_fields_of(selector: &DecompressionSelector, point: &Point6) -> Point6 {
+        let mut point = *point;
+        if !selector.z_requested() {
+            point.z = 0;
+        }
+        if !selector.classification_requested() {
+            point.classification = 0;
+        }
+        if !selector.flags_requested() {
+            // The scanner channel is part of the always decompressed layer
+            point.flags &= 0b0011_0000;
+        }
+        if !selector.intensity_requested() {
+            point.intensity = 0;
+        }
+        if !selector.scan_angle_requested() {
+            point.scan_angle_rank = 0;
+        }
+        if !selector.user_data_requested() {
+            point.user_data = 0;
+        }
+        if !selector.point_source_requested() {
+            point.point_source_id = 0;
+        }
+        if !selector.gps_time_requested() {
+            point.gps_time = 0.0;
+        }
+        point
+    }
+
     fn compute_last_point_return(last_point: &Point6) -> usize {
         // Create single (3) / first (1) / last (2) / intermediate (0) context from last point return
         let mut lpr = if last_point.return_number() == 1 {
@@ -913,6 +1088,7 @@ pub mod v3 {
                 context.unused = true;
             }
             let point = read_and_unpack::<_, Point6>(src, first_point)?;
+            pack_requested_into(&self.decompression_selector, &point, first_point);
             self.current_context = point.scanner_channel() as usize;
             *context = self.current_context;
 
@@ -1150,7 +1326,7 @@ pub mod v3 {
 
             let last_point = &mut self.contexts[self.current_context].last_point;
             last_point.gps_time_change = gps_time_changed;
-            last_point.pack_into(current_point);
+            pack_requested_into(&self.decompression_selector, last_point, current_point);
             Ok(())
         }
 
@@ -1225,6 +1401,14 @@ pub mod v3 {
             )?;
             Ok(())
         }
+
+        fn push_last_to_columns(&self, columns: &mut PointColumns) {
+            let last_point = &self.contexts[self.current_context].last_point;
+            columns.push_point6(&requested_fields_of(
+                &self.decompression_selector,
+                last_point,
+            ));
+        }
     }
 
     struct Point6Encoders {
@@ -3,7 +3,8 @@ use crate::las::nir::Nir;
 pub use crate::las::point0::{LasPoint0, Point0};
 pub use crate::las::point6::{LasPoint6, Point6};
 pub use crate::las::rgb::{LasRGB, RGB};
-use crate::laszip::{DefaultVersion, LazItem, LazItemType, Version1, Version2, Version3};
+pub use crate::las::wavepacket::{LasWavepacket, Wavepacket};
+use crate::laszip::{DefaultVersion, LazItem, LazItemType, Version1, Version2, Version3, Version4};
 
 pub trait Point0Based {
     fn point0(&self) -> &Point0;
@@ -302,6 +303,273 @@ impl DefaultVersion for Point3 {
     }
 }
 
+/***************************************************************************************************
+                    Point Format 4
+***************************************************************************************************/
+
+#[derive(Default, Debug, PartialEq, Copy, Clone)]
+pub struct Point4 {
+    base: Point0,
+    gps_time: f64,
+    wavepacket: Wavepacket,
+}
+
+impl Point0Based for Point4 {
+    fn point0(&self) -> &Point0 {
+        &self.base
+    }
+
+    fn point0_mut(&mut self) -> &mut Point0 {
+        &mut self.base
+    }
+}
+
+impl LasGpsTime for Point4 {
+    fn gps_time(&self) -> f64 {
+        self.gps_time
+    }
+
+    fn set_gps_time(&mut self, new_value: f64) {
+        self.gps_time = new_value;
+    }
+}
+
+impl LasWavepacket for Point4 {
+    fn descriptor_index(&self) -> u8 {
+        self.wavepacket.descriptor_index()
+    }
+
+    fn offset_to_data(&self) -> u64 {
+        self.wavepacket.offset_to_data()
+    }
+
+    fn packet_size(&self) -> u32 {
+        self.wavepacket.packet_size()
+    }
+
+    fn return_point(&self) -> f32 {
+        self.wavepacket.return_point()
+    }
+
+    fn x_t(&self) -> f32 {
+        self.wavepacket.x_t()
+    }
+
+    fn y_t(&self) -> f32 {
+        self.wavepacket.y_t()
+    }
+
+    fn z_t(&self) -> f32 {
+        self.wavepacket.z_t()
+    }
+
+    fn set_descriptor_index(&mut self, new_val: u8) {
+        self.wavepacket.set_descriptor_index(new_val)
+    }
+
+    fn set_offset_to_data(&mut self, new_val: u64) {
+        self.wavepacket.set_offset_to_data(new_val)
+    }
+
+    fn set_packet_size(&mut self, new_val: u32) {
+        self.wavepacket.set_packet_size(new_val)
+    }
+
+    fn set_return_point(&mut self, new_val: f32) {
+        self.wavepacket.set_return_point(new_val)
+    }
+
+    fn set_x_t(&mut self, new_val: f32) {
+        self.wavepacket.set_x_t(new_val)
+    }
+
+    fn set_y_t(&mut self, new_val: f32) {
+        self.wavepacket.set_y_t(new_val)
+    }
+
+    fn set_z_t(&mut self, new_val: f32) {
+        self.wavepacket.set_z_t(new_val)
+    }
+}
+
+impl Version2 for Point4 {
+    fn version_2(num_extra_bytes: u16) -> Vec<LazItem> {
+        vec_of_laz_items!(
+            vec_capacity: 4,
+            extra_bytes_type: LazItemType::Byte(num_extra_bytes), version: 2,
+            LazItemType::Point10, version: 2,
+            LazItemType::GpsTime, version: 2,
+            LazItemType::WavePacket13, version: 1
+        )
+    }
+}
+
+impl Version1 for Point4 {
+    fn version_1(num_extra_bytes: u16) -> Vec<LazItem> {
+        vec_of_laz_items!(
+            vec_capacity: 4,
+            extra_bytes_type: LazItemType::Byte(num_extra_bytes), version: 1,
+            LazItemType::Point10, version: 1,
+            LazItemType::GpsTime, version: 1,
+            LazItemType::WavePacket13, version: 1
+        )
+    }
+}
+
+impl DefaultVersion for Point4 {
+    fn default_version(num_extra_bytes: u16) -> Vec<LazItem> {
+        <Self as Version2>::version_2(num_extra_bytes)
+    }
+}
+
+/***************************************************************************************************
+                    Point Format 5
+***************************************************************************************************/
+
+#[derive(Default, Debug, PartialEq, Copy, Clone)]
+pub struct Point5 {
+    base: Point0,
+    gps_time: f64,
+    rgb: RGB,
+    wavepacket: Wavepacket,
+}
+
+impl Point0Based for Point5 {
+    fn point0(&self) -> &Point0 {
+        &self.base
+    }
+
+    fn point0_mut(&mut self) -> &mut Point0 {
+        &mut self.base
+    }
+}
+
+impl LasGpsTime for Point5 {
+    fn gps_time(&self) -> f64 {
+        self.gps_time
+    }
+
+    fn set_gps_time(&mut self, new_value: f64) {
+        self.gps_time = new_value;
+    }
+}
+
+impl LasRGB for Point5 {
+    fn red(&self) -> u16 {
+        self.rgb.red()
+    }
+
+    fn green(&self) -> u16 {
+        self.rgb.green()
+    }
+
+    fn blue(&self) -> u16 {
+        self.rgb.blue()
+    }
+
+    fn set_red(&mut self, new_val: u16) {
+        self.rgb.set_red(new_val)
+    }
+
+    fn set_green(&mut self, new_val: u16) {
+        self.rgb.set_green(new_val)
+    }
+
+    fn set_blue(&mut self, new_val: u16) {
+        self.rgb.set_blue(new_val)
+    }
+}
+
+impl LasWavepacket for Point5 {
+    fn descriptor_index(&self) -> u8 {
+        self.wavepacket.descriptor_index()
+    }
+
+    fn offset_to_data(&self) -> u64 {
+        self.wavepacket.offset_to_data()
+    }
+
+    fn packet_size(&self) -> u32 {
+        self.wavepacket.packet_size()
+    }
+
+    fn return_point(&self) -> f32 {
+        self.wavepacket.return_point()
+    }
+
+    fn x_t(&self) -> f32 {
+        self.wavepacket.x_t()
+    }
+
+    fn y_t(&self) -> f32 {
+        self.wavepacket.y_t()
+    }
+
+    fn z_t(&self) -> f32 {
+        self.wavepacket.z_t()
+    }
+
+    fn set_descriptor_index(&mut self, new_val: u8) {
+        self.wavepacket.set_descriptor_index(new_val)
+    }
+
+    fn set_offset_to_data(&mut self, new_val: u64) {
+        self.wavepacket.set_offset_to_data(new_val)
+    }
+
+    fn set_packet_size(&mut self, new_val: u32) {
+        self.wavepacket.set_packet_size(new_val)
+    }
+
+    fn set_return_point(&mut self, new_val: f32) {
+        self.wavepacket.set_return_point(new_val)
+    }
+
+    fn set_x_t(&mut self, new_val: f32) {
+        self.wavepacket.set_x_t(new_val)
+    }
+
+    fn set_y_t(&mut self, new_val: f32) {
+        self.wavepacket.set_y_t(new_val)
+    }
+
+    fn set_z_t(&mut self, new_val: f32) {
+        self.wavepacket.set_z_t(new_val)
+    }
+}
+
+impl Version2 for Point5 {
+    fn version_2(num_extra_bytes: u16) -> Vec<LazItem> {
+        vec_of_laz_items!(
+            vec_capacity: 5,
+            extra_bytes_type: LazItemType::Byte(num_extra_bytes), version: 2,
+            LazItemType::Point10, version: 2,
+            LazItemType::GpsTime, version: 2,
+            LazItemType::RGB12, version: 2,
+            LazItemType::WavePacket13, version: 1
+        )
+    }
+}
+
+impl Version1 for Point5 {
+    fn version_1(num_extra_bytes: u16) -> Vec<LazItem> {
+        vec_of_laz_items!(
+            vec_capacity: 5,
+            extra_bytes_type: LazItemType::Byte(num_extra_bytes), version: 1,
+            LazItemType::Point10, version: 1,
+            LazItemType::GpsTime, version: 1,
+            LazItemType::RGB12, version: 1,
+            LazItemType::WavePacket13, version: 1
+        )
+    }
+}
+
+impl DefaultVersion for Point5 {
+    fn default_version(num_extra_bytes: u16) -> Vec<LazItem> {
+        <Self as Version2>::version_2(num_extra_bytes)
+    }
+}
+
 /***************************************************************************************************
                     Point Format 6
 ***************************************************************************************************/
@@ -316,6 +584,17 @@ impl Version3 for Point6 {
     }
 }
 
+impl Version4 for Point6 {
+    fn version_4(num_extra_bytes: u16) -> Vec<LazItem> {
+        vec_of_laz_items![
+            vec_capacity: 2,
+            version: 4,
+            extra_bytes_type: LazItemType::Byte14(num_extra_bytes),
+            LazItemType::Point14
+        ]
+    }
+}
+
 impl DefaultVersion for Point6 {
     fn default_version(num_extra_bytes: u16) -> Vec<LazItem> {
         Self::version_3(num_extra_bytes)
@@ -380,6 +659,18 @@ impl Version3 for Point7 {
     }
 }
 
+impl Version4 for Point7 {
+    fn version_4(num_extra_bytes: u16) -> Vec<LazItem> {
+        vec_of_laz_items![
+            vec_capacity: 3,
+            version: 4,
+            extra_bytes_type: LazItemType::Byte14(num_extra_bytes),
+            LazItemType::Point14,
+            LazItemType::RGB14
+        ]
+    }
+}
+
 impl DefaultVersion for Point7 {
     fn default_version(num_extra_bytes: u16) -> Vec<LazItem> {
         Self::version_3(num_extra_bytes)
@@ -409,12 +700,270 @@ impl Version3 for Point8 {
     }
 }
 
+impl Version4 for Point8 {
+    fn version_4(num_extra_bytes: u16) -> Vec<LazItem> {
+        vec_of_laz_items![
+            vec_capacity: 3,
+            version: 4,
+            extra_bytes_type: LazItemType::Byte14(num_extra_bytes),
+            LazItemType::Point14,
+            LazItemType::RGBNIR14
+        ]
+    }
+}
+
 impl DefaultVersion for Point8 {
     fn default_version(num_extra_bytes: u16) -> Vec<LazItem> {
         Self::version_3(num_extra_bytes)
     }
 }
 
+/***************************************************************************************************
+                    Point Format 9
+***************************************************************************************************/
+
+#[derive(Default, Debug, PartialEq, Copy, Clone)]
+pub struct Point9 {
+    base: Point6,
+    wavepacket: Wavepacket,
+}
+
+impl Point6Based for Point9 {
+    fn point6(&self) -> &Point6 {
+        &self.base
+    }
+
+    fn point6_mut(&mut self) -> &mut Point6 {
+        &mut self.base
+    }
+}
+
+impl LasWavepacket for Point9 {
+    fn descriptor_index(&self) -> u8 {
+        self.wavepacket.descriptor_index()
+    }
+
+    fn offset_to_data(&self) -> u64 {
+        self.wavepacket.offset_to_data()
+    }
+
+    fn packet_size(&self) -> u32 {
+        self.wavepacket.packet_size()
+    }
+
+    fn return_point(&self) -> f32 {
+        self.wavepacket.return_point()
+    }
+
+    fn x_t(&self) -> f32 {
+        self.wavepacket.x_t()
+    }
+
+    fn y_t(&self) -> f32 {
+        self.wavepacket.y_t()
+    }
+
+    fn z_t(&self) -> f32 {
+        self.wavepacket.z_t()
+    }
+
+    fn set_descriptor_index(&mut self, new_val: u8) {
+        self.wavepacket.set_descriptor_index(new_val)
+    }
+
+    fn set_offset_to_data(&mut self, new_val: u64) {
+        self.wavepacket.set_offset_to_data(new_val)
+    }
+
+    fn set_packet_size(&mut self, new_val: u32) {
+        self.wavepacket.set_packet_size(new_val)
+    }
+
+    fn set_return_point(&mut self, new_val: f32) {
+        self.wavepacket.set_return_point(new_val)
+    }
+
+    fn set_x_t(&mut self, new_val: f32) {
+        self.wavepacket.set_x_t(new_val)
+    }
+
+    fn set_y_t(&mut self, new_val: f32) {
+        self.wavepacket.set_y_t(new_val)
+    }
+
+    fn set_z_t(&mut self, new_val: f32) {
+        self.wavepacket.set_z_t(new_val)
+    }
+}
+
+impl Version3 for Point9 {
+    fn version_3(num_extra_bytes: u16) -> Vec<LazItem> {
+        vec_of_laz_items![
+            vec_capacity: 3,
+            version: 3,
+            extra_bytes_type: LazItemType::Byte14(num_extra_bytes),
+            LazItemType::Point14,
+            LazItemType::WavePacket14
+        ]
+    }
+}
+
+impl Version4 for Point9 {
+    fn version_4(num_extra_bytes: u16) -> Vec<LazItem> {
+        vec_of_laz_items![
+            vec_capacity: 3,
+            version: 4,
+            extra_bytes_type: LazItemType::Byte14(num_extra_bytes),
+            LazItemType::Point14,
+            LazItemType::WavePacket14
+        ]
+    }
+}
+
+impl DefaultVersion for Point9 {
+    fn default_version(num_extra_bytes: u16) -> Vec<LazItem> {
+        Self::version_3(num_extra_bytes)
+    }
+}
+
+/***************************************************************************************************
+                    Point Format 10
+***************************************************************************************************/
+
+#[derive(Default, Debug, PartialEq, Copy, Clone)]
+pub struct Point10 {
+    base: Point6,
+    rgb: RGB,
+    nir: Nir,
+    wavepacket: Wavepacket,
+}
+
+impl Point6Based for Point10 {
+    fn point6(&self) -> &Point6 {
+        &self.base
+    }
+
+    fn point6_mut(&mut self) -> &mut Point6 {
+        &mut self.base
+    }
+}
+
+impl LasRGB for Point10 {
+    fn red(&self) -> u16 {
+        self.rgb.red()
+    }
+
+    fn green(&self) -> u16 {
+        self.rgb.green()
+    }
+
+    fn blue(&self) -> u16 {
+        self.rgb.blue()
+    }
+
+    fn set_red(&mut self, new_val: u16) {
+        self.rgb.set_red(new_val)
+    }
+
+    fn set_green(&mut self, new_val: u16) {
+        self.rgb.set_green(new_val)
+    }
+
+    fn set_blue(&mut self, new_val: u16) {
+        self.rgb.set_blue(new_val)
+    }
+}
+
+impl LasWavepacket for Point10 {
+    fn descriptor_index(&self) -> u8 {
+        self.wavepacket.descriptor_index()
+    }
+
+    fn offset_to_data(&self) -> u64 {
+        self.wavepacket.offset_to_data()
+    }
+
+    fn packet_size(&self) -> u32 {
+        self.wavepacket.packet_size()
+    }
+
+    fn return_point(&self) -> f32 {
+        self.wavepacket.return_point()
+    }
+
+    fn x_t(&self) -> f32 {
+        self.wavepacket.x_t()
+    }
+
+    fn y_t(&self) -> f32 {
+        self.wavepacket.y_t()
+    }
+
+    fn z_t(&self) -> f32 {
+        self.wavepacket.z_t()
+    }
+
+    fn set_descriptor_index(&mut self, new_val: u8) {
+        self.wavepacket.set_descriptor_index(new_val)
+    }
+
+    fn set_offset_to_data(&mut self, new_val: u64) {
+        self.wavepacket.set_offset_to_data(new_val)
+    }
+
+    fn set_packet_size(&mut self, new_val: u32) {
+        self.wavepacket.set_packet_size(new_val)
+    }
+
+    fn set_return_point(&mut self, new_val: f32) {
+        self.wavepacket.set_return_point(new_val)
+    }
+
+    fn set_x_t(&mut self, new_val: f32) {
+        self.wavepacket.set_x_t(new_val)
+    }
+
+    fn set_y_t(&mut self, new_val: f32) {
+        self.wavepacket.set_y_t(new_val)
+    }
+
+    fn set_z_t(&mut self, new_val: f32) {
+        self.wavepacket.set_z_t(new_val)
+    }
+}
+
+impl Version3 for Point10 {
+    fn version_3(num_extra_bytes: u16) -> Vec<LazItem> {
+        vec_of_laz_items![
+            vec_capacity: 4,
+            version: 3,
+            extra_bytes_type: LazItemType::Byte14(num_extra_bytes),
+            LazItemType::Point14,
+            LazItemType::RGBNIR14,
+            LazItemType::WavePacket14
+        ]
+    }
+}
+
+impl Version4 for Point10 {
+    fn version_4(num_extra_bytes: u16) -> Vec<LazItem> {
+        vec_of_laz_items![
+            vec_capacity: 4,
+            version: 4,
+            extra_bytes_type: LazItemType::Byte14(num_extra_bytes),
+            LazItemType::Point14,
+            LazItemType::RGBNIR14,
+            LazItemType::WavePacket14
+        ]
+    }
+}
+
+impl DefaultVersion for Point10 {
+    fn default_version(num_extra_bytes: u16) -> Vec<LazItem> {
+        Self::version_3(num_extra_bytes)
+    }
+}
+
 /***************************************************************************************************
                     Auto implementation of some traits
 ***************************************************************************************************/
@@ -172,6 +172,7 @@ pub mod v1 {
     use crate::encoders::ArithmeticEncoder;
     use crate::las::rgb::LasRGB;
     use crate::las::utils::{lower_byte, read_and_unpack, upper_byte};
+    use crate::las::PointColumns;
     use crate::models::{ArithmeticModel, ArithmeticModelBuilder};
     use crate::packers::Packable;
     use crate::record::{FieldCompressor, FieldDecompressor};
@@ -309,6 +310,10 @@ pub mod v1 {
             self.last.pack_into(buf);
             Ok(())
         }
+
+        fn push_last_to_columns(&self, columns: &mut PointColumns) {
+            columns.rgb.push(self.last);
+        }
     }
 
     impl<W: Write> FieldCompressor<W> for LasRGBCompressor {
@@ -404,6 +409,7 @@ pub mod v2 {
     use crate::encoders::ArithmeticEncoder;
     use crate::las::rgb::LasRGB;
     use crate::las::utils::{lower_byte, read_and_unpack, u8_clamp, upper_byte};
+    use crate::las::PointColumns;
     use crate::models::{ArithmeticModel, ArithmeticModelBuilder};
     use crate::packers::Packable;
     use crate::record::{FieldCompressor, FieldDecompressor};
@@ -639,6 +645,10 @@ pub mod v2 {
             this_val.pack_into(buf);
             Ok(())
         }
+
+        fn push_last_to_columns(&self, columns: &mut PointColumns) {
+            columns.rgb.push(self.last);
+        }
     }
 }
 
@@ -656,10 +666,12 @@ pub mod v3 {
 
     use crate::decoders::ArithmeticDecoder;
     use crate::encoders::ArithmeticEncoder;
+    use crate::las::point6::DecompressionSelector;
     use crate::las::rgb::RGB;
     use crate::las::utils::{
         copy_bytes_into_decoder, copy_encoder_content_to, inner_buffer_len_of, read_and_unpack,
     };
+    use crate::las::PointColumns;
     use crate::packers::Packable;
     use crate::record::{LayeredFieldCompressor, LayeredFieldDecompressor};
 
@@ -690,8 +702,29 @@ pub mod v3 {
         // rgb context, not sure if its truly intentional, or if its a 'bug' in laszip
         contexts: [LasDecompressionContextRGB; 4],
         last_rgbs: [RGB; 4],
+        // The RGB of the last decompressed point,
+        // which of the last_rgbs it is depends on the version
+        last_rgb: RGB,
 
         last_context_used: usize,
+        // true for the version 4, see crate::las::v4
+        last_item_follows_context: bool,
+    }
+
+    impl LasRGBDecompressor {
+        pub fn selective(selector: DecompressionSelector) -> Self {
+            Self {
+                requested_rgb: selector.rgb_requested(),
+                ..Self::default()
+            }
+        }
+
+        pub(super) fn for_version_4(selector: DecompressionSelector) -> Self {
+            Self {
+                last_item_follows_context: true,
+                ..Self::selective(selector)
+            }
+        }
     }
 
     impl Default for LasRGBDecompressor {
@@ -708,7 +741,9 @@ pub mod v3 {
                     LasDecompressionContextRGB::default(),
                 ],
                 last_rgbs: [RGB::default(); 4],
+                last_rgb: RGB::default(),
                 last_context_used: 0,
+                last_item_follows_context: false,
             }
         }
     }
@@ -731,6 +766,9 @@ pub mod v3 {
             self.last_rgbs[*context] = read_and_unpack::<_, RGB>(src, first_point)?;
             self.contexts[*context].unused = false;
             self.last_context_used = *context;
+            if !self.requested_rgb {
+                first_point.fill(0);
+            }
             Ok(())
         }
 
@@ -739,6 +777,11 @@ pub mod v3 {
             current_point: &mut [u8],
             context: &mut usize,
         ) -> std::io::Result<()> {
+            if !self.requested_rgb {
+                current_point.fill(0);
+                return Ok(());
+            }
+
             let mut last_item = &mut self.last_rgbs[self.last_context_used];
 
             // If the context changed we may have to do an initialization
@@ -748,6 +791,8 @@ pub mod v3 {
                     self.last_rgbs[*context] = *last_item;
                     self.contexts[*context].unused = false;
 
+                    last_item = &mut self.last_rgbs[*context];
+                } else if self.last_item_follows_context {
                     last_item = &mut self.last_rgbs[*context];
                 }
             }
@@ -763,6 +808,7 @@ pub mod v3 {
             } else {
                 last_item.pack_into(current_point);
             }
+            self.last_rgb = *last_item;
 
             Ok(())
         }
@@ -781,6 +827,14 @@ pub mod v3 {
             )?;
             Ok(())
         }
+
+        fn push_last_to_columns(&self, columns: &mut PointColumns) {
+            if self.requested_rgb {
+                columns.rgb.push(self.last_rgb);
+            } else {
+                columns.rgb.push(RGB::default());
+            }
+        }
     }
 
     pub struct LasRGBCompressor {
@@ -789,6 +843,17 @@ pub mod v3 {
         contexts: [Option<v2::RGBModels>; 4],
         last_rgbs: [Option<RGB>; 4],
         last_context_used: usize,
+        // Same as in the decompressor
+        last_item_follows_context: bool,
+    }
+
+    impl LasRGBCompressor {
+        pub(super) fn for_version_4() -> Self {
+            Self {
+                last_item_follows_context: true,
+                ..Self::default()
+            }
+        }
     }
 
     impl Default for LasRGBCompressor {
@@ -799,6 +864,7 @@ pub mod v3 {
                 contexts: [None, None, None, None],
                 last_rgbs: [None; 4],
                 last_context_used: 0,
+                last_item_follows_context: false,
             }
         }
     }
@@ -832,6 +898,10 @@ pub mod v3 {
                     self.contexts[*context] = Some(v2::RGBModels::default());
                     self.last_rgbs[*context] = Some(*last_rgb);
                     last_rgb = self.last_rgbs[*context].as_mut().unwrap();
+                } else if self.last_item_follows_context {
+                    last_rgb = self.last_rgbs[*context]
+                        .as_mut()
+                        .expect("internal error: last value is not initialized");
                 }
                 self.last_context_used = *context;
             }
@@ -864,6 +934,39 @@ pub mod v3 {
     }
 }
 
+pub mod v4 {
+    //! Contains the implementation for the Version 4 of the RGB Compression / Decompression
+    use crate::las::point6::DecompressionSelector;
+
+    use super::v3;
+
+    pub struct LasRGBDecompressor(v3::LasRGBDecompressor);
+
+    impl LasRGBDecompressor {
+        pub fn selective(selector: DecompressionSelector) -> Self {
+            Self(v3::LasRGBDecompressor::for_version_4(selector))
+        }
+    }
+
+    impl Default for LasRGBDecompressor {
+        fn default() -> Self {
+            Self::selective(DecompressionSelector::decompress_all())
+        }
+    }
+
+    forward_layered_field_decompressor_impl!(LasRGBDecompressor);
+
+    pub struct LasRGBCompressor(v3::LasRGBCompressor);
+
+    impl Default for LasRGBCompressor {
+        fn default() -> Self {
+            Self(v3::LasRGBCompressor::for_version_4())
+        }
+    }
+
+    forward_layered_field_compressor_impl!(LasRGBCompressor);
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -207,6 +207,98 @@ macro_rules! is_nth_bit_set {
     };
 }
 
+/// Implements [`LayeredFieldDecompressor`] for a new type
+/// by forwarding every call to the wrapped decompressor.
+///
+/// [`LayeredFieldDecompressor`]: crate::record::LayeredFieldDecompressor
+macro_rules! forward_layered_field_decompressor_impl {
+    ($Type:ty) => {
+        impl<R: std::io::Read + std::io::Seek> crate::record::LayeredFieldDecompressor<R>
+            for $Type
+        {
+            fn size_of_field(&self) -> usize {
+                crate::record::LayeredFieldDecompressor::<R>::size_of_field(&self.0)
+            }
+
+            fn init_first_point(
+                &mut self,
+                src: &mut R,
+                first_point: &mut [u8],
+                context: &mut usize,
+            ) -> std::io::Result<()> {
+                self.0.init_first_point(src, first_point, context)
+            }
+
+            fn decompress_field_with(
+                &mut self,
+                current_point: &mut [u8],
+                context: &mut usize,
+            ) -> std::io::Result<()> {
+                crate::record::LayeredFieldDecompressor::<R>::decompress_field_with(
+                    &mut self.0,
+                    current_point,
+                    context,
+                )
+            }
+
+            fn read_layers_sizes(&mut self, src: &mut R) -> std::io::Result<()> {
+                self.0.read_layers_sizes(src)
+            }
+
+            fn read_layers(&mut self, src: &mut R) -> std::io::Result<()> {
+                self.0.read_layers(src)
+            }
+
+            fn push_last_to_columns(&self, columns: &mut crate::las::PointColumns) {
+                crate::record::LayeredFieldDecompressor::<R>::push_last_to_columns(&self.0, columns)
+            }
+        }
+    };
+}
+
+/// Implements [`LayeredFieldCompressor`] for a new type
+/// by forwarding every call to the wrapped compressor.
+///
+/// [`LayeredFieldCompressor`]: crate::record::LayeredFieldCompressor
+macro_rules! forward_layered_field_compressor_impl {
+    ($Type:ty) => {
+        impl<W: std::io::Write> crate::record::LayeredFieldCompressor<W> for $Type {
+            fn size_of_field(&self) -> usize {
+                crate::record::LayeredFieldCompressor::<W>::size_of_field(&self.0)
+            }
+
+            fn init_first_point(
+                &mut self,
+                dst: &mut W,
+                first_point: &[u8],
+                context: &mut usize,
+            ) -> std::io::Result<()> {
+                self.0.init_first_point(dst, first_point, context)
+            }
+
+            fn compress_field_with(
+                &mut self,
+                current_point: &[u8],
+                context: &mut usize,
+            ) -> std::io::Result<()> {
+                crate::record::LayeredFieldCompressor::<W>::compress_field_with(
+                    &mut self.0,
+                    current_point,
+                    context,
+                )
+            }
+
+            fn write_layers_sizes(&mut self, dst: &mut W) -> std::io::Result<()> {
+                self.0.write_layers_sizes(dst)
+            }
+
+            fn write_layers(&mut self, dst: &mut W) -> std::io::Result<()> {
+                self.0.write_layers(dst)
+            }
+        }
+    };
+}
+
 // for LAS files with the return (r) and the number (n) of
 // returns field correctly populated the mapping should really
 // be only the following.
@@ -0,0 +1,704 @@
+/*
+===============================================================================
+
+  PROGRAMMERS:
+
+    martin.isenburg@rapidlasso.com  -  http://rapidlasso.com
+    uday.karan@gmail.com - Hobu, Inc.
+
+  COPYRIGHT:
+
+    (c) 2007-2014, martin isenburg, rapidlasso - tools to catch reality
+    (c) 2014, Uday Verma, Hobu, Inc.
+    (c) 2019, Thomas Montaigu
+
+    This is free software; you can redistribute and/or modify it under the
+    terms of the Apache Public License 2.0 published by the Apache Software
+    Foundation. See the COPYING file for more information.
+
+    This software is distributed WITHOUT ANY WARRANTY and without even the
+    implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+
+  CHANGE HISTORY:
+    6 June 2019: Translated to Rust
+===============================================================================
+*/
+//! Defines the different version of compressors and decompressors for the wave packet
+//! descriptor found in point formats 4, 5, 9 and 10
+
+use crate::packers::Packable;
+
+/// LASzip never defined a version 2 of the WavePacket13 item,
+/// files written with version 2 of the other items still use version 1
+pub use v1 as v2;
+
+pub trait LasWavepacket {
+    fn descriptor_index(&self) -> u8;
+    fn offset_to_data(&self) -> u64;
+    fn packet_size(&self) -> u32;
+    fn return_point(&self) -> f32;
+    fn x_t(&self) -> f32;
+    fn y_t(&self) -> f32;
+    fn z_t(&self) -> f32;
+
+    fn set_descriptor_index(&mut self, new_val: u8);
+    fn set_offset_to_data(&mut self, new_val: u64);
+    fn set_packet_size(&mut self, new_val: u32);
+    fn set_return_point(&mut self, new_val: f32);
+    fn set_x_t(&mut self, new_val: f32);
+    fn set_y_t(&mut self, new_val: f32);
+    fn set_z_t(&mut self, new_val: f32);
+}
+
+/// The wave packet descriptor of a point
+///
+/// The float fields are stored as their raw bits, as this is how
+/// they are compressed.
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub struct Wavepacket {
+    pub descriptor_index: u8,
+    pub offset_to_data: u64,
+    pub packet_size: u32,
+    pub return_point: u32,
+    pub x_t: u32,
+    pub y_t: u32,
+    pub z_t: u32,
+}
+
+impl Wavepacket {
+    pub const SIZE: usize = 29;
+}
+
+impl LasWavepacket for Wavepacket {
+    fn descriptor_index(&self) -> u8 {
+        self.descriptor_index
+    }
+
+    fn offset_to_data(&self) -> u64 {
+        self.offset_to_data
+    }
+
+    fn packet_size(&self) -> u32 {
+        self.packet_size
+    }
+
+    fn return_point(&self) -> f32 {
+        f32::from_bits(self.return_point)
+    }
+
+    fn x_t(&self) -> f32 {
+        f32::from_bits(self.x_t)
+    }
+
+    fn y_t(&self) -> f32 {
+        f32::from_bits(self.y_t)
+    }
+
+    fn z_t(&self) -> f32 {
+        f32::from_bits(self.z_t)
+    }
+
+    fn set_descriptor_index(&mut self, new_val: u8) {
+        self.descriptor_index = new_val;
+    }
+
+    fn set_offset_to_data(&mut self, new_val: u64) {
+        self.offset_to_data = new_val;
+    }
+
+    fn set_packet_size(&mut self, new_val: u32) {
+        self.packet_size = new_val;
+    }
+
+    fn set_return_point(&mut self, new_val: f32) {
+        self.return_point = new_val.to_bits();
+    }
+
+    fn set_x_t(&mut self, new_val: f32) {
+        self.x_t = new_val.to_bits();
+    }
+
+    fn set_y_t(&mut self, new_val: f32) {
+        self.y_t = new_val.to_bits();
+    }
+
+    fn set_z_t(&mut self, new_val: f32) {
+        self.z_t = new_val.to_bits();
+    }
+}
+
+impl Packable for Wavepacket {
+    fn unpack_from(input: &[u8]) -> Self {
+        assert!(
+            input.len() >= Self::SIZE,
+            "Wavepacket::unpack_from expected a buffer of 29 bytes"
+        );
+        unsafe { Self::unpack_from_unchecked(input) }
+    }
+
+    fn pack_into(&self, output: &mut [u8]) {
+        assert!(
+            output.len() >= Self::SIZE,
+            "Wavepacket::pack_into expected a buffer of 29 bytes"
+        );
+        unsafe { self.pack_into_unchecked(output) }
+    }
+
+    unsafe fn unpack_from_unchecked(input: &[u8]) -> Self {
+        let lower = u32::unpack_from_unchecked(input.get_unchecked(1..5));
+        let upper = u32::unpack_from_unchecked(input.get_unchecked(5..9));
+        Self {
+            descriptor_index: u8::unpack_from_unchecked(input.get_unchecked(0..1)),
+            offset_to_data: u64::from(upper) << 32 | u64::from(lower),
+            packet_size: u32::unpack_from_unchecked(input.get_unchecked(9..13)),
+            return_point: u32::unpack_from_unchecked(input.get_unchecked(13..17)),
+            x_t: u32::unpack_from_unchecked(input.get_unchecked(17..21)),
+            y_t: u32::unpack_from_unchecked(input.get_unchecked(21..25)),
+            z_t: u32::unpack_from_unchecked(input.get_unchecked(25..29)),
+        }
+    }
+
+    unsafe fn pack_into_unchecked(&self, output: &mut [u8]) {
+        u8::pack_into_unchecked(&self.descriptor_index, output.get_unchecked_mut(0..1));
+        u32::pack_into_unchecked(
+            &((self.offset_to_data & 0xFFFF_FFFF) as u32),
+            output.get_unchecked_mut(1..5),
+        );
+        u32::pack_into_unchecked(
+            &((self.offset_to_data >> 32) as u32),
+            output.get_unchecked_mut(5..9),
+        );
+        u32::pack_into_unchecked(&self.packet_size, output.get_unchecked_mut(9..13));
+        u32::pack_into_unchecked(&self.return_point, output.get_unchecked_mut(13..17));
+        u32::pack_into_unchecked(&self.x_t, output.get_unchecked_mut(17..21));
+        u32::pack_into_unchecked(&self.y_t, output.get_unchecked_mut(21..25));
+        u32::pack_into_unchecked(&self.z_t, output.get_unchecked_mut(25..29));
+    }
+}
+
+pub mod v1 {
+    //! The offset to the waveform data is predicted from the previous
+    //! descriptor (same offset, offset + packet size, or a 32 bit difference),
+    //! the other fields are compressed as integer differences.
+    use std::io::{Read, Write};
+
+    use crate::compressors::{IntegerCompressor, IntegerCompressorBuilder};
+    use crate::decoders::ArithmeticDecoder;
+    use crate::decompressors::{IntegerDecompressor, IntegerDecompressorBuilder};
+    use crate::encoders::ArithmeticEncoder;
+    use crate::las::utils::read_and_unpack;
+    use crate::models::{ArithmeticModel, ArithmeticModelBuilder};
+    use crate::packers::Packable;
+    use crate::record::{FieldCompressor, FieldDecompressor};
+
+    use super::Wavepacket;
+
+    fn offset_diff_models() -> [ArithmeticModel; 4] {
+        [
+            ArithmeticModelBuilder::new(4).build(),
+            ArithmeticModelBuilder::new(4).build(),
+            ArithmeticModelBuilder::new(4).build(),
+            ArithmeticModelBuilder::new(4).build(),
+        ]
+    }
+
+    pub(super) struct WavepacketDecompressionModels {
+        last_diff_32: i32,
+        sym_last_offset_diff: u32,
+        packet_index: ArithmeticModel,
+        offset_diff: [ArithmeticModel; 4],
+        ic_offset_diff: IntegerDecompressor,
+        ic_packet_size: IntegerDecompressor,
+        ic_return_point: IntegerDecompressor,
+        ic_xyz: IntegerDecompressor,
+    }
+
+    impl Default for WavepacketDecompressionModels {
+        fn default() -> Self {
+            Self {
+                last_diff_32: 0,
+                sym_last_offset_diff: 0,
+                packet_index: ArithmeticModelBuilder::new(256).build(),
+                offset_diff: offset_diff_models(),
+                ic_offset_diff: IntegerDecompressorBuilder::new()
+                    .bits(32)
+                    .build_initialized(),
+                ic_packet_size: IntegerDecompressorBuilder::new()
+                    .bits(32)
+                    .build_initialized(),
+                ic_return_point: IntegerDecompressorBuilder::new()
+                    .bits(32)
+                    .build_initialized(),
+                ic_xyz: IntegerDecompressorBuilder::new()
+                    .bits(32)
+                    .contexts(3)
+                    .build_initialized(),
+            }
+        }
+    }
+
+    pub(super) struct WavepacketCompressionModels {
+        last_diff_32: i32,
+        sym_last_offset_diff: u32,
+        packet_index: ArithmeticModel,
+        offset_diff: [ArithmeticModel; 4],
+        ic_offset_diff: IntegerCompressor,
+        ic_packet_size: IntegerCompressor,
+        ic_return_point: IntegerCompressor,
+        ic_xyz: IntegerCompressor,
+    }
+
+    impl Default for WavepacketCompressionModels {
+        fn default() -> Self {
+            Self {
+                last_diff_32: 0,
+                sym_last_offset_diff: 0,
+                packet_index: ArithmeticModelBuilder::new(256).build(),
+                offset_diff: offset_diff_models(),
+                ic_offset_diff: IntegerCompressorBuilder::new().bits(32).build_initialized(),
+                ic_packet_size: IntegerCompressorBuilder::new().bits(32).build_initialized(),
+                ic_return_point: IntegerCompressorBuilder::new().bits(32).build_initialized(),
+                ic_xyz: IntegerCompressorBuilder::new()
+                    .bits(32)
+                    .contexts(3)
+                    .build_initialized(),
+            }
+        }
+    }
+
+    pub(super) fn decompress_wavepacket_using<R: Read>(
+        decoder: &mut ArithmeticDecoder<R>,
+        models: &mut WavepacketDecompressionModels,
+        last: &Wavepacket,
+    ) -> std::io::Result<Wavepacket> {
+        let mut current = Wavepacket {
+            descriptor_index: decoder.decode_symbol(&mut models.packet_index)? as u8,
+            ..Default::default()
+        };
+
+        models.sym_last_offset_diff =
+            decoder.decode_symbol(&mut models.offset_diff[models.sym_last_offset_diff as usize])?;
+
+        current.offset_to_data = match models.sym_last_offset_diff {
+            0 => last.offset_to_data,
+            1 => last
+                .offset_to_data
+                .wrapping_add(u64::from(last.packet_size)),
+            2 => {
+                models.last_diff_32 =
+                    models
+                        .ic_offset_diff
+                        .decompress(decoder, models.last_diff_32, 0)?;
+                last.offset_to_data
+                    .wrapping_add(models.last_diff_32 as i64 as u64)
+            }
+            _ => decoder.read_int_64()?,
+        };
+
+        current.packet_size =
+            models
+                .ic_packet_size
+                .decompress(decoder, last.packet_size as i32, 0)? as u32;
+        current.return_point =
+            models
+                .ic_return_point
+                .decompress(decoder, last.return_point as i32, 0)? as u32;
+        current.x_t = models.ic_xyz.decompress(decoder, last.x_t as i32, 0)? as u32;
+        current.y_t = models.ic_xyz.decompress(decoder, last.y_t as i32, 1)? as u32;
+        current.z_t = models.ic_xyz.decompress(decoder, last.z_t as i32, 2)? as u32;
+        Ok(current)
+    }
+
+    pub(super) fn compress_wavepacket_using<W: Write>(
+        encoder: &mut ArithmeticEncoder<W>,
+        models: &mut WavepacketCompressionModels,
+        current: &Wavepacket,
+        last: &Wavepacket,
+    ) -> std::io::Result<()> {
+        encoder.encode_symbol(
+            &mut models.packet_index,
+            u32::from(current.descriptor_index),
+        )?;
+
+        let curr_diff_64 = current.offset_to_data.wrapping_sub(last.offset_to_data) as i64;
+        let curr_diff_32 = curr_diff_64 as i32;
+
+        let sym = if curr_diff_64 == i64::from(curr_diff_32) {
+            // the difference can be represented with 32 bits
+            if curr_diff_32 == 0 {
+                0
+            } else if curr_diff_32 == last.packet_size as i32 {
+                1
+            } else {
+                2
+            }
+        } else {
+            3
+        };
+
+        encoder.encode_symbol(
+            &mut models.offset_diff[models.sym_last_offset_diff as usize],
+            sym,
+        )?;
+        models.sym_last_offset_diff = sym;
+
+        if sym == 2 {
+            models
+                .ic_offset_diff
+                .compress(encoder, models.last_diff_32, curr_diff_32, 0)?;
+            models.last_diff_32 = curr_diff_32;
+        } else if sym == 3 {
+            encoder.write_int64(current.offset_to_data)?;
+        }
+
+        models.ic_packet_size.compress(
+            encoder,
+            last.packet_size as i32,
+            current.packet_size as i32,
+            0,
+        )?;
+        models.ic_return_point.compress(
+            encoder,
+            last.return_point as i32,
+            current.return_point as i32,
+            0,
+        )?;
+        models
+            .ic_xyz
+            .compress(encoder, last.x_t as i32, current.x_t as i32, 0)?;
+        models
+            .ic_xyz
+            .compress(encoder, last.y_t as i32, current.y_t as i32, 1)?;
+        models
+            .ic_xyz
+            .compress(encoder, last.z_t as i32, current.z_t as i32, 2)?;
+        Ok(())
+    }
+
+    #[derive(Default)]
+    pub struct LasWavepacketDecompressor {
+        last_wavepacket: Wavepacket,
+        models: WavepacketDecompressionModels,
+    }
+
+    impl<R: Read> FieldDecompressor<R> for LasWavepacketDecompressor {
+        fn size_of_field(&self) -> usize {
+            Wavepacket::SIZE
+        }
+
+        fn decompress_first(&mut self, src: &mut R, first_point: &mut [u8]) -> std::io::Result<()> {
+            self.last_wavepacket = read_and_unpack::<_, Wavepacket>(src, first_point)?;
+            Ok(())
+        }
+
+        fn decompress_with(
+            &mut self,
+            decoder: &mut ArithmeticDecoder<R>,
+            buf: &mut [u8],
+        ) -> std::io::Result<()> {
+            let current =
+                decompress_wavepacket_using(decoder, &mut self.models, &self.last_wavepacket)?;
+            current.pack_into(buf);
+            self.last_wavepacket = current;
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    pub struct LasWavepacketCompressor {
+        last_wavepacket: Wavepacket,
+        models: WavepacketCompressionModels,
+    }
+
+    impl<W: Write> FieldCompressor<W> for LasWavepacketCompressor {
+        fn size_of_field(&self) -> usize {
+            Wavepacket::SIZE
+        }
+
+        fn compress_first(&mut self, dst: &mut W, buf: &[u8]) -> std::io::Result<()> {
+            self.last_wavepacket = Wavepacket::unpack_from(buf);
+            dst.write_all(&buf[..Wavepacket::SIZE])
+        }
+
+        fn compress_with(
+            &mut self,
+            encoder: &mut ArithmeticEncoder<W>,
+            buf: &[u8],
+        ) -> std::io::Result<()> {
+            let current = Wavepacket::unpack_from(buf);
+            compress_wavepacket_using(encoder, &mut self.models, &current, &self.last_wavepacket)?;
+            self.last_wavepacket = current;
+            Ok(())
+        }
+    }
+}
+
+pub mod v3 {
+    //! Contains the implementation for the Version 3 of the wave packet Compression / Decompression
+    //!
+    //! The version 3 of the compression / decompression algorithm
+    //! is the same as the version 1, but with the support for the contexts system
+    //! and the data is stored in its own layer.
+    use std::io::{Cursor, Read, Seek, Write};
+
+    use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+    use crate::decoders::ArithmeticDecoder;
+    use crate::encoders::ArithmeticEncoder;
+    use crate::las::point6::DecompressionSelector;
+    use crate::las::utils::{
+        copy_bytes_into_decoder, copy_encoder_content_to, inner_buffer_len_of, read_and_unpack,
+    };
+    use crate::packers::Packable;
+    use crate::record::{LayeredFieldCompressor, LayeredFieldDecompressor};
+
+    use super::v1::{
+        compress_wavepacket_using, decompress_wavepacket_using, WavepacketCompressionModels,
+        WavepacketDecompressionModels,
+    };
+    use super::Wavepacket;
+
+    pub struct LasWavepacketDecompressor {
+        decoder: ArithmeticDecoder<Cursor<Vec<u8>>>,
+        changed_wavepacket: bool,
+        requested_wavepacket: bool,
+        layer_size: u32,
+        // Last & contexts are separated for the same reasons as in v3::RGB
+        contexts: [Option<WavepacketDecompressionModels>; 4],
+        last_wavepackets: [Wavepacket; 4],
+        last_context_used: usize,
+        // true for the version 4, see crate::las::v4
+        last_item_follows_context: bool,
+    }
+
+    impl LasWavepacketDecompressor {
+        pub fn selective(selector: DecompressionSelector) -> Self {
+            Self {
+                requested_wavepacket: selector.wavepacket_requested(),
+                ..Self::default()
+            }
+        }
+
+        pub(super) fn for_version_4(selector: DecompressionSelector) -> Self {
+            Self {
+                last_item_follows_context: true,
+                ..Self::selective(selector)
+            }
+        }
+    }
+
+    impl Default for LasWavepacketDecompressor {
+        fn default() -> Self {
+            Self {
+                decoder: ArithmeticDecoder::new(Cursor::new(Vec::<u8>::new())),
+                changed_wavepacket: false,
+                requested_wavepacket: true,
+                layer_size: 0,
+                contexts: [None, None, None, None],
+                last_wavepackets: [Wavepacket::default(); 4],
+                last_context_used: 0,
+                last_item_follows_context: false,
+            }
+        }
+    }
+
+    impl<R: Read + Seek> LayeredFieldDecompressor<R> for LasWavepacketDecompressor {
+        fn size_of_field(&self) -> usize {
+            Wavepacket::SIZE
+        }
+
+        fn init_first_point(
+            &mut self,
+            src: &mut R,
+            first_point: &mut [u8],
+            context: &mut usize,
+        ) -> std::io::Result<()> {
+            for wavepacket_context in &mut self.contexts {
+                *wavepacket_context = None;
+            }
+            self.last_wavepackets[*context] = read_and_unpack::<_, Wavepacket>(src, first_point)?;
+            self.contexts[*context] = Some(WavepacketDecompressionModels::default());
+            self.last_context_used = *context;
+            if !self.requested_wavepacket {
+                first_point.fill(0);
+            }
+            Ok(())
+        }
+
+        fn decompress_field_with(
+            &mut self,
+            current_point: &mut [u8],
+            context: &mut usize,
+        ) -> std::io::Result<()> {
+            if !self.requested_wavepacket {
+                current_point.fill(0);
+                return Ok(());
+            }
+
+            let mut last_wavepacket = &mut self.last_wavepackets[self.last_context_used];
+            if self.last_context_used != *context {
+                self.last_context_used = *context;
+                if self.contexts[*context].is_none() {
+                    self.contexts[*context] = Some(WavepacketDecompressionModels::default());
+                    self.last_wavepackets[*context] = *last_wavepacket;
+                    last_wavepacket = &mut self.last_wavepackets[*context];
+                } else if self.last_item_follows_context {
+                    last_wavepacket = &mut self.last_wavepackets[*context];
+                }
+            }
+
+            if self.changed_wavepacket {
+                let models = self.contexts[self.last_context_used]
+                    .as_mut()
+                    .expect("internal error: context is not initialized");
+                let new = decompress_wavepacket_using(&mut self.decoder, models, last_wavepacket)?;
+                *last_wavepacket = new;
+            }
+            last_wavepacket.pack_into(current_point);
+            Ok(())
+        }
+
+        fn read_layers_sizes(&mut self, src: &mut R) -> std::io::Result<()> {
+            self.layer_size = src.read_u32::<LittleEndian>()?;
+            Ok(())
+        }
+
+        fn read_layers(&mut self, src: &mut R) -> std::io::Result<()> {
+            self.changed_wavepacket = copy_bytes_into_decoder(
+                self.requested_wavepacket,
+                self.layer_size as usize,
+                &mut self.decoder,
+                src,
+            )?;
+            Ok(())
+        }
+    }
+
+    pub struct LasWavepacketCompressor {
+        encoder: ArithmeticEncoder<Cursor<Vec<u8>>>,
+        wavepacket_has_changed: bool,
+        contexts: [Option<WavepacketCompressionModels>; 4],
+        last_wavepackets: [Wavepacket; 4],
+        last_context_used: usize,
+        // Same as in the decompressor
+        last_item_follows_context: bool,
+    }
+
+    impl LasWavepacketCompressor {
+        pub(super) fn for_version_4() -> Self {
+            Self {
+                last_item_follows_context: true,
+                ..Self::default()
+            }
+        }
+    }
+
+    impl Default for LasWavepacketCompressor {
+        fn default() -> Self {
+            Self {
+                encoder: ArithmeticEncoder::new(Cursor::new(Vec::<u8>::new())),
+                wavepacket_has_changed: false,
+                contexts: [None, None, None, None],
+                last_wavepackets: [Wavepacket::default(); 4],
+                last_context_used: 0,
+                last_item_follows_context: false,
+            }
+        }
+    }
+
+    impl<W: Write> LayeredFieldCompressor<W> for LasWavepacketCompressor {
+        fn size_of_field(&self) -> usize {
+            Wavepacket::SIZE
+        }
+
+        fn init_first_point(
+            &mut self,
+            dst: &mut W,
+            first_point: &[u8],
+            context: &mut usize,
+        ) -> std::io::Result<()> {
+            dst.write_all(first_point)?;
+            self.contexts[*context] = Some(WavepacketCompressionModels::default());
+            self.last_wavepackets[*context] = Wavepacket::unpack_from(first_point);
+            self.last_context_used = *context;
+            Ok(())
+        }
+
+        fn compress_field_with(&mut self, buf: &[u8], context: &mut usize) -> std::io::Result<()> {
+            let current = Wavepacket::unpack_from(buf);
+            let mut last_wavepacket = &mut self.last_wavepackets[self.last_context_used];
+            if self.last_context_used != *context {
+                self.last_context_used = *context;
+                if self.contexts[*context].is_none() {
+                    self.contexts[*context] = Some(WavepacketCompressionModels::default());
+                    self.last_wavepackets[*context] = *last_wavepacket;
+                    last_wavepacket = &mut self.last_wavepackets[*context];
+                } else if self.last_item_follows_context {
+                    last_wavepacket = &mut self.last_wavepackets[*context];
+                }
+            }
+
+            if *last_wavepacket != current {
+                self.wavepacket_has_changed = true;
+            }
+            let models = self.contexts[self.last_context_used]
+                .as_mut()
+                .expect("internal error: context is not initialized");
+            compress_wavepacket_using(&mut self.encoder, models, &current, last_wavepacket)?;
+            *last_wavepacket = current;
+            Ok(())
+        }
+
+        fn write_layers_sizes(&mut self, dst: &mut W) -> std::io::Result<()> {
+            // Only the descriptor of the first point is stored when they are all the same
+            let layer_size = if self.wavepacket_has_changed {
+                self.encoder.done()?;
+                inner_buffer_len_of(&self.encoder)
+            } else {
+                0
+            };
+            dst.write_u32::<LittleEndian>(layer_size as u32)?;
+            Ok(())
+        }
+
+        fn write_layers(&mut self, dst: &mut W) -> std::io::Result<()> {
+            if self.wavepacket_has_changed {
+                copy_encoder_content_to(&mut self.encoder, dst)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+pub mod v4 {
+    //! Contains the implementation for the Version 4 of the Wavepacket Compression / Decompression
+    use crate::las::point6::DecompressionSelector;
+
+    use super::v3;
+
+    pub struct LasWavepacketDecompressor(v3::LasWavepacketDecompressor);
+
+    impl LasWavepacketDecompressor {
+        pub fn selective(selector: DecompressionSelector) -> Self {
+            Self(v3::LasWavepacketDecompressor::for_version_4(selector))
+        }
+    }
+
+    impl Default for LasWavepacketDecompressor {
+        fn default() -> Self {
+            Self::selective(DecompressionSelector::decompress_all())
+        }
+    }
+
+    forward_layered_field_decompressor_impl!(LasWavepacketDecompressor);
+
+    pub struct LasWavepacketCompressor(v3::LasWavepacketCompressor);
+
+    impl Default for LasWavepacketCompressor {
+        fn default() -> Self {
+            Self(v3::LasWavepacketCompressor::for_version_4())
+        }
+    }
+
+    forward_layered_field_compressor_impl!(LasWavepacketCompressor);
+}
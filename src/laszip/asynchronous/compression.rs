@@ -0,0 +1,187 @@
+use std::io::{Cursor, SeekFrom};
+
+use tokio::io::{AsyncSeek, AsyncSeekExt, AsyncWrite, AsyncWriteExt};
+
+use crate::laszip::chunk_table::{ChunkTable, ChunkTableEntry};
+use crate::laszip::details::record_compressor_from_laz_items;
+use crate::laszip::CompressorType;
+use crate::{LasZipError, LazVlr};
+
+/// LasZip compressor that writes to an async destination.
+///
+/// Points are compressed in memory, one chunk at a time,
+/// each compressed chunk is then written asynchronously.
+/// The compression of a chunk is not async, it runs on the thread polling the future.
+///
+/// This supports both **variable-size** and **fixed-size** chunks.
+/// Its the [`LazVlr`] that controls which type of chunks you want to write.
+///
+/// You must call [`done`] when you have compressed all the points you wanted.
+///
+/// # Fixed-Size
+///
+/// - Use [`compress_one`] and/or [`compress_many`].
+/// - The compressor will take care of managing the chunking.
+///
+/// # Variable-Size
+///
+/// - Use [`compress_one`] and/or [`compress_many`] to compress points.
+/// - Use [`finish_current_chunk`] achieve variable-size chunks.
+///
+/// [`compress_one`]: Self::compress_one
+/// [`compress_many`]: Self::compress_many
+/// [`finish_current_chunk`]: Self::finish_current_chunk
+/// [`done`]: Self::done
+pub struct AsyncLasZipCompressor<W> {
+    vlr: LazVlr,
+    /// Table of chunks written so far
+    chunk_table: ChunkTable,
+    /// offset from beginning of the file to where the
+    /// offset to chunk table will be written
+    table_offset: i64,
+    // Uncompressed points of the chunk being formed
+    rest: Vec<u8>,
+    dest: W,
+}
+
+impl<W: AsyncWrite + AsyncSeek + Unpin> AsyncLasZipCompressor<W> {
+    /// Creates a new AsyncLasZipCompressor
+    pub fn new(dest: W, vlr: LazVlr) -> crate::Result<Self> {
+        if vlr.compressor != CompressorType::PointWiseChunked
+            && vlr.compressor != CompressorType::LayeredChunked
+        {
+            return Err(LasZipError::UnsupportedCompressorType(vlr.compressor));
+        }
+
+        let mut rest = Vec::<u8>::new();
+        if !vlr.uses_variable_size_chunks() {
+            rest.reserve(vlr.num_bytes_in_decompressed_chunk() as usize);
+        }
+
+        Ok(Self {
+            vlr,
+            chunk_table: ChunkTable::default(),
+            table_offset: -1,
+            rest,
+            dest,
+        })
+    }
+
+    /// Reserves and prepares the offset to chunk table that will be
+    /// updated when [done] is called.
+    ///
+    /// This method will automatically be called on the first point(s) being compressed,
+    /// but for some scenarios, manually calling this might be useful.
+    ///
+    /// [done]: Self::done
+    pub async fn reserve_offset_to_chunk_table(&mut self) -> std::io::Result<()> {
+        self.table_offset = self.dest.stream_position().await? as i64;
+        self.dest.write_i64_le(self.table_offset).await
+    }
+
+    /// Compresses one point
+    pub async fn compress_one(&mut self, point: &[u8]) -> std::io::Result<()> {
+        self.compress_many(point).await
+    }
+
+    /// Compresses many points.
+    ///
+    /// For **fixed-size** chunks, each time a chunk is complete
+    /// it is compressed and written.
+    pub async fn compress_many(&mut self, mut points: &[u8]) -> std::io::Result<()> {
+        if self.table_offset == -1 {
+            self.reserve_offset_to_chunk_table().await?;
+        }
+        debug_assert_eq!(points.len() % self.vlr.items_size() as usize, 0);
+        if self.vlr.uses_variable_size_chunks() {
+            self.rest.extend_from_slice(points);
+            return Ok(());
+        }
+
+        let chunk_size_in_bytes = self.vlr.num_bytes_in_decompressed_chunk() as usize;
+        while !points.is_empty() {
+            let num_bytes_to_copy = (chunk_size_in_bytes - self.rest.len()).min(points.len());
+            self.rest.extend_from_slice(&points[..num_bytes_to_copy]);
+            points = &points[num_bytes_to_copy..];
+            if self.rest.len() == chunk_size_in_bytes {
+                self.finish_current_chunk().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Compresses & writes the points compressed since the last chunk
+    /// as one chunk.
+    ///
+    /// # Important
+    ///
+    /// Only call this when writing **variable-size** chunks.
+    pub async fn finish_current_chunk(&mut self) -> std::io::Result<()> {
+        if self.rest.is_empty() {
+            return Ok(());
+        }
+        let mut compressed_chunk = Cursor::new(Vec::<u8>::new());
+        {
+            // io::Error::other is not available in the Rust versions we support
+            #[allow(clippy::io_other_error)]
+            let mut compressor =
+                record_compressor_from_laz_items(self.vlr.items(), &mut compressed_chunk)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            compressor.compress_many(&self.rest)?;
+            compressor.done()?;
+        }
+        let compressed_chunk = compressed_chunk.into_inner();
+        self.dest.write_all(&compressed_chunk).await?;
+        self.chunk_table.push(ChunkTableEntry {
+            point_count: (self.rest.len() / self.vlr.items_size() as usize) as u64,
+            byte_count: compressed_chunk.len() as u64,
+        });
+        self.rest.clear();
+        Ok(())
+    }
+
+    /// Tells the compressor that no more points will be compressed
+    ///
+    /// - Compresses & writes the rest of the points to form the last chunk
+    /// - Writes the chunk table
+    /// - update the offset to the chunk_table
+    pub async fn done(&mut self) -> crate::Result<()> {
+        if self.table_offset == -1 {
+            // No point was compressed
+            self.reserve_offset_to_chunk_table().await?;
+        }
+        self.finish_current_chunk().await?;
+
+        let start_of_chunk_table = self.dest.stream_position().await?;
+        self.dest
+            .seek(SeekFrom::Start(self.table_offset as u64))
+            .await?;
+        self.dest.write_i64_le(start_of_chunk_table as i64).await?;
+        self.dest
+            .seek(SeekFrom::Start(start_of_chunk_table))
+            .await?;
+
+        let mut chunk_table_data = Vec::<u8>::new();
+        self.chunk_table
+            .write_to(&mut chunk_table_data, &self.vlr)?;
+        self.dest.write_all(&chunk_table_data).await?;
+        self.dest.flush().await?;
+        Ok(())
+    }
+
+    pub fn vlr(&self) -> &LazVlr {
+        &self.vlr
+    }
+
+    pub fn into_inner(self) -> W {
+        self.dest
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.dest
+    }
+
+    pub fn get(&self) -> &W {
+        &self.dest
+    }
+}
@@ -0,0 +1,223 @@
+use std::io::{Cursor, Read, SeekFrom};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
+use crate::laszip::chunk_table::{read_layered_chunk_point_count, ChunkIndex, ChunkTable};
+use crate::laszip::ChunkDecoder;
+use crate::laszip::CompressorType;
+use crate::{LasZipError, LazVlr};
+
+/// LasZip decompressor that reads from an async source.
+///
+/// The chunk table and the compressed chunks are read asynchronously,
+/// each chunk is then decompressed in memory.
+/// The decompression of a chunk is not async, it runs on the thread polling the future.
+///
+/// Supports both **fixed-size** and **variable-size** chunks,
+/// the source must have a chunk table.
+pub struct AsyncLasZipDecompressor<R> {
+    decoder: ChunkDecoder,
+    /// Table of chunks read from the source.
+    chunk_table: ChunkTable,
+    /// Cumulative point & byte counts of the chunk table.
+    chunk_index: ChunkIndex,
+    /// Position of the first compressed point.
+    start_of_data: u64,
+    /// Index of the chunk that will be read when the points of the current one are consumed.
+    next_chunk: usize,
+    /// Compressed bytes of the last chunk read.
+    chunk_data: Vec<u8>,
+    /// Decompressed points of the current chunk.
+    points: Cursor<Vec<u8>>,
+    source: R,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncLasZipDecompressor<R> {
+    /// Creates a new decompressor
+    ///
+    /// The source position **must** be at the start of the point data,
+    /// and `point_count` is the number of points of the data (e.g. the one of the LAS header),
+    /// as fixed-size chunks do not store their number of points.
+    ///
+    /// Fails if no chunk table could be found, or if it cannot hold `point_count` points.
+    pub async fn new(mut source: R, vlr: LazVlr, point_count: u64) -> crate::Result<Self> {
+        let decoder = ChunkDecoder::new(vlr)?;
+        let mut chunk_table = read_chunk_table(&mut source, decoder.vlr()).await?;
+        chunk_table.set_total_point_count(decoder.vlr(), point_count)?;
+        let start_of_data = source.stream_position().await?;
+        let chunk_index = ChunkIndex::new(&chunk_table);
+        Ok(Self {
+            decoder,
+            chunk_table,
+            chunk_index,
+            start_of_data,
+            next_chunk: 0,
+            chunk_data: vec![],
+            points: Cursor::new(vec![]),
+            source,
+        })
+    }
+
+    /// Decompresses one point
+    pub async fn decompress_one(&mut self, out: &mut [u8]) -> crate::Result<()> {
+        self.decompress_many(out).await
+    }
+
+    /// Decompresses as many points as the `out` buffer can hold.
+    ///
+    /// The compressed chunks are read when needed.
+    pub async fn decompress_many(&mut self, mut out: &mut [u8]) -> crate::Result<()> {
+        debug_assert_eq!(out.len() % self.decoder.vlr().items_size() as usize, 0);
+        while !out.is_empty() {
+            if self.points.position() as usize == self.points.get_ref().len() {
+                if self.next_chunk >= self.chunk_table.len() {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "No more points to decompress",
+                    )
+                    .into());
+                }
+                self.read_chunk(self.next_chunk).await?;
+            }
+            let num_read = Read::read(&mut self.points, out)?;
+            out = &mut out[num_read..];
+        }
+        Ok(())
+    }
+
+    /// Seeks to the point designed by the index
+    ///
+    /// Only the chunk containing the point is read.
+    ///
+    /// Fails with [LasZipError::PointIndexOutOfBounds] if the index is past the last point.
+    pub async fn seek(&mut self, point_idx: u64) -> crate::Result<()> {
+        let chunk_of_point = self.chunk_index.chunk_of_point(point_idx).ok_or(
+            LasZipError::PointIndexOutOfBounds {
+                requested: point_idx,
+                total: self.chunk_index.point_count(),
+            },
+        )?;
+        if chunk_of_point + 1 != self.next_chunk {
+            self.source
+                .seek(SeekFrom::Start(
+                    self.start_of_data + self.chunk_index.byte_offset_of_chunk(chunk_of_point),
+                ))
+                .await?;
+            self.read_chunk(chunk_of_point).await?;
+        }
+        let point_in_chunk = point_idx - self.chunk_index.first_point_of_chunk(chunk_of_point);
+        self.points
+            .set_position(point_in_chunk * self.decoder.vlr().items_size());
+        Ok(())
+    }
+
+    /// Reads the chunk and decompresses its points.
+    ///
+    /// The source **must** be at the start of the chunk.
+    async fn read_chunk(&mut self, chunk_idx: usize) -> crate::Result<()> {
+        let entry = self.chunk_table[chunk_idx];
+        self.chunk_data.resize(entry.byte_count as usize, 0);
+        self.source.read_exact(&mut self.chunk_data).await?;
+
+        let points = self.points.get_mut();
+        points.resize(
+            entry.point_count as usize * self.decoder.vlr().items_size() as usize,
+            0,
+        );
+        self.points.set_position(0);
+        self.next_chunk = chunk_idx + 1;
+        if let Err(error) =
+            self.decoder
+                .decode_into(&entry, &self.chunk_data, self.points.get_mut())
+        {
+            // Do not give back points of a chunk that was not decompressed
+            self.points.get_mut().clear();
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    pub fn vlr(&self) -> &LazVlr {
+        self.decoder.vlr()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.source
+    }
+
+    pub fn get(&self) -> &R {
+        &self.source
+    }
+}
+
+/// Upper bound of the number of bytes of an arithmetic coded entry of the chunk table.
+///
+/// An entry is at most 2 integers (point count & byte count), each one being
+/// 2 symbols (of at most 16 bits) and at most 32 raw bits.
+const MAX_ENCODED_ENTRY_SIZE: u64 = 2 * (2 * 2 + 4);
+
+/// Reads the chunk table, like [ChunkTable::read_from] does.
+async fn read_chunk_table<R: AsyncRead + AsyncSeek + Unpin>(
+    src: &mut R,
+    vlr: &LazVlr,
+) -> crate::Result<ChunkTable> {
+    let data_start = src.stream_position().await?;
+    let mut offset_to_chunk_table = src.read_i64_le().await?;
+    if offset_to_chunk_table <= data_start as i64 {
+        // The writer could not update the offset
+        // so we have to find it at the end of the data
+        src.seek(SeekFrom::End(-8)).await?;
+        offset_to_chunk_table = src.read_i64_le().await?;
+        if offset_to_chunk_table <= data_start as i64 {
+            return Err(LasZipError::MissingChunkTable);
+        }
+    }
+
+    src.seek(SeekFrom::Start(offset_to_chunk_table as u64))
+        .await?;
+    // Only read the chunk table, not what may follow it (e.g. EVLRs):
+    // its header (version & number of chunks) then at most
+    // MAX_ENCODED_ENTRY_SIZE bytes per entry
+    let mut chunk_table_data = vec![0u8; 8];
+    src.read_exact(&mut chunk_table_data).await?;
+    let number_of_chunks = u32::from_le_bytes([
+        chunk_table_data[4],
+        chunk_table_data[5],
+        chunk_table_data[6],
+        chunk_table_data[7],
+    ]);
+    let max_encoded_size = (u64::from(number_of_chunks) + 1) * MAX_ENCODED_ENTRY_SIZE;
+    (&mut *src)
+        .take(max_encoded_size)
+        .read_to_end(&mut chunk_table_data)
+        .await?;
+    let mut chunk_table = ChunkTable::read(
+        &mut Cursor::new(chunk_table_data),
+        vlr.uses_variable_size_chunks(),
+    )?;
+
+    let first_chunk_start = data_start + ChunkTable::OFFSET_SIZE as u64;
+    if !vlr.uses_variable_size_chunks() {
+        chunk_table.set_point_counts(vlr.chunk_size().into());
+        // Same as in ChunkTable::read_from, the last layered chunk may not be complete
+        let last_chunk_offset =
+            ChunkIndex::new(&chunk_table).byte_offset_of_chunk(chunk_table.len().saturating_sub(1));
+        if let (Some(last_entry), CompressorType::LayeredChunked) =
+            (chunk_table.last_entry_mut(), vlr.compressor)
+        {
+            src.seek(SeekFrom::Start(first_chunk_start + last_chunk_offset))
+                .await?;
+            let mut chunk_start = vec![0u8; vlr.items_size() as usize + 4];
+            if src.read_exact(&mut chunk_start).await.is_ok() {
+                last_entry.point_count =
+                    read_layered_chunk_point_count(&mut Cursor::new(chunk_start), vlr)?;
+            }
+        }
+    }
+    src.seek(SeekFrom::Start(first_chunk_start)).await?;
+    Ok(chunk_table)
+}
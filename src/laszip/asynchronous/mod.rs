@@ -0,0 +1,15 @@
+//! Compressor & decompressor for sources and destinations implementing
+//! the async IO traits of `tokio`.
+//!
+//! The IO is done asynchronously, one whole chunk at a time,
+//! the (de)compression of a chunk is done in memory and is not async.
+//!
+//! This means that (de)compressing a chunk blocks the task polling the future
+//! (and so the executor thread running it) for the time it takes,
+//! with big chunks, consider running these in a task for blocking code
+//! (e.g. `tokio::task::spawn_blocking`) or on a dedicated thread.
+pub use compression::AsyncLasZipCompressor;
+pub use decompression::AsyncLasZipDecompressor;
+
+mod compression;
+mod decompression;
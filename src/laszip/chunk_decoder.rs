@@ -0,0 +1,105 @@
+//! Decompression of a single chunk, independently of the rest of the file
+use std::io::Cursor;
+
+use crate::las::point6::DecompressionSelector;
+use crate::laszip::chunk_table::ChunkTableEntry;
+use crate::laszip::details::selective_record_decompressor_from_laz_items;
+use crate::laszip::CompressorType;
+use crate::{LasZipError, LazVlr};
+
+/// Decompresses chunks whose compressed bytes were fetched by the caller.
+///
+/// In a chunked LAZ file each chunk can be decompressed on its own,
+/// this allows to do the IO outside of laz-rs (e.g. using HTTP range requests),
+/// [ChunkTable::chunk_byte_ranges](crate::laszip::ChunkTable::chunk_byte_ranges)
+/// gives the bytes to fetch for a range of points.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> laz::Result<()> {
+/// use laz::laszip::{ChunkDecoder, ChunkTable};
+/// use laz::LazVlr;
+/// # let vlr: LazVlr = unimplemented!();
+/// # let chunk_table: ChunkTable = unimplemented!();
+/// # let offset_to_point_data = 0u64;
+/// # fn fetch(offset: u64, len: u64) -> Vec<u8> { unimplemented!() }
+///
+/// let decoder = ChunkDecoder::new(vlr)?;
+/// for chunk in chunk_table.chunk_byte_ranges(offset_to_point_data, 1_000..5_000)? {
+///     let compressed = fetch(chunk.byte_offset, chunk.byte_len);
+///     let points = decoder.decode(&chunk.entry(), &compressed)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ChunkDecoder {
+    vlr: LazVlr,
+    selection: DecompressionSelector,
+}
+
+impl ChunkDecoder {
+    /// Creates a new decoder for the chunks of the data described by the `vlr`
+    ///
+    /// Fails if the data is not compressed in chunks.
+    pub fn new(vlr: LazVlr) -> crate::Result<Self> {
+        Self::selective(vlr, DecompressionSelector::decompress_all())
+    }
+
+    /// Creates a new decoder that only decompresses the layers in the `selection`.
+    ///
+    /// See [LasZipDecompressor::selective](crate::LasZipDecompressor::selective).
+    pub fn selective(vlr: LazVlr, selection: DecompressionSelector) -> crate::Result<Self> {
+        if vlr.compressor != CompressorType::PointWiseChunked
+            && vlr.compressor != CompressorType::LayeredChunked
+        {
+            return Err(LasZipError::UnsupportedCompressorType(vlr.compressor));
+        }
+        Ok(Self { vlr, selection })
+    }
+
+    /// Returns the vlr describing the compressed data.
+    pub fn vlr(&self) -> &LazVlr {
+        &self.vlr
+    }
+
+    /// Decompresses the chunk described by the `entry` and returns its points.
+    ///
+    /// `compressed` must start with the first byte of the chunk.
+    pub fn decode(&self, entry: &ChunkTableEntry, compressed: &[u8]) -> crate::Result<Vec<u8>> {
+        let mut points = vec![0u8; entry.point_count as usize * self.vlr.items_size() as usize];
+        self.decode_into(entry, compressed, &mut points)?;
+        Ok(points)
+    }
+
+    /// Decompresses the chunk described by the `entry` into `out`.
+    ///
+    /// `compressed` must start with the first byte of the chunk.
+    ///
+    /// # Panics
+    ///
+    /// If `out` is not exactly the size of the points of the chunk.
+    pub fn decode_into(
+        &self,
+        entry: &ChunkTableEntry,
+        compressed: &[u8],
+        out: &mut [u8],
+    ) -> crate::Result<()> {
+        assert_eq!(
+            out.len(),
+            entry.point_count as usize * self.vlr.items_size() as usize,
+            "out must hold exactly the points of the chunk"
+        );
+        if out.is_empty() {
+            return Ok(());
+        }
+        let mut record_decompressor = selective_record_decompressor_from_laz_items(
+            self.vlr.items(),
+            Cursor::new(compressed),
+            self.selection,
+        )?;
+        record_decompressor.decompress_many(out)?;
+        Ok(())
+    }
+}
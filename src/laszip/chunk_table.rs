@@ -1,6 +1,6 @@
 //! Module with all the things related to LAZ chunk tables
 use std::io::{Read, Seek, SeekFrom, Write};
-use std::ops::Index;
+use std::ops::{Index, Range};
 use std::slice::SliceIndex;
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
@@ -9,6 +9,7 @@ use crate::compressors::IntegerCompressorBuilder;
 use crate::decoders::ArithmeticDecoder;
 use crate::decompressors::IntegerDecompressorBuilder;
 use crate::encoders::ArithmeticEncoder;
+use crate::laszip::{details, CompressorType};
 use crate::{LasZipError, LazVlr};
 
 /// Indices of the contexts used for the IntegerCompressor/IntergerDecompressor
@@ -20,12 +21,37 @@ const BYTE_COUNT_CONTEXT: u32 = 1;
 ///
 /// - The number of bytes in the compressed chunk
 /// - The number of points in the compressed
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct ChunkTableEntry {
     pub point_count: u64,
     pub byte_count: u64,
 }
 
+/// Location in a LAZ file of a chunk, as returned by [ChunkTable::chunk_byte_ranges].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ChunkByteRange {
+    /// Index of the chunk in the chunk table.
+    pub index: usize,
+    /// Index of the first point of the chunk.
+    pub first_point: u64,
+    /// Number of points in the chunk.
+    pub point_count: u64,
+    /// Offset of the chunk from the start of the file.
+    pub byte_offset: u64,
+    /// Number of compressed bytes of the chunk.
+    pub byte_len: u64,
+}
+
+impl ChunkByteRange {
+    /// Returns the entry of the chunk table for this chunk.
+    pub fn entry(&self) -> ChunkTableEntry {
+        ChunkTableEntry {
+            point_count: self.point_count,
+            byte_count: self.byte_len,
+        }
+    }
+}
+
 /// The ChunkTable contains chunk entries for a LAZ file.
 ///
 /// The ChunkTable has two ways of being stored in a LAZ file
@@ -52,9 +78,15 @@ impl ChunkTable {
     ///
     /// # Important
     ///
-    /// When the chunks are `fixed-size`, each entry will have the same number of points,
-    /// the `chunk_size` registered in the `vlr`.
-    /// This means that for the **last** chunk, the [ChunkTableEntry]'s `point_count` won't be correct.
+    /// When the chunks are `fixed-size`, the chunk table does not store the number of points,
+    /// each entry will have the `chunk_size` registered in the `vlr` as its `point_count`,
+    /// except the **last** one of layered chunks (point formats >= 6),
+    /// whose number of points is read from the start of the chunk.
+    /// (If it cannot be read, its `point_count` will be the `chunk_size`)
+    ///
+    /// The last chunk of point-wise chunks (point formats < 6) does not store its
+    /// number of points, so its `point_count` is the `chunk_size`,
+    /// use [set_total_point_count](Self::set_total_point_count) to get the actual one.
     ///
     /// For `variable-size` chunks the `point_count` of each entry is the one read
     /// from the source.
@@ -62,8 +94,113 @@ impl ChunkTable {
         if vlr.uses_variable_size_chunks() {
             ChunkTable::read_as_variably_sized(&mut src)
         } else {
-            ChunkTable::read_as_fixed_size(&mut src, vlr.chunk_size().into())
+            let mut chunk_table =
+                ChunkTable::read_as_fixed_size(&mut src, vlr.chunk_size().into())?;
+            let data_start = src.stream_position()?;
+            let last_point_count = chunk_table.count_points_of_last_chunk(&mut src, vlr);
+            src.seek(SeekFrom::Start(data_start))?;
+            if let (Ok(Some(point_count)), Some(last_entry)) =
+                (last_point_count, chunk_table.last_entry_mut())
+            {
+                last_entry.point_count = point_count;
+            }
+            Ok(chunk_table)
+        }
+    }
+
+    /// Rebuilds the chunk table by decompressing the chunks of the `src`.
+    ///
+    /// This is for files where the chunk table is missing or corrupted,
+    /// (e.g. when the writer could not finish writing the file).
+    ///
+    /// The source position **must** be at the start of the point data,
+    /// `point_count` is the number of points in the file, as written in the LAS header.
+    ///
+    /// Like [read_from](Self::read_from), this functions set position of the `src`
+    /// where the points actually starts (that is, after the chunk table offset).
+    ///
+    /// # Important
+    ///
+    /// Point-wise compressed chunks (point formats < 6) do not store their number of points,
+    /// so **variable-size** chunks can only be rebuilt for point formats >= 6,
+    /// [LasZipError::MissingChunkTable] is returned otherwise.
+    pub fn rebuild<R: Read + Seek + Send>(
+        mut src: R,
+        vlr: &LazVlr,
+        point_count: u64,
+    ) -> crate::Result<Self> {
+        let is_layered = match vlr.compressor {
+            CompressorType::LayeredChunked => true,
+            CompressorType::PointWiseChunked if !vlr.uses_variable_size_chunks() => false,
+            CompressorType::PointWiseChunked => return Err(LasZipError::MissingChunkTable),
+            compressor => return Err(LasZipError::UnsupportedCompressorType(compressor)),
+        };
+        let first_chunk_start = src.stream_position()? + Self::OFFSET_SIZE as u64;
+        src.seek(SeekFrom::Start(first_chunk_start))?;
+
+        let mut chunk_table = ChunkTable::default();
+        let mut point = vec![0u8; vlr.items_size() as usize];
+        let mut chunk_start = first_chunk_start;
+        let mut num_points_left = point_count;
+        while num_points_left > 0 {
+            let chunk_point_count = if is_layered {
+                // Layered chunks store their number of points right after the first point
+                // and the decompressor reads all the layers of the chunk with the first point
+                src.seek(SeekFrom::Start(chunk_start + vlr.items_size()))?;
+                let chunk_point_count = u64::from(src.read_u32::<LittleEndian>()?);
+                src.seek(SeekFrom::Start(chunk_start))?;
+                let mut decompressor =
+                    details::record_decompressor_from_laz_items(vlr.items(), &mut src)?;
+                decompressor.decompress_next(&mut point)?;
+                chunk_point_count
+            } else {
+                // Point-wise chunks end right after their last point
+                let chunk_point_count = num_points_left.min(u64::from(vlr.chunk_size()));
+                let mut decompressor =
+                    details::record_decompressor_from_laz_items(vlr.items(), &mut src)?;
+                for _ in 0..chunk_point_count {
+                    decompressor.decompress_next(&mut point)?;
+                }
+                chunk_point_count
+            };
+            if chunk_point_count == 0 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Found a chunk without points",
+                )
+                .into());
+            }
+            let chunk_end = src.stream_position()?;
+            chunk_table.push(ChunkTableEntry {
+                point_count: chunk_point_count,
+                byte_count: chunk_end - chunk_start,
+            });
+            num_points_left = num_points_left.saturating_sub(chunk_point_count);
+            chunk_start = chunk_end;
         }
+        src.seek(SeekFrom::Start(first_chunk_start))?;
+        Ok(chunk_table)
+    }
+
+    /// Rebuilds the chunk table like [rebuild](Self::rebuild) does, and writes it to the `file`.
+    ///
+    /// The chunk table is written at the end of the file and the offset to the chunk table
+    /// is updated, so that the file can then be read normally.
+    ///
+    /// The file position **must** be at the start of the point data, it is left
+    /// where the points actually starts.
+    pub fn rebuild_and_write<F: Read + Write + Seek + Send>(
+        mut file: F,
+        vlr: &LazVlr,
+        point_count: u64,
+    ) -> crate::Result<Self> {
+        let data_start = file.stream_position()?;
+        let chunk_table = Self::rebuild(&mut file, vlr, point_count)?;
+        file.seek(SeekFrom::End(0))?;
+        update_chunk_table_offset(&mut file, SeekFrom::Start(data_start))?;
+        chunk_table.write_to(&mut file, vlr)?;
+        file.seek(SeekFrom::Start(data_start + Self::OFFSET_SIZE as u64))?;
+        Ok(chunk_table)
     }
 
     /// Writes the chunk table to the `dst`.
@@ -94,11 +231,79 @@ impl ChunkTable {
         src.seek(SeekFrom::Start(chunk_table_start))?;
         let mut chunk_table = Self::read(&mut src, false)?;
         src.seek(SeekFrom::Start(data_start + 8))?;
+        chunk_table.set_point_counts(point_count);
+        Ok(chunk_table)
+    }
 
-        for entry in &mut chunk_table.0 {
+    /// Sets the `point_count` of the last entry of **fixed-size** chunks
+    /// from the total number of points (e.g. the one of the LAS header),
+    /// as all the other chunks have `chunk_size` points.
+    ///
+    /// The entries of **variable-size** chunks already have their `point_count`,
+    /// so they are left untouched.
+    ///
+    /// Returns an `InvalidData` error if the chunks cannot hold `point_count` points.
+    pub fn set_total_point_count(&mut self, vlr: &LazVlr, point_count: u64) -> crate::Result<()> {
+        if vlr.uses_variable_size_chunks() {
+            return Ok(());
+        }
+        let chunk_size = u64::from(vlr.chunk_size());
+        let num_points_in_full_chunks = self.0.len().saturating_sub(1) as u64 * chunk_size;
+        match self.0.last_mut() {
+            None if point_count == 0 => Ok(()),
+            Some(last_entry)
+                if point_count > num_points_in_full_chunks
+                    && point_count - num_points_in_full_chunks <= chunk_size =>
+            {
+                last_entry.point_count = point_count - num_points_in_full_chunks;
+                Ok(())
+            }
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "{} chunks of {} points cannot hold {} points",
+                    self.0.len(),
+                    chunk_size,
+                    point_count
+                ),
+            )
+            .into()),
+        }
+    }
+
+    /// Sets the `point_count` of all the entries.
+    pub(crate) fn set_point_counts(&mut self, point_count: u64) {
+        for entry in &mut self.0 {
             entry.point_count = point_count;
         }
-        Ok(chunk_table)
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<ChunkTableEntry> {
+        self.0.pop()
+    }
+
+    pub(crate) fn last_entry_mut(&mut self) -> Option<&mut ChunkTableEntry> {
+        self.0.last_mut()
+    }
+
+    /// Reads the number of points of the last chunk, if it is stored in the chunk.
+    ///
+    /// `src` should be at the start of the first chunk,
+    /// this function does not restore the position of the `src`.
+    fn count_points_of_last_chunk<R: Read + Seek>(
+        &self,
+        src: &mut R,
+        vlr: &LazVlr,
+    ) -> crate::Result<Option<u64>> {
+        if vlr.compressor != CompressorType::LayeredChunked || self.0.is_empty() {
+            return Ok(None);
+        }
+        let offset_of_last_chunk = self.0[..self.0.len() - 1]
+            .iter()
+            .map(|entry| entry.byte_count)
+            .sum::<u64>();
+        src.seek(SeekFrom::Current(offset_of_last_chunk as i64))?;
+        Ok(Some(read_layered_chunk_point_count(src, vlr)?))
     }
 
     /// Reads the offset to the chunk table.
@@ -144,7 +349,8 @@ impl ChunkTable {
         let mut decoder = ArithmeticDecoder::new(&mut src);
         decoder.read_init_bytes()?;
 
-        let mut chunk_table = ChunkTable::with_capacity(number_of_chunks as usize);
+        // The number of chunks is not trusted to allocate, the table grows while it is read
+        let mut chunk_table = ChunkTable::with_capacity((number_of_chunks as usize).min(1024));
         let mut previous_entry = ChunkTableEntry::default();
         for _ in 1..=number_of_chunks {
             let mut current_entry = ChunkTableEntry {
@@ -219,15 +425,56 @@ impl ChunkTable {
         self.0.len()
     }
 
-    #[cfg(feature = "parallel")]
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
 
-    #[cfg(feature = "parallel")]
     pub fn extend(&mut self, other: &ChunkTable) {
         self.0.extend(&other.0)
     }
+
+    /// Returns the location of the chunks that contain the `points`.
+    ///
+    /// `data_start` is the `offset_to_point_data` of the LAS header,
+    /// that is the position of the offset to the chunk table, which comes before the first chunk.
+    ///
+    /// The returned chunks can be fetched from the file, and decompressed
+    /// independently with a [ChunkDecoder](crate::laszip::ChunkDecoder).
+    /// The first and last chunk may contain points that are not in the range.
+    ///
+    /// Fails with [LasZipError::PointIndexOutOfBounds] if the range goes past the last point.
+    pub fn chunk_byte_ranges(
+        &self,
+        data_start: u64,
+        points: Range<u64>,
+    ) -> crate::Result<Vec<ChunkByteRange>> {
+        if points.start >= points.end {
+            return Ok(vec![]);
+        }
+        let index = ChunkIndex::new(self);
+        let (first_chunk, last_chunk) = match (
+            index.chunk_of_point(points.start),
+            index.chunk_of_point(points.end - 1),
+        ) {
+            (Some(first_chunk), Some(last_chunk)) => (first_chunk, last_chunk),
+            _ => {
+                return Err(LasZipError::PointIndexOutOfBounds {
+                    requested: points.end - 1,
+                    total: index.point_count(),
+                })
+            }
+        };
+        let first_chunk_offset = data_start + Self::OFFSET_SIZE as u64;
+        Ok((first_chunk..=last_chunk)
+            .map(|i| ChunkByteRange {
+                index: i,
+                first_point: index.first_point_of_chunk(i),
+                point_count: self.0[i].point_count,
+                byte_offset: first_chunk_offset + index.byte_offset_of_chunk(i),
+                byte_len: self.0[i].byte_count,
+            })
+            .collect())
+    }
 }
 
 impl AsRef<[ChunkTableEntry]> for ChunkTable {
@@ -256,6 +503,80 @@ where
     }
 }
 
+/// Cumulative point & byte counts of the chunks of a [ChunkTable].
+///
+/// It allows to find the chunk a point belongs to, and where that chunk starts,
+/// in logarithmic time instead of walking the whole chunk table.
+#[derive(Default, Debug, Clone)]
+pub(crate) struct ChunkIndex {
+    // `point_starts[i]` is the index of the first point of the chunk `i`,
+    // the last value is the total number of points
+    point_starts: Vec<u64>,
+    // `byte_starts[i]` is the offset of the chunk `i` from the start of the first chunk,
+    // the last value is the total number of bytes
+    byte_starts: Vec<u64>,
+}
+
+impl ChunkIndex {
+    pub(crate) fn new(chunk_table: &ChunkTable) -> Self {
+        let mut point_starts = Vec::with_capacity(chunk_table.len() + 1);
+        let mut byte_starts = Vec::with_capacity(chunk_table.len() + 1);
+        let (mut point_start, mut byte_start) = (0u64, 0u64);
+        point_starts.push(point_start);
+        byte_starts.push(byte_start);
+        for entry in chunk_table {
+            point_start += entry.point_count;
+            byte_start += entry.byte_count;
+            point_starts.push(point_start);
+            byte_starts.push(byte_start);
+        }
+        Self {
+            point_starts,
+            byte_starts,
+        }
+    }
+
+    /// Returns the index of the chunk that contains the point,
+    /// or `None` if the point is past the last chunk.
+    pub(crate) fn chunk_of_point(&self, point_idx: u64) -> Option<usize> {
+        if point_idx >= self.point_count() {
+            return None;
+        }
+        Some(
+            self.point_starts
+                .partition_point(|&start| start <= point_idx)
+                - 1,
+        )
+    }
+
+    /// Returns the index of the first point of the chunk.
+    pub(crate) fn first_point_of_chunk(&self, chunk_idx: usize) -> u64 {
+        self.point_starts[chunk_idx]
+    }
+
+    /// Returns the offset of the chunk, counted from the start of the first chunk.
+    pub(crate) fn byte_offset_of_chunk(&self, chunk_idx: usize) -> u64 {
+        self.byte_starts[chunk_idx]
+    }
+
+    /// Returns the total number of points of all the chunks.
+    pub(crate) fn point_count(&self) -> u64 {
+        *self.point_starts.last().unwrap_or(&0)
+    }
+}
+
+/// Reads the number of points of a layered chunk,
+/// stored right after its first point.
+///
+/// `src` should be at the start of the chunk.
+pub(crate) fn read_layered_chunk_point_count<R: Read + Seek>(
+    src: &mut R,
+    vlr: &LazVlr,
+) -> std::io::Result<u64> {
+    src.seek(SeekFrom::Current(vlr.items_size() as i64))?;
+    Ok(u64::from(src.read_u32::<LittleEndian>()?))
+}
+
 /// Updates the 'chunk table offset'
 ///
 /// It is the first 8 byte (i64) of a Laszip compressed data
@@ -280,3 +601,68 @@ pub(super) fn update_chunk_table_offset<W: Write + Seek>(
     dst.seek(SeekFrom::Start(start_of_chunk_table_pos))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_chunk_index() {
+        let mut chunk_table = ChunkTable::with_capacity(3);
+        for (point_count, byte_count) in [(10, 100), (5, 40), (1, 12)] {
+            chunk_table.push(ChunkTableEntry {
+                point_count,
+                byte_count,
+            });
+        }
+        let index = ChunkIndex::new(&chunk_table);
+
+        assert_eq!(index.point_count(), 16);
+
+        assert_eq!(index.chunk_of_point(0), Some(0));
+        assert_eq!(index.chunk_of_point(9), Some(0));
+        assert_eq!(index.chunk_of_point(10), Some(1));
+        assert_eq!(index.chunk_of_point(14), Some(1));
+        assert_eq!(index.chunk_of_point(15), Some(2));
+        assert_eq!(index.chunk_of_point(16), None);
+
+        assert_eq!(index.first_point_of_chunk(2), 15);
+        assert_eq!(index.byte_offset_of_chunk(2), 140);
+    }
+
+    #[test]
+    fn test_set_total_point_count() {
+        let vlr = LazVlr::from_laz_items(crate::LazItemRecordBuilder::default_version_of::<
+            crate::las::Point0,
+        >(0));
+        let chunk_size = u64::from(vlr.chunk_size());
+        let mut chunk_table = ChunkTable::default();
+        assert!(chunk_table.set_total_point_count(&vlr, 0).is_ok());
+        assert!(chunk_table.set_total_point_count(&vlr, 1).is_err());
+
+        for _ in 0..3 {
+            chunk_table.push(ChunkTableEntry {
+                point_count: chunk_size,
+                byte_count: 10,
+            });
+        }
+        chunk_table
+            .set_total_point_count(&vlr, 2 * chunk_size + 5)
+            .unwrap();
+        assert_eq!(chunk_table[2].point_count, 5);
+        assert_eq!(chunk_table[1].point_count, chunk_size);
+        assert!(chunk_table
+            .set_total_point_count(&vlr, 2 * chunk_size)
+            .is_err());
+        assert!(chunk_table
+            .set_total_point_count(&vlr, 3 * chunk_size + 1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_read_huge_number_of_chunks() {
+        // A corrupted number of chunks must give an error, not a huge allocation
+        let mut data = std::io::Cursor::new(vec![0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0]);
+        assert!(ChunkTable::read(&mut data, true).is_err());
+    }
+}
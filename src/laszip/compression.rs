@@ -1,4 +1,4 @@
-use std::io::{Seek, SeekFrom, Write};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 
 use byteorder::{LittleEndian, WriteBytesExt};
 
@@ -50,6 +50,8 @@ pub struct LasZipCompressor<'a, W: Write + Send + 'a> {
     /// Position (offset from beginning)
     /// where the current chunk started
     chunk_start_pos: u64,
+    /// Whether the compressor was created by `open_for_append`
+    is_appending: bool,
 }
 
 impl<'a, W: Write + Seek + Send + 'a> LasZipCompressor<'a, W> {
@@ -69,6 +71,7 @@ impl<'a, W: Write + Seek + Send + 'a> LasZipCompressor<'a, W> {
             start_pos: 0,
             chunk_table: ChunkTable::default(),
             current_chunk_entry: ChunkTableEntry::default(),
+            is_appending: false,
         })
     }
 
@@ -138,8 +141,15 @@ impl<'a, W: Write + Seek + Send + 'a> LasZipCompressor<'a, W> {
         if self.chunk_start_pos == 0 {
             self.reserve_offset_to_chunk_table()?;
         }
-        self.record_compressor.done()?;
-        self.update_chunk_table()?;
+        // When appending, the current chunk may be empty,
+        // there is no need to add it
+        if !self.is_appending
+            || self.current_chunk_entry.point_count != 0
+            || self.chunk_table.is_empty()
+        {
+            self.record_compressor.done()?;
+            self.update_chunk_table()?;
+        }
         let stream = self.record_compressor.get_mut();
         chunk_table::update_chunk_table_offset(stream, SeekFrom::Start(self.start_pos))?;
         self.chunk_table.write_to(stream, &self.vlr)?;
@@ -188,6 +198,11 @@ impl<'a, W: Write + Seek + Send + 'a> LasZipCompressor<'a, W> {
         &self.vlr
     }
 
+    /// Returns the table of the chunks written so far
+    pub fn chunk_table(&self) -> &ChunkTable {
+        &self.chunk_table
+    }
+
     pub fn into_inner(self) -> W {
         self.record_compressor.box_into_inner()
     }
@@ -225,6 +240,82 @@ impl<'a, W: Write + Seek + Send + 'a> LasZipCompressor<'a, W> {
     }
 }
 
+impl<'a, W: Read + Write + Seek + Send + 'a> LasZipCompressor<'a, W> {
+    /// Creates a compressor that appends points to the LAZ data of the `stream`.
+    ///
+    /// The `stream` position **must** be at the start of the point data,
+    /// the `vlr` must be the one of the data, and `point_count` the number
+    /// of points already in the data (e.g. the one of the LAS header).
+    ///
+    /// The chunk table of the data is read, and the new chunks are written
+    /// where the chunk table was. When [`done`] is called the chunk table,
+    /// containing the existing and new chunks, is written after the new chunks.
+    ///
+    /// For **fixed-size** chunks, if the last chunk is not full, its points are decompressed and
+    /// compressed again with the new points so that it is completed.
+    /// As fixed-size chunks do not store their number of points, it is computed from `point_count`.
+    ///
+    /// # Important
+    ///
+    /// - Fails if the data does not have a chunk table
+    ///   (see [`ChunkTable::rebuild_and_write`](crate::laszip::ChunkTable::rebuild_and_write)).
+    /// - Anything that was after the chunk table (e.g. EVLRs) is overwritten.
+    /// - The LAS header (e.g. the number of points) is not updated.
+    ///
+    /// [`done`]: Self::done
+    pub fn open_for_append(mut stream: W, vlr: LazVlr, point_count: u64) -> crate::Result<Self> {
+        let start_pos = stream.stream_position()?;
+        let mut chunk_table = ChunkTable::read_from(&mut stream, &vlr)?;
+        chunk_table.set_total_point_count(&vlr, point_count)?;
+        let num_bytes_in_chunks = chunk_table
+            .as_ref()
+            .iter()
+            .map(|entry| entry.byte_count)
+            .sum::<u64>();
+        let mut chunk_start_pos = start_pos + ChunkTable::OFFSET_SIZE as u64 + num_bytes_in_chunks;
+
+        // `done` ends variable-size chunks with an empty chunk, new chunks replace it
+        if let Some(last_entry) = chunk_table.as_ref().last().copied() {
+            if vlr.uses_variable_size_chunks() && last_entry.point_count == 0 {
+                chunk_start_pos -= last_entry.byte_count;
+                chunk_table.pop();
+            }
+        }
+
+        // Points of the last chunk, when it has to be completed
+        let mut last_chunk_points = Vec::<u8>::new();
+        if let Some(last_entry) = chunk_table.as_ref().last().copied() {
+            if !vlr.uses_variable_size_chunks()
+                && last_entry.point_count < u64::from(vlr.chunk_size())
+            {
+                chunk_start_pos -= last_entry.byte_count;
+                stream.seek(SeekFrom::Start(chunk_start_pos))?;
+                let mut chunk_data = vec![0u8; last_entry.byte_count as usize];
+                stream.read_exact(&mut chunk_data)?;
+                let mut decompressor = details::record_decompressor_from_laz_items(
+                    vlr.items(),
+                    Cursor::new(chunk_data),
+                )?;
+                last_chunk_points.resize(
+                    last_entry.point_count as usize * vlr.items_size() as usize,
+                    0,
+                );
+                decompressor.decompress_many(&mut last_chunk_points)?;
+                chunk_table.pop();
+            }
+        }
+
+        stream.seek(SeekFrom::Start(chunk_start_pos))?;
+        let mut compressor = Self::new(stream, vlr)?;
+        compressor.start_pos = start_pos;
+        compressor.chunk_start_pos = chunk_start_pos;
+        compressor.chunk_table = chunk_table;
+        compressor.is_appending = true;
+        compressor.compress_many(&last_chunk_points)?;
+        Ok(compressor)
+    }
+}
+
 impl<'a, W: Write + Seek + Send + 'a> super::LazCompressor for LasZipCompressor<'a, W> {
     fn compress_many(&mut self, points: &[u8]) -> crate::Result<()> {
         self.compress_many(points)?;
@@ -1,10 +1,12 @@
 use std::io::{Read, Seek, SeekFrom};
 
 use crate::errors::LasZipError::MissingChunkTable;
+use crate::las::columns::{ColumnsLayout, PointColumns};
+use crate::las::point6::DecompressionSelector;
 use crate::record::RecordDecompressor;
 use crate::LasZipError;
 
-use super::chunk_table::ChunkTable;
+use super::chunk_table::{ChunkIndex, ChunkTable};
 use super::{details, CompressorType, LazVlr};
 
 /// SeekInfo aggregates the two information needed to be able to seek
@@ -13,6 +15,10 @@ pub(super) struct SeekInfo {
     // offset to the first point
     pub(super) data_start: u64,
     pub(super) chunk_table: ChunkTable,
+    // cumulative point & byte counts of the chunk_table
+    pub(super) chunk_index: ChunkIndex,
+    // false for the last fixed-size point-wise chunk, until the point count is set
+    pub(super) last_point_count_is_known: bool,
 }
 
 impl SeekInfo {
@@ -22,23 +28,16 @@ impl SeekInfo {
     ) -> crate::Result<Self> {
         let chunk_table = ChunkTable::read_from(&mut source, vlr)?;
         let data_start = source.seek(SeekFrom::Current(0))?;
+        let chunk_index = ChunkIndex::new(&chunk_table);
 
         Ok(Self {
             data_start,
             chunk_table,
+            chunk_index,
+            last_point_count_is_known: vlr.compressor != CompressorType::PointWiseChunked
+                || vlr.uses_variable_size_chunks(),
         })
     }
-
-    /// Returns the offset where the chunk table is written
-    pub(super) fn offset_to_chunk_table(&self) -> u64 {
-        self.data_start
-            + self
-                .chunk_table
-                .as_ref()
-                .iter()
-                .map(|e| e.byte_count)
-                .sum::<u64>()
-    }
 }
 
 /// LasZip decompressor that decompresses points.
@@ -55,10 +54,64 @@ pub struct LasZipDecompressor<'a, R: Read + Seek + 'a> {
     num_points_in_chunk: u64,
 }
 
+/// Outcome of the decompression of possibly damaged (e.g. truncated) data,
+/// see [LasZipDecompressor::decompress_recoverable].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct RecoveryReport {
+    /// Number of points that were decompressed.
+    pub points_recovered: u64,
+    /// Index of the last chunk that was completely decompressed.
+    pub last_good_chunk: Option<usize>,
+    /// Offset in the source of the start of the first chunk that
+    /// could not be decompressed, `None` if all the points were decompressed.
+    pub damage_offset: Option<u64>,
+}
+
+impl RecoveryReport {
+    /// Returns true if all the requested points could be decompressed.
+    pub fn is_complete(&self) -> bool {
+        self.damage_offset.is_none()
+    }
+}
+
 impl<'a, R: Read + Seek + Send + 'a> LasZipDecompressor<'a, R> {
     /// Creates a new instance from a data source of compressed points
     /// and the LazVlr describing the compressed data
-    pub fn new(mut source: R, vlr: LazVlr) -> crate::Result<Self> {
+    pub fn new(source: R, vlr: LazVlr) -> crate::Result<Self> {
+        Self::selective(source, vlr, DecompressionSelector::decompress_all())
+    }
+
+    /// Creates a new instance that only decompresses the layers in the `selection`.
+    ///
+    /// The bytes of the layers that are not selected are skipped
+    /// and the corresponding fields are set to zero in the decompressed points.
+    ///
+    /// Only point formats 6 to 10 store their data in layers,
+    /// for other point formats all the fields are always decompressed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> laz::Result<()> {
+    /// use laz::las::point6::DecompressionSelector;
+    /// use laz::{LasZipDecompressor, LazVlr};
+    /// # let source = std::io::Cursor::new(Vec::<u8>::new());
+    /// # let vlr: LazVlr = unimplemented!();
+    ///
+    /// let selection = DecompressionSelector::xy_returns_channel()
+    ///     .z()
+    ///     .classification();
+    /// let mut decompressor = LasZipDecompressor::selective(source, vlr, selection)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn selective(
+        mut source: R,
+        vlr: LazVlr,
+        selection: DecompressionSelector,
+    ) -> crate::Result<Self> {
+        // Not available if the source is not actually seekable
+        let data_start = source.stream_position().ok();
         // The chunk table is not always mandatory when just reading data.
         let seek_info = match vlr.compressor {
             CompressorType::PointWise => {
@@ -71,12 +124,11 @@ impl<'a, R: Read + Seek + Send + 'a> LasZipDecompressor<'a, R> {
                     (Ok(info), _) => Some(info),
                     (Err(_), false) => {
                         // The error is probably due to a seek error
-                        // (Eg for a source that is not actually seekable).
-                        // So we _may_ still be at the start of point data,
-                        // we need to skip the chunk table offset otherwise
+                        // (Eg for a source that is not actually seekable),
+                        // or to a chunk table lost in a truncated file.
+                        // We need to skip the chunk table offset otherwise
                         // decompression won't be correct.
-                        let mut tmp = [0u8; ChunkTable::OFFSET_SIZE];
-                        source.read_exact(&mut tmp)?;
+                        skip_chunk_table_offset(&mut source, data_start)?;
                         None
                     }
                     (Err(err), true) => {
@@ -93,8 +145,7 @@ impl<'a, R: Read + Seek + Send + 'a> LasZipDecompressor<'a, R> {
                 let seek_info = SeekInfo::read_from(&mut source, &vlr).ok();
                 if seek_info.is_none() {
                     // Same as in PointWiseChunked
-                    let mut tmp = [0u8; ChunkTable::OFFSET_SIZE];
-                    source.read_exact(&mut tmp)?;
+                    skip_chunk_table_offset(&mut source, data_start)?;
                 }
                 seek_info
             }
@@ -104,7 +155,7 @@ impl<'a, R: Read + Seek + Send + 'a> LasZipDecompressor<'a, R> {
         };
 
         let record_decompressor =
-            details::record_decompressor_from_laz_items(&vlr.items(), source)?;
+            details::selective_record_decompressor_from_laz_items(vlr.items(), source, selection)?;
 
         Ok(Self {
             vlr,
@@ -165,6 +216,50 @@ impl<'a, R: Read + Seek + Send + 'a> LasZipDecompressor<'a, R> {
         Ok(())
     }
 
+    /// Decompresses `num_points` points and appends them to the `columns`.
+    ///
+    /// The fields of the points are appended straight from the field decompressors,
+    /// only the first point of each chunk, which is not compressed,
+    /// is transposed from its record.
+    pub fn decompress_columns(
+        &mut self,
+        num_points: usize,
+        columns: &mut PointColumns,
+    ) -> std::io::Result<()> {
+        let layout = ColumnsLayout::from_laz_items(self.vlr.items());
+        columns.num_extra_bytes = layout.num_extra_bytes();
+        columns.reserve(&layout, num_points);
+
+        let mut point = vec![0u8; layout.point_size];
+        for _ in 0..num_points {
+            self.decompress_one(&mut point)?;
+            if self.chunk_points_read == 1 {
+                columns.push_point(&layout, &point);
+            } else {
+                self.record_decompressor.push_last_to_columns(columns);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the number of points of the compressed data (e.g. the one of the LAS header).
+    ///
+    /// The last **fixed-size** chunk of point formats < 6 does not store its number of points,
+    /// so until this is called, `seek` finds the end of that chunk by decompressing it,
+    /// and `decompress_range` cannot detect point indices past the last point.
+    ///
+    /// Returns an `InvalidData` error if the chunks cannot hold `point_count` points.
+    pub fn set_point_count(&mut self, point_count: u64) -> crate::Result<()> {
+        if let Some(seek_info) = self.seek_info.as_mut() {
+            seek_info
+                .chunk_table
+                .set_total_point_count(&self.vlr, point_count)?;
+            seek_info.chunk_index = ChunkIndex::new(&seek_info.chunk_table);
+            seek_info.last_point_count_is_known = true;
+        }
+        Ok(())
+    }
+
     /// Seeks to the point designed by the index
     ///
     /// # Important
@@ -177,89 +272,154 @@ impl<'a, R: Read + Seek + Send + 'a> LasZipDecompressor<'a, R> {
         let SeekInfo {
             data_start,
             chunk_table,
+            chunk_index,
+            last_point_count_is_known,
         } = self.seek_info.as_ref().ok_or(MissingChunkTable)?;
 
-        let chunk_info = {
-            let mut chunk_of_point = 0usize;
-            let mut start_of_chunk = *data_start;
-            let mut tmp_count = 0;
-            for entry in chunk_table {
-                tmp_count += entry.point_count;
-                if tmp_count >= point_idx {
-                    break;
-                }
-                start_of_chunk += entry.byte_count;
-                chunk_of_point += 1;
-            }
+        let chunk_of_point =
+            chunk_index
+                .chunk_of_point(point_idx)
+                .ok_or(LasZipError::PointIndexOutOfBounds {
+                    requested: point_idx,
+                    total: chunk_index.point_count(),
+                })?;
+        let start_of_chunk = data_start + chunk_index.byte_offset_of_chunk(chunk_of_point);
+        let first_point_of_chunk = chunk_index.first_point_of_chunk(chunk_of_point);
+        let delta = point_idx - first_point_of_chunk;
+        let is_last_chunk_of_unknown_size =
+            !last_point_count_is_known && chunk_of_point + 1 == chunk_table.len();
+        let chunk_byte_count = chunk_table[chunk_of_point].byte_count;
 
-            if point_idx > tmp_count {
-                None
-            } else {
-                Some((chunk_of_point, start_of_chunk))
+        self.current_chunk = chunk_of_point;
+        self.record_decompressor
+            .get_mut()
+            .seek(SeekFrom::Start(start_of_chunk))?;
+        if is_last_chunk_of_unknown_size {
+            // The last point-wise chunk does not store its number of points,
+            // like the parallel decompressor, only decompress its bytes to find its end
+            let mut chunk_data = vec![0u8; chunk_byte_count as usize];
+            self.record_decompressor
+                .get_mut()
+                .read_exact(&mut chunk_data)?;
+            let mut decompressor = details::record_decompressor_from_laz_items(
+                self.vlr.items(),
+                std::io::Cursor::new(chunk_data),
+            )?;
+            let point_size = self.vlr.items_size() as usize;
+            let mut points = vec![0u8; (delta as usize + 1) * point_size];
+            let num_points =
+                (decompressor.decompress_until_end_of_file(&mut points)? / point_size) as u64;
+            if num_points <= delta {
+                return Err(LasZipError::PointIndexOutOfBounds {
+                    requested: point_idx,
+                    total: first_point_of_chunk + num_points,
+                });
             }
-        };
+            self.record_decompressor
+                .get_mut()
+                .seek(SeekFrom::Start(start_of_chunk))?;
+        }
+        self.reset_for_new_chunk();
 
-        if let Some((chunk_of_point, start_of_chunk)) = chunk_info {
-            self.current_chunk = chunk_of_point as usize;
-            let delta = point_idx % chunk_table[self.current_chunk].point_count;
-            if chunk_of_point == (chunk_table.len() - 1) {
-                // the requested point fall into the last chunk,
-                // but that does not mean that the point exists
-                // so we have to be careful, we will do as we would normally,
-                // but if we reach the chunk_table_offset that means the requested
-                // point is out ouf bounds so will just seek to the end cf(the else in the if let below)
-                // we do this to avoid decompressing data (ie the chunk table) thinking its a record
-                let mut tmp_out = vec![0u8; self.record_decompressor.record_size()];
-                self.record_decompressor
-                    .get_mut()
-                    .seek(SeekFrom::Start(start_of_chunk))?;
-
-                self.reset_for_new_chunk();
-                let offset_to_chunk_table = self
-                    .seek_info
-                    .as_ref()
-                    .map(SeekInfo::offset_to_chunk_table)
-                    .unwrap();
-
-                for _i in 0..delta {
-                    self.decompress_one(&mut tmp_out)?;
-                    let current_pos = self
-                        .record_decompressor
-                        .get_mut()
-                        .seek(SeekFrom::Current(0))?;
-
-                    if current_pos >= offset_to_chunk_table {
-                        self.record_decompressor.get_mut().seek(SeekFrom::End(0))?;
-                        return Ok(());
-                    }
-                }
-            } else {
-                self.record_decompressor
-                    .get_mut()
-                    .seek(SeekFrom::Start(start_of_chunk))?;
-                self.reset_for_new_chunk();
-                let mut tmp_out = vec![0u8; self.record_decompressor.record_size()];
-
-                for _i in 0..delta {
-                    self.decompress_one(&mut tmp_out)?;
-                }
-            }
-        } else {
-            // the requested point it out of bounds (ie higher than the number of
-            // points compressed)
+        let mut tmp_out = vec![0u8; self.record_decompressor.record_size()];
+        for _i in 0..delta {
+            self.decompress_one(&mut tmp_out)?;
+        }
+        Ok(())
+    }
 
-            // Seek to the end so that the next call to decompress causes en error
-            // like "Failed to fill whole buffer (seeking past end is allowed by the Seek Trait)
-            self.record_decompressor.get_mut().seek(SeekFrom::End(0))?;
+    /// Decompresses the `count` points starting at the point of index `start`.
+    ///
+    /// The `out` slice must be able to hold at least `count` points,
+    /// otherwise an `InvalidInput` error is returned.
+    ///
+    /// Like seeking, after this call, decompression continues from the point following the range.
+    pub fn decompress_range(
+        &mut self,
+        start: u64,
+        count: u64,
+        out: &mut [u8],
+    ) -> crate::Result<()> {
+        let point_size = self.vlr.items_size() as usize;
+        if (out.len() as u64) < count.saturating_mul(point_size as u64) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "The out buffer is too small to hold the range of points",
+            )
+            .into());
+        }
+        if count == 0 {
+            return Ok(());
         }
+        let total = self
+            .seek_info
+            .as_ref()
+            .ok_or(MissingChunkTable)?
+            .chunk_index
+            .point_count();
+        if start.saturating_add(count) > total {
+            return Err(LasZipError::PointIndexOutOfBounds {
+                requested: start.saturating_add(count - 1),
+                total,
+            });
+        }
+
+        self.seek(start)?;
+        self.decompress_many(&mut out[..count as usize * point_size])?;
         Ok(())
     }
 
+    /// Decompresses `num_points` points and appends them to `out`,
+    /// without failing when the data is damaged (e.g. the file is truncated).
+    ///
+    /// Points are decompressed chunk by chunk, decompression stops at the first chunk
+    /// that cannot be fully decompressed, and the points of that chunk are not appended.
+    /// The returned report tells how many points were recovered and where the damage is.
+    ///
+    /// Errors that are not related to the decompression of the points
+    /// (e.g. failing to get the position of the source) are still returned.
+    pub fn decompress_recoverable(
+        &mut self,
+        num_points: u64,
+        out: &mut Vec<u8>,
+    ) -> crate::Result<RecoveryReport> {
+        let mut report = RecoveryReport::default();
+        let mut point = vec![0u8; self.vlr.items_size() as usize];
+        let mut chunk_points = Vec::<u8>::new();
+        let mut chunk_start = 0u64;
+        for _ in 0..num_points {
+            if self.chunk_points_read == self.num_points_in_chunk && !chunk_points.is_empty() {
+                report.points_recovered += (chunk_points.len() / point.len()) as u64;
+                report.last_good_chunk = Some(self.current_chunk);
+                out.append(&mut chunk_points);
+            }
+            if chunk_points.is_empty() {
+                chunk_start = self.get_mut().stream_position()?;
+            }
+            if self.decompress_one(&mut point).is_err() {
+                report.damage_offset = Some(chunk_start);
+                return Ok(report);
+            }
+            chunk_points.extend_from_slice(&point);
+        }
+        if !chunk_points.is_empty() {
+            report.points_recovered += (chunk_points.len() / point.len()) as u64;
+            report.last_good_chunk = Some(self.current_chunk);
+            out.append(&mut chunk_points);
+        }
+        Ok(report)
+    }
+
     /// Returns the vlr used.
     pub fn vlr(&self) -> &LazVlr {
         &self.vlr
     }
 
+    /// Returns the chunk table, if it could be read.
+    pub fn chunk_table(&self) -> Option<&ChunkTable> {
+        self.seek_info.as_ref().map(|info| &info.chunk_table)
+    }
+
     /// Consumes the decompressor and returns the data source.
     pub fn into_inner(self) -> R {
         self.record_decompressor.box_into_inner()
@@ -297,6 +457,26 @@ impl<'a, R: Read + Seek + Send + 'a> super::LazDecompressor for LasZipDecompress
     }
 }
 
+/// Moves the `source` after the offset to the chunk table.
+///
+/// `data_start` is the position of the offset, if it is known.
+fn skip_chunk_table_offset<R: Read + Seek>(
+    source: &mut R,
+    data_start: Option<u64>,
+) -> std::io::Result<()> {
+    match data_start {
+        Some(data_start) => {
+            source.seek(SeekFrom::Start(data_start + ChunkTable::OFFSET_SIZE as u64))?;
+        }
+        None => {
+            // We _may_ still be at the start of point data
+            let mut tmp = [0u8; ChunkTable::OFFSET_SIZE];
+            source.read_exact(&mut tmp)?;
+        }
+    }
+    Ok(())
+}
+
 /// Decompresses all points from the buffer
 ///
 /// The `compressed_points_data` slice must contain all the laszip data
@@ -1,5 +1,6 @@
 use std::io::{Read, Seek, Write};
 
+use crate::las::point6::DecompressionSelector;
 use crate::record::{
     LayeredPointRecordCompressor, LayeredPointRecordDecompressor, RecordCompressor,
     RecordDecompressor, SequentialPointRecordCompressor, SequentialPointRecordDecompressor,
@@ -9,6 +10,20 @@ use crate::{LasZipError, LazItem};
 pub(super) fn record_decompressor_from_laz_items<'a, R: Read + Seek + Send + 'a>(
     items: &Vec<LazItem>,
     input: R,
+) -> crate::Result<Box<dyn RecordDecompressor<R> + Send + 'a>> {
+    selective_record_decompressor_from_laz_items(
+        items,
+        input,
+        DecompressionSelector::decompress_all(),
+    )
+}
+
+/// The `selection` only has an effect on layered compressed data (LazItem version 3 and above),
+/// as point wise compressed data has to be fully decoded.
+pub(super) fn selective_record_decompressor_from_laz_items<'a, R: Read + Seek + Send + 'a>(
+    items: &Vec<LazItem>,
+    input: R,
+    selection: DecompressionSelector,
 ) -> crate::Result<Box<dyn RecordDecompressor<R> + Send + 'a>> {
     let first_item = items
         .get(0)
@@ -20,7 +35,7 @@ pub(super) fn record_decompressor_from_laz_items<'a, R: Read + Seek + Send + 'a>
             Box::new(decompressor) as Box<dyn RecordDecompressor<R> + Send>
         }
         3 | 4 => {
-            let decompressor = LayeredPointRecordDecompressor::new(input);
+            let decompressor = LayeredPointRecordDecompressor::selective(input, selection);
             Box::new(decompressor) as Box<dyn RecordDecompressor<R> + Send>
         }
         _ => {
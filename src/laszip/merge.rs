@@ -0,0 +1,198 @@
+//! Merging & extraction of LAZ data by copying the compressed chunks
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+
+use crate::laszip::chunk_table::{update_chunk_table_offset, ChunkIndex, ChunkTable};
+use crate::laszip::CompressorType;
+use crate::{LasZipError, LazVlr};
+
+/// Merges the LAZ data of the `sources` into the `dest`, without decompressing the points.
+///
+/// Each source comes with the [LazVlr] of its data and its number of points
+/// (e.g. the one of its LAS header), as fixed-size chunks do not store their number of points.
+/// The position of each source **must** be at the start of the point data,
+/// the position of the `dest` **must** be where the point data has to be written.
+///
+/// Chunks can be decompressed independently, so the compressed chunks of each source
+/// are copied one after the other and a chunk table covering all of them is written.
+///
+/// Returns the [LazVlr] of the merged data, that should be written in the LAS header:
+///
+/// - If all the sources use the same **fixed-size** chunks and the last chunk of every source
+///   (except the last one) is complete, the merged data uses the same fixed-size chunks.
+/// - Otherwise the merged data uses **variable-size** chunks.
+///
+/// Fails with [LasZipError::IncompatibleLazVlr] if the items of a source are not the same
+/// as the ones of the first source, and [LasZipError::MissingChunkTable] if a source
+/// does not have a chunk table.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> laz::Result<()> {
+/// # use std::fs::File;
+/// # let (tile_a, vlr_a, count_a): (File, laz::LazVlr, u64) = unimplemented!();
+/// # let (tile_b, vlr_b, count_b): (File, laz::LazVlr, u64) = unimplemented!();
+/// # let mut merged: File = unimplemented!();
+/// let merged_vlr = laz::merge(
+///     vec![(tile_a, vlr_a, count_a), (tile_b, vlr_b, count_b)],
+///     &mut merged,
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn merge<R, W, I>(sources: I, mut dest: W) -> crate::Result<LazVlr>
+where
+    R: Read + Seek,
+    W: Write + Seek,
+    I: IntoIterator<Item = (R, LazVlr, u64)>,
+{
+    let mut sources = sources
+        .into_iter()
+        .map(|(mut src, vlr, point_count)| {
+            let mut chunk_table = ChunkTable::read_from(&mut src, &vlr)?;
+            chunk_table.set_total_point_count(&vlr, point_count)?;
+            Ok((src, vlr, chunk_table))
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    let mut merged_vlr = match sources.first() {
+        Some((_, vlr, _)) => vlr.clone(),
+        None => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "No sources to merge",
+            )
+            .into())
+        }
+    };
+    if merged_vlr.compressor != CompressorType::PointWiseChunked
+        && merged_vlr.compressor != CompressorType::LayeredChunked
+    {
+        return Err(LasZipError::UnsupportedCompressorType(
+            merged_vlr.compressor,
+        ));
+    }
+
+    let mut keeps_fixed_size = !merged_vlr.uses_variable_size_chunks();
+    for (index, (_, vlr, chunk_table)) in sources.iter().enumerate() {
+        if vlr.compressor != merged_vlr.compressor || vlr.items() != merged_vlr.items() {
+            return Err(LasZipError::IncompatibleLazVlr { index });
+        }
+        let is_last_source = index + 1 == sources.len();
+        let last_chunk_is_partial = matches!(
+            chunk_table.as_ref().last(),
+            Some(entry) if entry.point_count != u64::from(vlr.chunk_size())
+        );
+        if vlr.uses_variable_size_chunks()
+            || vlr.chunk_size() != merged_vlr.chunk_size()
+            || (last_chunk_is_partial && !is_last_source)
+        {
+            keeps_fixed_size = false;
+        }
+    }
+    if !keeps_fixed_size {
+        merged_vlr.set_variable_size_chunks();
+    }
+
+    let offset_pos = dest.stream_position()?;
+    dest.write_i64::<LittleEndian>(-1)?;
+    let mut merged_chunk_table = ChunkTable::default();
+    for (src, _, chunk_table) in &mut sources {
+        let num_bytes = chunk_table
+            .as_ref()
+            .iter()
+            .map(|entry| entry.byte_count)
+            .sum::<u64>();
+        copy_bytes(src, &mut dest, num_bytes)?;
+        merged_chunk_table.extend(chunk_table);
+    }
+    update_chunk_table_offset(&mut dest, SeekFrom::Start(offset_pos))?;
+    merged_chunk_table.write_to(&mut dest, &merged_vlr)?;
+    Ok(merged_vlr)
+}
+
+/// Extracts the chunks at the `chunk_indices` of the `src` into the `dest`,
+/// without decompressing the points.
+///
+/// The position of the `src` **must** be at the start of the point data,
+/// and `point_count` is its number of points (e.g. the one of its LAS header),
+/// as fixed-size chunks do not store their number of points.
+/// The position of the `dest` **must** be where the point data has to be written.
+/// The chunks are written in the order of the `chunk_indices`, which do not
+/// have to be contiguous or sorted.
+///
+/// Returns the [LazVlr] of the extracted data, that should be written in the LAS header,
+/// and the number of points extracted.
+/// The extracted data keeps the fixed-size chunks of the `vlr` if all the extracted chunks,
+/// except the last one, are complete. Otherwise it uses **variable-size** chunks.
+///
+/// Fails with [LasZipError::ChunkIndexOutOfBounds] if an index is past the last chunk,
+/// and [LasZipError::MissingChunkTable] if the `src` does not have a chunk table.
+pub fn extract_chunks<R: Read + Seek, W: Write + Seek>(
+    mut src: R,
+    vlr: &LazVlr,
+    point_count: u64,
+    chunk_indices: &[usize],
+    mut dest: W,
+) -> crate::Result<(LazVlr, u64)> {
+    if vlr.compressor != CompressorType::PointWiseChunked
+        && vlr.compressor != CompressorType::LayeredChunked
+    {
+        return Err(LasZipError::UnsupportedCompressorType(vlr.compressor));
+    }
+    let mut chunk_table = ChunkTable::read_from(&mut src, vlr)?;
+    chunk_table.set_total_point_count(vlr, point_count)?;
+    let first_chunk_start = src.stream_position()?;
+    let chunk_index = ChunkIndex::new(&chunk_table);
+
+    let mut extracted_vlr = vlr.clone();
+    for (i, &chunk_idx) in chunk_indices.iter().enumerate() {
+        let entry =
+            chunk_table
+                .as_ref()
+                .get(chunk_idx)
+                .ok_or(LasZipError::ChunkIndexOutOfBounds {
+                    requested: chunk_idx,
+                    total: chunk_table.len(),
+                })?;
+        let is_last_chunk = i + 1 == chunk_indices.len();
+        if entry.point_count != u64::from(vlr.chunk_size()) && !is_last_chunk {
+            extracted_vlr.set_variable_size_chunks();
+        }
+    }
+
+    let offset_pos = dest.stream_position()?;
+    dest.write_i64::<LittleEndian>(-1)?;
+    let mut extracted_chunk_table = ChunkTable::with_capacity(chunk_indices.len());
+    for &chunk_idx in chunk_indices {
+        let entry = chunk_table[chunk_idx];
+        src.seek(SeekFrom::Start(
+            first_chunk_start + chunk_index.byte_offset_of_chunk(chunk_idx),
+        ))?;
+        copy_bytes(&mut src, &mut dest, entry.byte_count)?;
+        extracted_chunk_table.push(entry);
+    }
+    update_chunk_table_offset(&mut dest, SeekFrom::Start(offset_pos))?;
+    extracted_chunk_table.write_to(&mut dest, &extracted_vlr)?;
+
+    let point_count = extracted_chunk_table
+        .as_ref()
+        .iter()
+        .map(|entry| entry.point_count)
+        .sum();
+    Ok((extracted_vlr, point_count))
+}
+
+/// Copies exactly `num_bytes` from the `src` to the `dest`.
+fn copy_bytes<R: Read, W: Write>(src: R, mut dest: W, num_bytes: u64) -> std::io::Result<()> {
+    let num_copied = std::io::copy(&mut src.take(num_bytes), &mut dest)?;
+    if num_copied != num_bytes {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "The source has less bytes than its chunk table says",
+        ));
+    }
+    Ok(())
+}
@@ -3,20 +3,29 @@
 //!
 //! It defines the LaszipCompressor & LaszipDecompressor
 //! as well as the Laszip VLr data  and how to build it
-pub use chunk_table::{ChunkTable, ChunkTableEntry};
+pub use chunk_decoder::ChunkDecoder;
+pub(crate) use chunk_table::ChunkIndex;
+pub use chunk_table::{ChunkByteRange, ChunkTable, ChunkTableEntry};
 pub use compression::{compress_buffer, LasZipCompressor};
-pub use decompression::{decompress_buffer, LasZipDecompressor};
+pub use decompression::{decompress_buffer, LasZipDecompressor, RecoveryReport};
+pub use merge::{extract_chunks, merge};
+pub use rechunk::rechunk;
 pub use vlr::{
     CompressorType, DefaultVersion, LazItem, LazItemRecordBuilder, LazItemType, LazVlr,
-    LazVlrBuilder, Version1, Version2, Version3,
+    LazVlrBuilder, Version1, Version2, Version3, Version4,
 };
 
+#[cfg(feature = "async")]
+pub mod asynchronous;
+mod chunk_decoder;
 mod chunk_table;
 mod compression;
 mod decompression;
 mod details;
+mod merge;
 #[cfg(feature = "parallel")]
 pub mod parallel;
+mod rechunk;
 mod vlr;
 
 #[deprecated(since = "0.6.0", note = "Please use laz::LazVlr::USER_ID")]
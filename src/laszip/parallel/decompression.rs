@@ -2,10 +2,11 @@ use std::io::{Read, Seek, SeekFrom};
 
 use rayon::prelude::*;
 
-use crate::byteslice::ChunksIrregularMut;
-use crate::laszip::chunk_table::{ChunkTable, ChunkTableEntry};
+use crate::byteslice::{ChunksIrregular, ChunksIrregularMut};
+use crate::las::columns::{ColumnsLayout, PointColumns};
+use crate::laszip::chunk_table::{ChunkIndex, ChunkTable, ChunkTableEntry};
 use crate::laszip::details::record_decompressor_from_laz_items;
-use crate::laszip::CompressorType;
+use crate::laszip::{CompressorType, RecoveryReport};
 use crate::{LasZipError, LazVlr};
 
 #[cfg(feature = "parallel")]
@@ -16,6 +17,10 @@ pub struct ParLasZipDecompressor<R> {
     vlr: LazVlr,
     /// Table of chunks read from the source.
     chunk_table: ChunkTable,
+    /// Cumulative point & byte counts of the chunk table.
+    chunk_index: ChunkIndex,
+    /// false for the last fixed-size point-wise chunk, until the point count is set
+    last_point_count_is_known: bool,
     last_chunk_read: isize,
     /// Position of the first compressed point.
     start_of_data: u64,
@@ -46,6 +51,7 @@ impl<R: Read + Seek> ParLasZipDecompressor<R> {
         }
         let chunk_table = ChunkTable::read_from(&mut source, &vlr)?;
         let start_of_data = source.seek(SeekFrom::Current(0))?;
+        let chunk_index = ChunkIndex::new(&chunk_table);
         let biggest_chunk = chunk_table
             .as_ref()
             .into_iter()
@@ -55,10 +61,14 @@ impl<R: Read + Seek> ParLasZipDecompressor<R> {
         let vec = Vec::<u8>::with_capacity(biggest_chunk as usize);
         let rest = std::io::Cursor::new(vec);
 
+        let last_point_count_is_known =
+            vlr.compressor != CompressorType::PointWiseChunked || vlr.uses_variable_size_chunks();
         Ok(Self {
             source,
             vlr,
             chunk_table,
+            chunk_index,
+            last_point_count_is_known,
             rest,
             internal_buffer: vec![],
             last_chunk_read: -1,
@@ -180,55 +190,452 @@ impl<R: Read + Seek> ParLasZipDecompressor<R> {
         Ok(())
     }
 
+    /// Decompresses `num_points` points using multiple threads
+    /// and appends them to the `columns`.
+    ///
+    /// The chunks that are entirely requested are decompressed in batches
+    /// of one chunk per thread, straight from the field decompressors into the columns.
+    /// The points of a chunk that is only partly requested are decompressed as records
+    /// (like [decompress_many](Self::decompress_many) does, so the points after them
+    /// are kept for the next calls) and transposed into the columns.
+    pub fn decompress_columns(
+        &mut self,
+        num_points: usize,
+        columns: &mut PointColumns,
+    ) -> crate::Result<()> {
+        let layout = ColumnsLayout::from_laz_items(self.vlr.items());
+        columns.num_extra_bytes = layout.num_extra_bytes();
+        columns.reserve(&layout, num_points);
+
+        // 1. The points left in the rest by a previous call
+        let rest_start = self.rest.position() as usize;
+        let num_points_in_rest = (self.rest.get_ref().len() - rest_start) / layout.point_size;
+        let num_points_from_rest = num_points.min(num_points_in_rest);
+        let rest_end = rest_start + num_points_from_rest * layout.point_size;
+        columns.extend_from_points(&layout, &self.rest.get_ref()[rest_start..rest_end]);
+        self.rest.set_position(rest_end as u64);
+        let mut num_points_left = num_points - num_points_from_rest;
+
+        // 2. The chunks that are entirely requested
+        let num_threads = rayon::current_num_threads();
+        while num_points_left > 0 {
+            let start_index = (self.last_chunk_read + 1) as usize;
+            // The number of points of the last fixed-size point-wise chunk may not be known
+            let num_known_chunks = if self.last_point_count_is_known {
+                self.chunk_table.len()
+            } else {
+                self.chunk_table.len().saturating_sub(1)
+            };
+            let mut batch_chunks = Vec::<ChunkTableEntry>::with_capacity(num_threads);
+            let mut num_points_in_batch = 0usize;
+            let mut num_bytes_in_batch = 0usize;
+            for entry in self.chunk_table.as_ref()[..num_known_chunks]
+                .iter()
+                .skip(start_index)
+                .take(num_threads)
+            {
+                if num_points_in_batch + entry.point_count as usize > num_points_left {
+                    break;
+                }
+                num_points_in_batch += entry.point_count as usize;
+                num_bytes_in_batch += entry.byte_count as usize;
+                batch_chunks.push(*entry);
+            }
+            if batch_chunks.is_empty() {
+                break;
+            }
+            self.internal_buffer.resize(num_bytes_in_batch, 0u8);
+            self.source.read_exact(&mut self.internal_buffer)?;
+
+            let vlr = &self.vlr;
+            let input_chunks = ChunksIrregular::new(
+                &self.internal_buffer,
+                batch_chunks.iter().map(|entry| entry.byte_count as usize),
+            );
+            let mut chunks_columns = input_chunks
+                .zip(batch_chunks.iter())
+                .collect::<Vec<(&[u8], &ChunkTableEntry)>>()
+                .into_par_iter()
+                .map(|(chunk_in, entry)| {
+                    decompress_chunk_columns(chunk_in, entry.point_count as usize, vlr, &layout)
+                })
+                .collect::<crate::Result<Vec<PointColumns>>>()?;
+            for chunk_columns in &mut chunks_columns {
+                columns.append(chunk_columns);
+            }
+            self.last_chunk_read += batch_chunks.len() as isize;
+            num_points_left -= num_points_in_batch;
+        }
+
+        // 3. The points of the chunk that is only partly requested
+        if num_points_left > 0 {
+            let mut points = vec![0u8; num_points_left * layout.point_size];
+            self.decompress_many(&mut points)?;
+            columns.extend_from_points(&layout, &points);
+        }
+        Ok(())
+    }
+
+    /// Decompresses the `count` points starting at the point of index `start`
+    /// using multiple threads.
+    ///
+    /// Only the chunks covering the range are decompressed, each one in its own thread,
+    /// the points of the first chunk that are before `start` are decompressed
+    /// but not copied to `out`, and the points of the last chunk that are after the range
+    /// are kept for the next call to `decompress_many`.
+    ///
+    /// The `out` slice must be able to hold at least `count` points,
+    /// otherwise an `InvalidInput` error is returned.
+    ///
+    /// Like [LasZipDecompressor::decompress_range], after this call,
+    /// decompression continues from the point following the range.
+    ///
+    /// [LasZipDecompressor::decompress_range]: crate::LasZipDecompressor::decompress_range
+    pub fn decompress_range(
+        &mut self,
+        start: u64,
+        count: u64,
+        out: &mut [u8],
+    ) -> crate::Result<()> {
+        let point_size = self.vlr.items_size() as usize;
+        if (out.len() as u64) < count.saturating_mul(point_size as u64) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "The out buffer is too small to hold the range of points",
+            )
+            .into());
+        }
+        if count == 0 {
+            return Ok(());
+        }
+        let total = self.chunk_index.point_count();
+        if start.saturating_add(count) > total {
+            return Err(LasZipError::PointIndexOutOfBounds {
+                requested: start.saturating_add(count - 1),
+                total,
+            });
+        }
+        let end = start + count;
+        let first_chunk = self.chunk_index.chunk_of_point(start).unwrap();
+        let last_chunk = self.chunk_index.chunk_of_point(end - 1).unwrap();
+
+        // Read the compressed bytes of the chunks covering the range
+        let first_chunk_offset = self.chunk_index.byte_offset_of_chunk(first_chunk);
+        let num_bytes = self.chunk_index.byte_offset_of_chunk(last_chunk + 1) - first_chunk_offset;
+        let mut compressed_chunks = vec![0u8; num_bytes as usize];
+        self.source
+            .seek(SeekFrom::Start(self.start_of_data + first_chunk_offset))?;
+        self.source.read_exact(&mut compressed_chunks)?;
+
+        // For each chunk, the number of points to skip, to decompress, and to keep in the rest
+        let chunk_index = &self.chunk_index;
+        let points_of_chunks = (first_chunk..=last_chunk)
+            .map(|i| {
+                let chunk_start = chunk_index.first_point_of_chunk(i);
+                let chunk_end = chunk_index.first_point_of_chunk(i + 1);
+                let range_start = start.max(chunk_start);
+                let range_end = end.min(chunk_end);
+                (
+                    range_start - chunk_start,
+                    range_end - range_start,
+                    chunk_end - range_end,
+                )
+            })
+            .collect::<Vec<(u64, u64, u64)>>();
+
+        let input_chunks = ChunksIrregular::new(
+            &compressed_chunks,
+            self.chunk_table[first_chunk..=last_chunk]
+                .iter()
+                .map(|entry| entry.byte_count as usize),
+        );
+        let output_chunks = ChunksIrregularMut::new(
+            &mut out[..count as usize * point_size],
+            points_of_chunks
+                .iter()
+                .map(|(_, num_points, _)| *num_points as usize * point_size),
+        );
+        let decompression_jobs = input_chunks
+            .zip(output_chunks)
+            .zip(points_of_chunks.iter().copied())
+            .map(|((chunk_in, chunk_out), points_of_chunk)| (chunk_in, chunk_out, points_of_chunk))
+            .collect::<Vec<_>>();
+
+        let vlr = &self.vlr;
+        // The number of points of the last point-wise chunk may not be known,
+        // it is decompressed until its end, and only what is decompressed is kept
+        let last_chunk_size_is_unknown =
+            !self.last_point_count_is_known && last_chunk + 1 == self.chunk_table.len();
+        let first_point_of_last_chunk = chunk_index.first_point_of_chunk(last_chunk);
+        let num_jobs = decompression_jobs.len();
+        let mut rests = decompression_jobs
+            .into_par_iter()
+            .enumerate()
+            .map(
+                |(i, (chunk_in, chunk_out, (num_skipped, num_points, num_after)))| {
+                    let src = std::io::Cursor::new(chunk_in);
+                    let mut record_decompressor =
+                        record_decompressor_from_laz_items(vlr.items(), src)?;
+                    if last_chunk_size_is_unknown && i + 1 == num_jobs {
+                        let num_in_chunk = (num_skipped + num_points + num_after) as usize;
+                        let mut points = vec![0u8; num_in_chunk * point_size];
+                        let num_decompressed = record_decompressor
+                            .decompress_until_end_of_file(&mut points)?
+                            / point_size;
+                        let range_end = (num_skipped + num_points) as usize;
+                        if num_decompressed < range_end {
+                            return Err(LasZipError::PointIndexOutOfBounds {
+                                requested: first_point_of_last_chunk + range_end as u64 - 1,
+                                total: first_point_of_last_chunk + num_decompressed as u64,
+                            });
+                        }
+                        chunk_out.copy_from_slice(
+                            &points[num_skipped as usize * point_size..range_end * point_size],
+                        );
+                        points.truncate(num_decompressed * point_size);
+                        return Ok(points.split_off(range_end * point_size));
+                    }
+                    let mut skipped_point = vec![0u8; point_size];
+                    for _ in 0..num_skipped {
+                        record_decompressor.decompress_next(&mut skipped_point)?;
+                    }
+                    record_decompressor.decompress_many(chunk_out)?;
+                    // Only the last chunk can have points after the range
+                    let mut rest = vec![0u8; num_after as usize * point_size];
+                    record_decompressor.decompress_many(&mut rest)?;
+                    Ok(rest)
+                },
+            )
+            .collect::<crate::Result<Vec<Vec<u8>>>>()?;
+
+        // Continue after the range
+        *self.rest.get_mut() = rests.pop().unwrap_or_default();
+        self.rest.set_position(0);
+        self.last_chunk_read = last_chunk as isize;
+        Ok(())
+    }
+
+    /// Decompresses `num_points` points using multiple threads and appends them to `out`,
+    /// without failing when the data is damaged.
+    ///
+    /// Decompression stops at the first chunk that cannot be fully read or decompressed,
+    /// and the points of that chunk are not appended.
+    /// The returned report tells how many points were recovered and where the damage is.
+    ///
+    /// This decompressor needs the chunk table which is lost when a file is truncated,
+    /// for such files use [LasZipDecompressor::decompress_recoverable].
+    ///
+    /// [LasZipDecompressor::decompress_recoverable]: crate::LasZipDecompressor::decompress_recoverable
+    pub fn decompress_recoverable(
+        &mut self,
+        num_points: u64,
+        out: &mut Vec<u8>,
+    ) -> crate::Result<RecoveryReport> {
+        let point_size = self.vlr.items_size() as usize;
+        let mut report = RecoveryReport::default();
+        let mut num_points_left = num_points;
+
+        // 1. Points of the current chunk that were already decompressed
+        let num_points_in_rest =
+            (self.rest.get_ref().len() - self.rest.position() as usize) / point_size;
+        let num_points_from_rest = (num_points_in_rest as u64).min(num_points_left);
+        let rest_start = self.rest.position() as usize;
+        out.extend_from_slice(
+            &self.rest.get_ref()
+                [rest_start..rest_start + num_points_from_rest as usize * point_size],
+        );
+        self.rest
+            .set_position((rest_start + num_points_from_rest as usize * point_size) as u64);
+        num_points_left -= num_points_from_rest;
+        report.points_recovered += num_points_from_rest;
+        if self.last_chunk_read >= 0 {
+            report.last_good_chunk = Some(self.last_chunk_read as usize);
+        }
+        if num_points_left == 0 {
+            return Ok(report);
+        }
+        self.rest.get_mut().clear();
+        self.rest.set_position(0);
+
+        // 2. Read & decompress the next chunks, as many chunks as there are threads at a time
+        let num_chunks_per_batch = rayon::current_num_threads().max(1);
+        let mut next_chunk = (self.last_chunk_read + 1) as usize;
+        self.source.seek(SeekFrom::Start(
+            self.start_of_data + self.chunk_index.byte_offset_of_chunk(next_chunk),
+        ))?;
+        while num_points_left > 0 {
+            if next_chunk >= self.chunk_table.len() {
+                // There are less points than requested
+                report.damage_offset =
+                    Some(self.start_of_data + self.chunk_index.byte_offset_of_chunk(next_chunk));
+                return Ok(report);
+            }
+            let batch_end = (next_chunk + num_chunks_per_batch).min(self.chunk_table.len());
+
+            let mut damaged_chunk = None;
+            self.internal_buffer.clear();
+            for (i, entry) in self.chunk_table[next_chunk..batch_end].iter().enumerate() {
+                let chunk_start = self.internal_buffer.len();
+                self.internal_buffer
+                    .resize(chunk_start + entry.byte_count as usize, 0u8);
+                if self
+                    .source
+                    .read_exact(&mut self.internal_buffer[chunk_start..])
+                    .is_err()
+                {
+                    self.internal_buffer.truncate(chunk_start);
+                    damaged_chunk = Some(next_chunk + i);
+                    break;
+                }
+            }
+            let read_chunks_end = damaged_chunk.unwrap_or(batch_end);
+
+            let read_chunks = &self.chunk_table[next_chunk..read_chunks_end];
+            let input_chunks = ChunksIrregular::new(
+                &self.internal_buffer,
+                read_chunks.iter().map(|entry| entry.byte_count as usize),
+            );
+            let vlr = &self.vlr;
+            // The number of points of the last point-wise chunk may not be known,
+            // its points are the ones that can be decompressed from its bytes
+            let unknown_size_chunk = if self.last_point_count_is_known {
+                None
+            } else {
+                Some(self.chunk_table.len() - 1)
+            };
+            let decompressed_chunks = input_chunks
+                .zip(read_chunks.iter())
+                .zip(next_chunk..)
+                .collect::<Vec<((&[u8], &ChunkTableEntry), usize)>>()
+                .into_par_iter()
+                .map(|((chunk_in, entry), chunk_idx)| {
+                    let src = std::io::Cursor::new(chunk_in);
+                    let mut record_decompressor =
+                        record_decompressor_from_laz_items(vlr.items(), src)?;
+                    let mut points = vec![0u8; entry.point_count as usize * point_size];
+                    if unknown_size_chunk == Some(chunk_idx) {
+                        let num_bytes =
+                            record_decompressor.decompress_until_end_of_file(&mut points)?;
+                        points.truncate(num_bytes);
+                    } else {
+                        record_decompressor.decompress_many(&mut points)?;
+                    }
+                    Ok(points)
+                })
+                .collect::<Vec<crate::Result<Vec<u8>>>>();
+
+            for (chunk_idx, decompressed_chunk) in
+                (next_chunk..read_chunks_end).zip(decompressed_chunks)
+            {
+                let points = match decompressed_chunk {
+                    Ok(points) => points,
+                    Err(_) => {
+                        damaged_chunk = Some(chunk_idx);
+                        break;
+                    }
+                };
+                let num_points_in_chunk = (points.len() / point_size) as u64;
+                let num_points_taken = num_points_in_chunk.min(num_points_left);
+                out.extend_from_slice(&points[..num_points_taken as usize * point_size]);
+                if num_points_taken < num_points_in_chunk {
+                    // Keep the points that were not requested for the next calls
+                    self.rest = std::io::Cursor::new(points);
+                    self.rest.set_position(num_points_taken * point_size as u64);
+                }
+                num_points_left -= num_points_taken;
+                report.points_recovered += num_points_taken;
+                report.last_good_chunk = Some(chunk_idx);
+                self.last_chunk_read = chunk_idx as isize;
+                if num_points_left == 0 {
+                    break;
+                }
+            }
+
+            if let Some(chunk_idx) = damaged_chunk {
+                report.damage_offset =
+                    Some(self.start_of_data + self.chunk_index.byte_offset_of_chunk(chunk_idx));
+                return Ok(report);
+            }
+            next_chunk = batch_end;
+        }
+
+        // Put the source at the start of the chunk following the last one decompressed
+        self.source.seek(SeekFrom::Start(
+            self.start_of_data
+                + self
+                    .chunk_index
+                    .byte_offset_of_chunk((self.last_chunk_read + 1) as usize),
+        ))?;
+        Ok(report)
+    }
+
+    /// Sets the number of points of the compressed data (e.g. the one of the LAS header).
+    ///
+    /// The last **fixed-size** chunk of point formats < 6 does not store its number of points,
+    /// so until this is called, that chunk is decompressed until the end of its bytes
+    /// to find its points.
+    ///
+    /// Returns an `InvalidData` error if the chunks cannot hold `point_count` points.
+    pub fn set_point_count(&mut self, point_count: u64) -> crate::Result<()> {
+        self.chunk_table
+            .set_total_point_count(&self.vlr, point_count)?;
+        self.chunk_index = ChunkIndex::new(&self.chunk_table);
+        self.last_point_count_is_known = true;
+        Ok(())
+    }
+
     /// Seeks to the position of the point at the given index
     pub fn seek(&mut self, index: u64) -> crate::Result<()> {
         // Throw away what's in the rest buffer
         self.rest.set_position(0);
         self.rest.get_mut().clear();
 
-        let chunk_of_point = (index / self.vlr.chunk_size() as u64) as usize;
-        if chunk_of_point >= self.chunk_table.len() {
-            let _ = self.source.seek(SeekFrom::End(0))?;
-            return Ok(());
-        }
+        let chunk_of_point =
+            self.chunk_index
+                .chunk_of_point(index)
+                .ok_or(LasZipError::PointIndexOutOfBounds {
+                    requested: index,
+                    total: self.chunk_index.point_count(),
+                })?;
+        let pos_in_chunk = index - self.chunk_index.first_point_of_chunk(chunk_of_point);
         // Seek to the start of the points chunk
         // and read the chunk data
-        let start_of_chunk_pos = self.start_of_data
-            + self.chunk_table[..chunk_of_point]
-                .iter()
-                .map(|entry| entry.byte_count)
-                .sum::<u64>();
+        let start_of_chunk_pos =
+            self.start_of_data + self.chunk_index.byte_offset_of_chunk(chunk_of_point);
         self.source.seek(SeekFrom::Start(start_of_chunk_pos))?;
         self.internal_buffer
             .resize(self.chunk_table[chunk_of_point].byte_count as usize, 0u8);
-        self.source.read(&mut self.internal_buffer)?;
+        self.source.read_exact(&mut self.internal_buffer)?;
 
         // Completely decompress the chunk
-        self.rest
-            .get_mut()
-            .resize(self.vlr.num_bytes_in_decompressed_chunk() as usize, 0u8);
+        self.rest.get_mut().resize(
+            self.chunk_table[chunk_of_point].point_count as usize * self.vlr.items_size() as usize,
+            0u8,
+        );
         let mut decompressor = record_decompressor_from_laz_items(
             self.vlr.items(),
             std::io::Cursor::new(&self.internal_buffer),
         )?;
-        let is_last_chunk = chunk_of_point == (self.chunk_table.len() - 1);
-        if is_last_chunk {
-            let num_bytes_decompressed =
-                decompressor.decompress_until_end_of_file(self.rest.get_mut())?;
-            let num_points_in_last_chunk = num_bytes_decompressed / self.vlr.items_size() as usize;
-            let pos_in_chunk = index % self.vlr.chunk_size() as u64;
-            if pos_in_chunk as usize >= num_points_in_last_chunk as usize {
-                // Make the rest appear as fully consumed to
-                // force EOF error on next decompression
-                self.rest.set_position(self.rest.get_ref().len() as u64);
-                return Ok(());
+        let is_last_chunk = chunk_of_point + 1 == self.chunk_table.len();
+        if is_last_chunk && !self.last_point_count_is_known {
+            // Unless set_point_count was called, the number of points
+            // of the last point-wise chunk is not known, so decompress all it has
+            let num_bytes = decompressor.decompress_until_end_of_file(self.rest.get_mut())?;
+            self.rest.get_mut().truncate(num_bytes);
+            let num_points_in_chunk = num_bytes as u64 / self.vlr.items_size();
+            if pos_in_chunk >= num_points_in_chunk {
+                self.rest.get_mut().clear();
+                return Err(LasZipError::PointIndexOutOfBounds {
+                    requested: index,
+                    total: self.chunk_index.first_point_of_chunk(chunk_of_point)
+                        + num_points_in_chunk,
+                });
             }
         } else {
             decompressor.decompress_many(self.rest.get_mut())?;
         }
         // This effectively discard points that were
         // before the one we just seeked to
-        let pos_in_chunk = index % self.vlr.chunk_size() as u64;
         self.rest.set_position(pos_in_chunk * self.vlr.items_size());
         self.last_chunk_read = chunk_of_point as isize;
         Ok(())
@@ -294,6 +701,34 @@ pub fn par_decompress_buffer(
     )
 }
 
+/// Decompresses the `num_points` points of a chunk into new columns.
+#[cfg(feature = "parallel")]
+fn decompress_chunk_columns(
+    chunk: &[u8],
+    num_points: usize,
+    laz_vlr: &LazVlr,
+    layout: &ColumnsLayout,
+) -> crate::Result<PointColumns> {
+    let src = std::io::Cursor::new(chunk);
+    let mut record_decompressor = record_decompressor_from_laz_items(laz_vlr.items(), src)?;
+    let mut columns = PointColumns {
+        num_extra_bytes: layout.num_extra_bytes(),
+        ..Default::default()
+    };
+    columns.reserve(layout, num_points);
+    let mut point = vec![0u8; layout.point_size];
+    for i in 0..num_points {
+        record_decompressor.decompress_next(&mut point)?;
+        if i == 0 {
+            // The first point of a chunk is not compressed
+            columns.push_point(layout, &point);
+        } else {
+            record_decompressor.push_last_to_columns(&mut columns);
+        }
+    }
+    Ok(columns)
+}
+
 /// Actual the parallel decompression
 ///
 /// `compressed_points` must contains only the bytes corresponding to the points
@@ -307,7 +742,6 @@ pub fn par_decompress(
     laz_vlr: &LazVlr,
     chunk_table: &[ChunkTableEntry],
 ) -> crate::Result<()> {
-    use crate::byteslice::ChunksIrregular;
     let sizes = chunk_table.iter().map(|entry| entry.byte_count as usize);
     let counts = chunk_table
         .iter()
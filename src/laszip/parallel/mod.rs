@@ -1,6 +1,8 @@
 pub use compression::{par_compress, par_compress_buffer, ParLasZipCompressor};
 pub use decompression::par_decompress;
 pub use decompression::{par_decompress_buffer, ParLasZipDecompressor};
+pub use rechunk::par_rechunk;
 
 mod compression;
 mod decompression;
+mod rechunk;
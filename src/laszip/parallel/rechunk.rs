@@ -0,0 +1,67 @@
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use crate::byteslice::ChunksIrregular;
+use crate::laszip::chunk_table::ChunkTable;
+use crate::laszip::parallel::{ParLasZipCompressor, ParLasZipDecompressor};
+use crate::laszip::rechunk::check_same_points;
+use crate::LazVlr;
+
+/// Compresses the points of the `source` again into the `dest`, using the chunks of the `new_vlr`.
+///
+/// Just like [rechunk](crate::rechunk) but the decompression & compression is done
+/// using multiple threads.
+/// To bound the memory used, only as many chunks as there are threads are decompressed
+/// at the same time.
+#[cfg(feature = "parallel")]
+pub fn par_rechunk<R, W>(
+    mut source: R,
+    src_vlr: LazVlr,
+    point_count: u64,
+    dest: W,
+    new_vlr: LazVlr,
+) -> crate::Result<()>
+where
+    R: Read + Seek,
+    W: Write + Seek + Send,
+{
+    check_same_points(&src_vlr, &new_vlr)?;
+    let data_start = source.stream_position()?;
+    let mut chunk_table = ChunkTable::read_from(&mut source, &src_vlr)?;
+    chunk_table.set_total_point_count(&src_vlr, point_count)?;
+    source.seek(SeekFrom::Start(data_start))?;
+
+    let point_size = src_vlr.items_size() as usize;
+    let num_threads = rayon::current_num_threads();
+    let mut decompressor = ParLasZipDecompressor::new(source, src_vlr)?;
+    let mut compressor = ParLasZipCompressor::new(dest, new_vlr)?;
+    let mut points = Vec::<u8>::new();
+    if compressor.vlr().uses_variable_size_chunks() {
+        let entries = chunk_table
+            .as_ref()
+            .iter()
+            .filter(|entry| entry.point_count != 0)
+            .copied()
+            .collect::<Vec<_>>();
+        for batch in entries.chunks(num_threads) {
+            let point_counts = batch
+                .iter()
+                .map(|entry| entry.point_count as usize * point_size);
+            points.resize(point_counts.clone().sum(), 0);
+            decompressor.decompress_many(&mut points)?;
+            let chunks = ChunksIrregular::new(&points, point_counts).collect::<Vec<_>>();
+            compressor.compress_chunks(chunks)?;
+        }
+    } else {
+        let batch_size = u64::from(compressor.vlr().chunk_size()) * num_threads as u64;
+        let mut num_points_left = point_count;
+        while num_points_left > 0 {
+            let num_points = num_points_left.min(batch_size);
+            points.resize(num_points as usize * point_size, 0);
+            decompressor.decompress_many(&mut points)?;
+            compressor.compress_many(&points)?;
+            num_points_left -= num_points;
+        }
+    }
+    compressor.done()?;
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! Re-compression of LAZ data with a different chunk size
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use crate::laszip::chunk_table::ChunkTable;
+use crate::laszip::{LasZipCompressor, LasZipDecompressor};
+use crate::{LazItem, LazVlr};
+
+/// Compresses the points of the `source` again into the `dest`, using the chunks of the `new_vlr`.
+///
+/// The position of the `source` **must** be at the start of the point data,
+/// the position of the `dest` **must** be where the point data has to be written.
+/// The `source` must have a chunk table, and `point_count` is its number of points
+/// (e.g. the one of its LAS header), as fixed-size chunks do not store their number of points.
+///
+/// The points are decompressed & compressed one chunk at a time:
+///
+/// - If the `new_vlr` uses **fixed-size** chunks, points are grouped in chunks of its `chunk_size`.
+/// - If the `new_vlr` uses **variable-size** chunks, each chunk of the `source` stays a chunk.
+///
+/// The items of the `new_vlr` must describe the same points as the ones of the `src_vlr`,
+/// only their compression version may differ.
+///
+/// See [par_rechunk](crate::par_rechunk) (`parallel` feature) to decompress & compress
+/// using multiple threads.
+pub fn rechunk<R, W>(
+    mut source: R,
+    src_vlr: LazVlr,
+    point_count: u64,
+    dest: W,
+    new_vlr: LazVlr,
+) -> crate::Result<()>
+where
+    R: Read + Seek + Send,
+    W: Write + Seek + Send,
+{
+    check_same_points(&src_vlr, &new_vlr)?;
+    let data_start = source.stream_position()?;
+    let mut chunk_table = ChunkTable::read_from(&mut source, &src_vlr)?;
+    chunk_table.set_total_point_count(&src_vlr, point_count)?;
+    source.seek(SeekFrom::Start(data_start))?;
+
+    let point_size = src_vlr.items_size() as usize;
+    let mut decompressor = LasZipDecompressor::new(source, src_vlr)?;
+    let mut compressor = LasZipCompressor::new(dest, new_vlr)?;
+    let mut points = Vec::<u8>::new();
+    if compressor.vlr().uses_variable_size_chunks() {
+        let entries = chunk_table
+            .as_ref()
+            .iter()
+            .filter(|entry| entry.point_count != 0)
+            .collect::<Vec<_>>();
+        for (i, entry) in entries.iter().enumerate() {
+            points.resize(entry.point_count as usize * point_size, 0);
+            decompressor.decompress_many(&mut points)?;
+            compressor.compress_many(&points)?;
+            // The last chunk is finished by `done`,
+            // finishing it here would add an empty chunk
+            if i + 1 != entries.len() {
+                compressor.finish_current_chunk()?;
+            }
+        }
+    } else {
+        let chunk_size = u64::from(compressor.vlr().chunk_size());
+        let mut num_points_left = point_count;
+        while num_points_left > 0 {
+            let num_points = num_points_left.min(chunk_size);
+            points.resize(num_points as usize * point_size, 0);
+            decompressor.decompress_many(&mut points)?;
+            compressor.compress_many(&points)?;
+            num_points_left -= num_points;
+        }
+    }
+    compressor.done()?;
+    Ok(())
+}
+
+/// Checks that the items of both vlrs describe the same points.
+pub(super) fn check_same_points(src_vlr: &LazVlr, new_vlr: &LazVlr) -> std::io::Result<()> {
+    let is_same_item = |(src_item, new_item): (&LazItem, &LazItem)| {
+        src_item.item_type() == new_item.item_type() && src_item.size() == new_item.size()
+    };
+    if src_vlr.items().len() != new_vlr.items().len()
+        || !src_vlr
+            .items()
+            .iter()
+            .zip(new_vlr.items())
+            .all(is_same_item)
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "The items of the new vlr do not describe the same points",
+        ));
+    }
+    Ok(())
+}
@@ -4,6 +4,7 @@ use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 
 use crate::las::nir::Nir;
 use crate::las::pointtypes::RGB;
+use crate::las::wavepacket::Wavepacket;
 use crate::las::{Point0, Point6};
 use crate::LasZipError;
 
@@ -54,14 +55,16 @@ pub enum LazItemType {
     GpsTime,
     /// RGB for LAS versions <= 1.3 & point format <= 5
     RGB12,
-    //WavePacket13,
+    /// Wave packet descriptor for LAS versions <= 1.3 & point format 4 & 5
+    WavePacket13,
     /// Point14 is the Point format id 6 of LAS for versions >= 1.4 & point format >= 6
     Point14,
     /// RGB for LAS versions >= 1.4
     RGB14,
     /// RGB + Nir for LAS versions >= 1.4
     RGBNIR14,
-    //WavePacket14,
+    /// Wave packet descriptor for LAS versions >= 1.4
+    WavePacket14,
     /// ExtraBytes for LAS versions >= 1.4
     Byte14(u16),
 }
@@ -73,11 +76,11 @@ impl LazItemType {
             6 => Some(LazItemType::Point10),
             7 => Some(LazItemType::GpsTime),
             8 => Some(LazItemType::RGB12),
-            //9 => LazItemType::WavePacket13,
+            9 => Some(LazItemType::WavePacket13),
             10 => Some(LazItemType::Point14),
             11 => Some(LazItemType::RGB14),
             12 => Some(LazItemType::RGBNIR14),
-            //13 => LazItemType::WavePacket14,
+            13 => Some(LazItemType::WavePacket14),
             14 => Some(LazItemType::Byte14(size)),
             _ => None,
         }
@@ -89,9 +92,11 @@ impl LazItemType {
             LazItemType::Point10 => Point0::SIZE as u16,
             LazItemType::GpsTime => std::mem::size_of::<f64>() as u16,
             LazItemType::RGB12 => RGB::SIZE as u16,
+            LazItemType::WavePacket13 => Wavepacket::SIZE as u16,
             LazItemType::Point14 => Point6::SIZE as u16,
             LazItemType::RGB14 => RGB::SIZE as u16,
             LazItemType::RGBNIR14 => (RGB::SIZE + Nir::SIZE) as u16,
+            LazItemType::WavePacket14 => Wavepacket::SIZE as u16,
             LazItemType::Byte14(size) => *size,
         }
     }
@@ -102,9 +107,11 @@ impl LazItemType {
             LazItemType::Point10 => 2,
             LazItemType::GpsTime => 2,
             LazItemType::RGB12 => 2,
+            LazItemType::WavePacket13 => 1,
             LazItemType::Point14 => 3,
             LazItemType::RGB14 => 3,
             LazItemType::RGBNIR14 => 3,
+            LazItemType::WavePacket14 => 3,
             LazItemType::Byte14(_) => 3,
         }
     }
@@ -117,11 +124,11 @@ impl From<LazItemType> for u16 {
             LazItemType::Point10 => 6,
             LazItemType::GpsTime => 7,
             LazItemType::RGB12 => 8,
-            //LazItemType::WavePacket13 => 9,
+            LazItemType::WavePacket13 => 9,
             LazItemType::Point14 => 10,
             LazItemType::RGB14 => 11,
             LazItemType::RGBNIR14 => 12,
-            //LazItemType::WavePacket14 => 13,
+            LazItemType::WavePacket14 => 13,
             LazItemType::Byte14(_) => 14,
         }
     }
@@ -205,6 +212,7 @@ define_trait_for_version!(DefaultVersion, default_version);
 define_trait_for_version!(Version1, version_1);
 define_trait_for_version!(Version2, version_2);
 define_trait_for_version!(Version3, version_3);
+define_trait_for_version!(Version4, version_4);
 
 pub struct LazItemRecordBuilder {
     items: Vec<LazItemType>,
@@ -237,11 +245,22 @@ impl LazItemRecordBuilder {
         PointFormat::version_3(num_extra_bytes)
     }
 
+    ///```
+    /// let items = laz::LazItemRecordBuilder::version_4_of::<laz::las::Point6>(0);
+    ///```
+    ///
+    /// ```compile_fail
+    /// let items = laz::LazItemRecordBuilder::version_4_of::<laz::las::Point0>(0);
+    /// ```
+    pub fn version_4_of<PointFormat: Version4>(num_extra_bytes: u16) -> Vec<LazItem> {
+        PointFormat::version_4(num_extra_bytes)
+    }
+
     pub fn default_for_point_format_id(
         point_format_id: u8,
         num_extra_bytes: u16,
     ) -> crate::Result<Vec<LazItem>> {
-        use crate::las::{Point1, Point2, Point3, Point7, Point8};
+        use crate::las::{Point1, Point10, Point2, Point3, Point4, Point5, Point7, Point8, Point9};
         match point_format_id {
             0 => Ok(LazItemRecordBuilder::default_version_of::<Point0>(
                 num_extra_bytes,
@@ -255,6 +274,12 @@ impl LazItemRecordBuilder {
             3 => Ok(LazItemRecordBuilder::default_version_of::<Point3>(
                 num_extra_bytes,
             )),
+            4 => Ok(LazItemRecordBuilder::default_version_of::<Point4>(
+                num_extra_bytes,
+            )),
+            5 => Ok(LazItemRecordBuilder::default_version_of::<Point5>(
+                num_extra_bytes,
+            )),
             6 => Ok(LazItemRecordBuilder::default_version_of::<Point6>(
                 num_extra_bytes,
             )),
@@ -264,6 +289,12 @@ impl LazItemRecordBuilder {
             8 => Ok(LazItemRecordBuilder::default_version_of::<Point8>(
                 num_extra_bytes,
             )),
+            9 => Ok(LazItemRecordBuilder::default_version_of::<Point9>(
+                num_extra_bytes,
+            )),
+            10 => Ok(LazItemRecordBuilder::default_version_of::<Point10>(
+                num_extra_bytes,
+            )),
             _ => Err(LasZipError::UnsupportedPointFormat(point_format_id)),
         }
     }
@@ -370,7 +401,7 @@ pub struct LazVlr {
 
     // -1 if unused
     number_of_special_evlrs: i64,
-    // -1 if unused
+    // -1 if unused, offset from the start of the file
     offset_to_special_evlrs: i64,
 
     items: Vec<LazItem>,
@@ -458,6 +489,11 @@ impl LazVlr {
         self.chunk_size == Self::VARIABLE_CHUNK_SIZE
     }
 
+    /// Makes the vlr describe **variable-size** chunks.
+    pub(crate) fn set_variable_size_chunks(&mut self) {
+        self.chunk_size = Self::VARIABLE_CHUNK_SIZE;
+    }
+
     /// Returns the chunk size, that is, the number of points in each chunk.
     ///
     /// This is only valid if [`Self::uses_variable_size_chunks`] returns false.
@@ -466,6 +502,29 @@ impl LazVlr {
         self.chunk_size
     }
 
+    /// Returns the number of special EVLRs, -1 if unused.
+    #[inline]
+    pub fn number_of_special_evlrs(&self) -> i64 {
+        self.number_of_special_evlrs
+    }
+
+    /// Returns the offset from the start of the file to the first special EVLR, -1 if unused.
+    #[inline]
+    pub fn offset_to_special_evlrs(&self) -> i64 {
+        self.offset_to_special_evlrs
+    }
+
+    /// Sets the number of the EVLRs written after the compressed points
+    /// (and the chunk table) and the offset to the first one.
+    pub fn set_special_evlrs(
+        &mut self,
+        number_of_special_evlrs: u64,
+        offset_to_special_evlrs: u64,
+    ) {
+        self.number_of_special_evlrs = number_of_special_evlrs as i64;
+        self.offset_to_special_evlrs = offset_to_special_evlrs as i64;
+    }
+
     /// Returns the items compressed by this VLR
     #[inline]
     pub fn items(&self) -> &Vec<LazItem> {
@@ -476,11 +535,11 @@ impl LazVlr {
     /// expected size of points (uncompressed).
     #[inline]
     pub fn items_size(&self) -> u64 {
-        u64::from(self.items.iter().map(|item| item.size).sum::<u16>())
+        self.items.iter().map(|item| u64::from(item.size)).sum()
     }
 
     /// returns how many bytes a decompressed chunk contains
-    #[cfg(feature = "parallel")]
+    #[cfg(any(feature = "parallel", feature = "async"))]
     #[inline]
     pub(crate) fn num_bytes_in_decompressed_chunk(&self) -> u64 {
         self.chunk_size as u64 * self.items_size()
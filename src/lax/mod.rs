@@ -0,0 +1,406 @@
+//! Reading and writing of LASindex (`.lax`) files.
+//!
+//! A `.lax` file is the spatial index written by LAStools' `lasindex` next to a LAS/LAZ file.
+//! It is made of:
+//!
+//! - a quadtree ([LaxQuadtree]) covering the x/y extent of the points,
+//! - the cells of the quadtree that contain points ([LaxCell]),
+//!   each one giving the intervals of point indices of the points it contains.
+//!
+//! Querying the index with a rectangle gives the intervals of points that may be inside,
+//! these intervals can then be decompressed with [decompress_intervals]
+//! which only decompresses the chunks containing them.
+//!
+//! Indexes are created with a [LaxIndexBuilder], or while compressing
+//! with a [LaxIndexingCompressor].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # fn main() -> laz::Result<()> {
+//! use laz::lax::{decompress_intervals, LaxIndex};
+//!
+//! let index = LaxIndex::read_from(std::fs::File::open("points.lax")?)?;
+//! let intervals = index.query(637_000.0, 851_000.0, 638_000.0, 852_000.0);
+//!
+//! # let (source, vlr): (std::io::Cursor<Vec<u8>>, laz::LazVlr) = unimplemented!();
+//! let mut decompressor = laz::LasZipDecompressor::new(source, vlr)?;
+//! let mut points = Vec::new();
+//! decompress_intervals(&mut decompressor, &intervals, &mut points)?;
+//! # Ok(())
+//! # }
+//! ```
+use std::convert::TryFrom;
+use std::io::{Read, Seek, Write};
+use std::ops::Range;
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+use crate::laszip::{ChunkIndex, ChunkTable, LasZipDecompressor};
+use crate::LasZipError;
+
+pub use writer::{sort_points_by_cell, LaxIndexBuilder, LaxIndexingCompressor};
+
+mod writer;
+
+const LAX_SIGNATURE: &[u8; 4] = b"LASX";
+const SPATIAL_SIGNATURE: &[u8; 4] = b"LASS";
+const QUADTREE_SIGNATURE: &[u8; 4] = b"LASQ";
+const INTERVAL_SIGNATURE: &[u8; 4] = b"LASV";
+
+/// Type of spatial index stored after the `LASS` signature,
+/// quadtrees are the only one that exists.
+const QUADTREE_TYPE: u32 = 0;
+
+fn read_signature<R: Read>(src: &mut R, expected: &[u8; 4]) -> std::io::Result<()> {
+    let mut signature = [0u8; 4];
+    src.read_exact(&mut signature)?;
+    if &signature != expected {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "Invalid LASindex signature, expected '{}'",
+                String::from_utf8_lossy(expected)
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Largest number of levels, so that the index of the cells fits in an `i32`.
+const MAX_LEVELS: u32 = 15;
+
+/// The quadtree of a LASindex.
+///
+/// Cells are numbered level by level, the root being cell 0,
+/// its 4 children cells 1 to 4, their 16 children cells 5 to 20 and so on.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LaxQuadtree {
+    pub version: u32,
+    /// Number of levels of the quadtree
+    pub levels: u32,
+    pub level_index: u32,
+    pub implicit_levels: u32,
+    /// Extent of the root cell
+    pub min_x: f32,
+    pub max_x: f32,
+    pub min_y: f32,
+    pub max_y: f32,
+}
+
+impl LaxQuadtree {
+    fn read_from<R: Read>(mut src: R) -> std::io::Result<Self> {
+        read_signature(&mut src, SPATIAL_SIGNATURE)?;
+        let spatial_type = src.read_u32::<LittleEndian>()?;
+        if spatial_type != QUADTREE_TYPE {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Unknown LASindex spatial type {}", spatial_type),
+            ));
+        }
+        read_signature(&mut src, QUADTREE_SIGNATURE)?;
+        Ok(Self {
+            version: src.read_u32::<LittleEndian>()?,
+            levels: src.read_u32::<LittleEndian>()?,
+            level_index: src.read_u32::<LittleEndian>()?,
+            implicit_levels: src.read_u32::<LittleEndian>()?,
+            min_x: src.read_f32::<LittleEndian>()?,
+            max_x: src.read_f32::<LittleEndian>()?,
+            min_y: src.read_f32::<LittleEndian>()?,
+            max_y: src.read_f32::<LittleEndian>()?,
+        })
+    }
+
+    fn write_to<W: Write>(&self, mut dst: W) -> std::io::Result<()> {
+        dst.write_all(SPATIAL_SIGNATURE)?;
+        dst.write_u32::<LittleEndian>(QUADTREE_TYPE)?;
+        dst.write_all(QUADTREE_SIGNATURE)?;
+        dst.write_u32::<LittleEndian>(self.version)?;
+        dst.write_u32::<LittleEndian>(self.levels)?;
+        dst.write_u32::<LittleEndian>(self.level_index)?;
+        dst.write_u32::<LittleEndian>(self.implicit_levels)?;
+        dst.write_f32::<LittleEndian>(self.min_x)?;
+        dst.write_f32::<LittleEndian>(self.max_x)?;
+        dst.write_f32::<LittleEndian>(self.min_y)?;
+        dst.write_f32::<LittleEndian>(self.max_y)?;
+        Ok(())
+    }
+
+    /// Returns the index of the cell of the deepest level that contains the point.
+    ///
+    /// Points outside of the quadtree are given the closest cell.
+    pub fn cell_index(&self, x: f64, y: f64) -> i32 {
+        let levels = self.levels.min(MAX_LEVELS);
+        let (mut min_x, mut max_x) = (f64::from(self.min_x), f64::from(self.max_x));
+        let (mut min_y, mut max_y) = (f64::from(self.min_y), f64::from(self.max_y));
+        let mut level_index = 0u32;
+        for _ in 0..levels {
+            level_index <<= 2;
+            let mid_x = (min_x + max_x) / 2.0;
+            let mid_y = (min_y + max_y) / 2.0;
+            if x < mid_x {
+                max_x = mid_x;
+            } else {
+                min_x = mid_x;
+                level_index |= 1;
+            }
+            if y < mid_y {
+                max_y = mid_y;
+            } else {
+                min_y = mid_y;
+                level_index |= 2;
+            }
+        }
+        let level_offset: u32 = (0..levels).map(|level| 4u32.pow(level)).sum();
+        (level_offset + level_index) as i32
+    }
+
+    /// Returns the extent `[min_x, min_y, max_x, max_y]` of the cell.
+    ///
+    /// Returns `None` if the cell index is not a valid index.
+    pub fn cell_bounds(&self, cell_index: i32) -> Option<[f64; 4]> {
+        if cell_index < 0 {
+            return None;
+        }
+        let mut local_index = cell_index as u32;
+        let mut level = 0u32;
+        let mut cells_in_level = 1u32;
+        while local_index >= cells_in_level {
+            local_index -= cells_in_level;
+            level += 1;
+            cells_in_level = cells_in_level.checked_mul(4)?;
+        }
+
+        let (mut min_x, mut max_x) = (f64::from(self.min_x), f64::from(self.max_x));
+        let (mut min_y, mut max_y) = (f64::from(self.min_y), f64::from(self.max_y));
+        for shift in (0..level).rev() {
+            let quadrant = (local_index >> (2 * shift)) & 3;
+            let mid_x = (min_x + max_x) / 2.0;
+            let mid_y = (min_y + max_y) / 2.0;
+            if quadrant & 1 == 0 {
+                max_x = mid_x;
+            } else {
+                min_x = mid_x;
+            }
+            if quadrant & 2 == 0 {
+                max_y = mid_y;
+            } else {
+                min_y = mid_y;
+            }
+        }
+        Some([min_x, min_y, max_x, max_y])
+    }
+}
+
+/// A cell of the quadtree that contains points.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LaxCell {
+    /// Index of the cell in the quadtree
+    pub index: i32,
+    /// Number of points in the cell
+    pub point_count: u32,
+    /// Intervals of the indices of the points of the cell,
+    /// note that an interval may also contain points that are not in the cell.
+    pub intervals: Vec<Range<u64>>,
+}
+
+impl LaxCell {
+    fn read_from<R: Read>(mut src: R) -> std::io::Result<Self> {
+        let index = src.read_i32::<LittleEndian>()?;
+        let num_intervals = src.read_u32::<LittleEndian>()?;
+        let point_count = src.read_u32::<LittleEndian>()?;
+        let mut intervals = Vec::with_capacity(num_intervals.min(1 << 16) as usize);
+        for _ in 0..num_intervals {
+            // The end of the interval is inclusive in the file
+            let start = src.read_u32::<LittleEndian>()?;
+            let end = src.read_u32::<LittleEndian>()?;
+            intervals.push(u64::from(start)..u64::from(end) + 1);
+        }
+        Ok(Self {
+            index,
+            point_count,
+            intervals,
+        })
+    }
+
+    fn write_to<W: Write>(&self, mut dst: W) -> std::io::Result<()> {
+        let to_u32 = |value: u64| {
+            u32::try_from(value).map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "LASindex intervals cannot go past the point 2^32 - 1",
+                )
+            })
+        };
+        let non_empty_intervals = self.intervals.iter().filter(|i| !i.is_empty());
+        dst.write_i32::<LittleEndian>(self.index)?;
+        dst.write_u32::<LittleEndian>(non_empty_intervals.clone().count() as u32)?;
+        dst.write_u32::<LittleEndian>(self.point_count)?;
+        for interval in non_empty_intervals {
+            dst.write_u32::<LittleEndian>(to_u32(interval.start)?)?;
+            dst.write_u32::<LittleEndian>(to_u32(interval.end - 1)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// The content of a LASindex (`.lax`) file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LaxIndex {
+    pub version: u32,
+    pub quadtree: LaxQuadtree,
+    pub interval_version: u32,
+    pub cells: Vec<LaxCell>,
+}
+
+impl LaxIndex {
+    /// Reads the index, the source must be at the start of the `.lax` file.
+    pub fn read_from<R: Read>(mut src: R) -> crate::Result<Self> {
+        read_signature(&mut src, LAX_SIGNATURE)?;
+        let version = src.read_u32::<LittleEndian>()?;
+        let quadtree = LaxQuadtree::read_from(&mut src)?;
+
+        read_signature(&mut src, INTERVAL_SIGNATURE)?;
+        let interval_version = src.read_u32::<LittleEndian>()?;
+        let num_cells = src.read_u32::<LittleEndian>()?;
+        let mut cells = Vec::with_capacity(num_cells.min(1 << 16) as usize);
+        for _ in 0..num_cells {
+            cells.push(LaxCell::read_from(&mut src)?);
+        }
+        Ok(Self {
+            version,
+            quadtree,
+            interval_version,
+            cells,
+        })
+    }
+
+    /// Writes the index, in the format of a `.lax` file.
+    pub fn write_to<W: Write>(&self, mut dst: W) -> std::io::Result<()> {
+        dst.write_all(LAX_SIGNATURE)?;
+        dst.write_u32::<LittleEndian>(self.version)?;
+        self.quadtree.write_to(&mut dst)?;
+        dst.write_all(INTERVAL_SIGNATURE)?;
+        dst.write_u32::<LittleEndian>(self.interval_version)?;
+        dst.write_u32::<LittleEndian>(self.cells.len() as u32)?;
+        for cell in &self.cells {
+            cell.write_to(&mut dst)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the cells whose extent intersects the rectangle.
+    pub fn cells_intersecting(
+        &self,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> impl Iterator<Item = &LaxCell> + '_ {
+        self.cells.iter().filter(move |cell| {
+            matches!(self.quadtree.cell_bounds(cell.index), Some(b)
+                if min_x <= b[2] && max_x >= b[0] && min_y <= b[3] && max_y >= b[1])
+        })
+    }
+
+    /// Returns the intervals of the indices of the points that may be inside the rectangle.
+    ///
+    /// The intervals are sorted and do not overlap, points outside of the rectangle
+    /// may also be in the intervals as the index only knows the cells of the points.
+    pub fn query(&self, min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Vec<Range<u64>> {
+        let intervals = self
+            .cells_intersecting(min_x, min_y, max_x, max_y)
+            .flat_map(|cell| cell.intervals.iter().cloned())
+            .collect();
+        merge_intervals(intervals)
+    }
+}
+
+/// Sorts the intervals and merges the ones that overlap or touch.
+fn merge_intervals(mut intervals: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    intervals.retain(|interval| !interval.is_empty());
+    intervals.sort_unstable_by_key(|interval| interval.start);
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(intervals.len());
+    for interval in intervals {
+        match merged.last_mut() {
+            Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+            _ => merged.push(interval),
+        }
+    }
+    merged
+}
+
+/// Returns the indices (sorted) of the chunks that contain points of the intervals.
+///
+/// Fails with [LasZipError::PointIndexOutOfBounds] if an interval goes past the last point.
+pub fn chunks_of_intervals(
+    chunk_table: &ChunkTable,
+    intervals: &[Range<u64>],
+) -> crate::Result<Vec<usize>> {
+    let chunk_index = ChunkIndex::new(chunk_table);
+    let mut chunks = Vec::<usize>::new();
+    for interval in intervals.iter().filter(|interval| !interval.is_empty()) {
+        let out_of_bounds = |point_idx| LasZipError::PointIndexOutOfBounds {
+            requested: point_idx,
+            total: chunk_index.point_count(),
+        };
+        let first = chunk_index
+            .chunk_of_point(interval.start)
+            .ok_or_else(|| out_of_bounds(interval.start))?;
+        let last = chunk_index
+            .chunk_of_point(interval.end - 1)
+            .ok_or_else(|| out_of_bounds(interval.end - 1))?;
+        chunks.extend(first..=last);
+    }
+    chunks.sort_unstable();
+    chunks.dedup();
+    Ok(chunks)
+}
+
+/// Decompresses the points of the intervals and appends them to `out`.
+///
+/// Only the chunks containing points of the intervals are read & decompressed,
+/// each of them once. Points are appended in the order of their index,
+/// points in more than one interval are only appended once.
+///
+/// The decompressor must have a chunk table, after this call its position is unspecified.
+/// For fixed-size chunks of point formats < 6, the point count must have been set with
+/// [LasZipDecompressor::set_point_count] to detect intervals past the last point.
+pub fn decompress_intervals<'a, R: Read + Seek + Send + 'a>(
+    decompressor: &mut LasZipDecompressor<'a, R>,
+    intervals: &[Range<u64>],
+    out: &mut Vec<u8>,
+) -> crate::Result<()> {
+    let chunk_table = decompressor
+        .chunk_table()
+        .ok_or(LasZipError::MissingChunkTable)?
+        .clone();
+    let intervals = merge_intervals(intervals.to_vec());
+    let chunks = chunks_of_intervals(&chunk_table, &intervals)?;
+    let chunk_index = ChunkIndex::new(&chunk_table);
+    let point_size = decompressor.vlr().items_size() as usize;
+
+    let mut chunk_points = Vec::<u8>::new();
+    let mut intervals = intervals.iter().peekable();
+    for chunk in chunks {
+        let chunk_start = chunk_index.first_point_of_chunk(chunk);
+        let chunk_end = chunk_start + chunk_table[chunk].point_count;
+        decompressor.seek(chunk_start)?;
+        chunk_points.resize((chunk_end - chunk_start) as usize * point_size, 0);
+        decompressor.decompress_many(&mut chunk_points)?;
+
+        while let Some(interval) = intervals.peek() {
+            if interval.start >= chunk_end {
+                break;
+            }
+            let start = (interval.start.max(chunk_start) - chunk_start) as usize;
+            let end = (interval.end.min(chunk_end) - chunk_start) as usize;
+            out.extend_from_slice(&chunk_points[start * point_size..end * point_size]);
+            if interval.end > chunk_end {
+                break;
+            }
+            intervals.next();
+        }
+    }
+    Ok(())
+}
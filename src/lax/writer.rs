@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, Write};
+
+use crate::laszip::{LasZipCompressor, LasZipDecompressor};
+use crate::lax::{LaxCell, LaxIndex, LaxQuadtree, MAX_LEVELS};
+
+/// Number of points decompressed at once when indexing the points of a decompressor
+const DECOMPRESSION_BATCH_SIZE: u64 = 50_000;
+
+/// Returns the real x, y coordinates of the point.
+///
+/// All the point formats start with the X, Y `i32`.
+fn point_xy(point: &[u8], scales: &[f64; 3], offsets: &[f64; 3]) -> (f64, f64) {
+    let x = i32::from_le_bytes([point[0], point[1], point[2], point[3]]);
+    let y = i32::from_le_bytes([point[4], point[5], point[6], point[7]]);
+    (
+        f64::from(x) * scales[0] + offsets[0],
+        f64::from(y) * scales[1] + offsets[1],
+    )
+}
+
+/// Builds a LASindex, the points must be added in the order they are stored.
+///
+/// The cells of the index are the cells of the deepest level of the quadtree,
+/// consecutive points in the same cell make one interval.
+///
+/// # Example
+///
+/// ```
+/// use laz::lax::LaxIndexBuilder;
+///
+/// let mut builder = LaxIndexBuilder::new(0.0, 0.0, 100.0, 100.0);
+/// builder.add_point(10.0, 10.0);
+/// builder.add_point(90.0, 90.0);
+/// let index = builder.build();
+/// assert_eq!(index.query(0.0, 0.0, 20.0, 20.0), vec![0..1]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LaxIndexBuilder {
+    quadtree: LaxQuadtree,
+    cells: BTreeMap<i32, LaxCell>,
+    num_points: u64,
+}
+
+impl LaxIndexBuilder {
+    /// Creates a builder for points in the given extent,
+    /// with the same size of cells as `lasindex` would use.
+    ///
+    /// # Panics
+    ///
+    /// If the extent is not finite.
+    pub fn new(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Self {
+        let extent = (max_x - min_x).max(max_y - min_y);
+        let cell_size = if extent < 1_000.0 {
+            10.0
+        } else if extent < 10_000.0 {
+            100.0
+        } else if extent < 100_000.0 {
+            1_000.0
+        } else if extent < 1_000_000.0 {
+            10_000.0
+        } else {
+            100_000.0
+        };
+        Self::with_cell_size(min_x, min_y, max_x, max_y, cell_size)
+    }
+
+    /// Creates a builder for points in the given extent,
+    /// the cells of the deepest level will be at least `cell_size` wide.
+    ///
+    /// The extent is aligned on the cell size and the quadtree is square,
+    /// the number of levels being limited, cells may be larger than `cell_size`.
+    ///
+    /// # Panics
+    ///
+    /// If `cell_size` is not finite and positive, or if the extent is not finite.
+    pub fn with_cell_size(min_x: f64, min_y: f64, max_x: f64, max_y: f64, cell_size: f64) -> Self {
+        assert!(cell_size.is_finite() && cell_size > 0.0);
+        let min_x = (min_x / cell_size).floor() * cell_size;
+        let min_y = (min_y / cell_size).floor() * cell_size;
+        let extent = (max_x - min_x).max(max_y - min_y);
+        assert!(extent.is_finite());
+        let mut levels = 0;
+        let mut root_size = cell_size;
+        while root_size <= extent {
+            root_size *= 2.0;
+            levels += 1;
+        }
+        let quadtree = LaxQuadtree {
+            levels: levels.min(MAX_LEVELS),
+            min_x: min_x as f32,
+            max_x: (min_x + root_size) as f32,
+            min_y: min_y as f32,
+            max_y: (min_y + root_size) as f32,
+            ..Default::default()
+        };
+        Self {
+            quadtree,
+            cells: BTreeMap::new(),
+            num_points: 0,
+        }
+    }
+
+    /// Returns the quadtree of the index.
+    pub fn quadtree(&self) -> &LaxQuadtree {
+        &self.quadtree
+    }
+
+    /// Adds the next point, given its real coordinates.
+    pub fn add_point(&mut self, x: f64, y: f64) {
+        let point_idx = self.num_points;
+        let index = self.quadtree.cell_index(x, y);
+        let cell = self.cells.entry(index).or_insert_with(|| LaxCell {
+            index,
+            ..Default::default()
+        });
+        cell.point_count += 1;
+        match cell.intervals.last_mut() {
+            Some(last) if last.end == point_idx => last.end += 1,
+            _ => cell.intervals.push(point_idx..point_idx + 1),
+        }
+        self.num_points += 1;
+    }
+
+    /// Adds the next points, given as LAS point records of `point_size` bytes.
+    ///
+    /// The `scales` and `offsets` are the ones of the LAS header.
+    pub fn add_points(
+        &mut self,
+        points: &[u8],
+        point_size: usize,
+        scales: [f64; 3],
+        offsets: [f64; 3],
+    ) {
+        for point in points.chunks_exact(point_size) {
+            let (x, y) = point_xy(point, &scales, &offsets);
+            self.add_point(x, y);
+        }
+    }
+
+    /// Decompresses `num_points` points from the decompressor and adds them.
+    ///
+    /// The `scales` and `offsets` are the ones of the LAS header.
+    pub fn add_decompressed<'a, R: Read + Seek + Send + 'a>(
+        &mut self,
+        decompressor: &mut LasZipDecompressor<'a, R>,
+        num_points: u64,
+        scales: [f64; 3],
+        offsets: [f64; 3],
+    ) -> std::io::Result<()> {
+        let point_size = decompressor.vlr().items_size() as usize;
+        let mut points = Vec::<u8>::new();
+        let mut num_points_left = num_points;
+        while num_points_left > 0 {
+            let num_points = num_points_left.min(DECOMPRESSION_BATCH_SIZE);
+            points.resize(num_points as usize * point_size, 0);
+            decompressor.decompress_many(&mut points)?;
+            self.add_points(&points, point_size, scales, offsets);
+            num_points_left -= num_points;
+        }
+        Ok(())
+    }
+
+    /// Returns the index of the points added so far.
+    pub fn build(&self) -> LaxIndex {
+        LaxIndex {
+            quadtree: self.quadtree,
+            cells: self.cells.values().cloned().collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Sorts the points by cell of the quadtree, keeping the order of the points of the same cell.
+///
+/// Sorting the points before compressing & indexing them gives one interval per cell,
+/// so that querying the index reads fewer chunks.
+///
+/// The `scales` and `offsets` are the ones of the LAS header.
+pub fn sort_points_by_cell(
+    points: &mut [u8],
+    point_size: usize,
+    quadtree: &LaxQuadtree,
+    scales: [f64; 3],
+    offsets: [f64; 3],
+) {
+    let mut cells = points
+        .chunks_exact(point_size)
+        .map(|point| {
+            let (x, y) = point_xy(point, &scales, &offsets);
+            quadtree.cell_index(x, y)
+        })
+        .enumerate()
+        .collect::<Vec<_>>();
+    cells.sort_by_key(|(_, cell_index)| *cell_index);
+
+    let mut sorted = Vec::with_capacity(points.len());
+    for (i, _) in cells {
+        sorted.extend_from_slice(&points[i * point_size..(i + 1) * point_size]);
+    }
+    points[..sorted.len()].copy_from_slice(&sorted);
+}
+
+/// A [LasZipCompressor] that also indexes the points it compresses,
+/// so that no second pass on the points is needed to create the LASindex.
+pub struct LaxIndexingCompressor<'a, W: Write + Seek + Send + 'a> {
+    compressor: LasZipCompressor<'a, W>,
+    builder: LaxIndexBuilder,
+    point_size: usize,
+    scales: [f64; 3],
+    offsets: [f64; 3],
+}
+
+impl<'a, W: Write + Seek + Send + 'a> LaxIndexingCompressor<'a, W> {
+    /// Creates the compressor, the `scales` and `offsets` are the ones of the LAS header.
+    pub fn new(
+        compressor: LasZipCompressor<'a, W>,
+        builder: LaxIndexBuilder,
+        scales: [f64; 3],
+        offsets: [f64; 3],
+    ) -> Self {
+        let point_size = compressor.vlr().items_size() as usize;
+        Self {
+            compressor,
+            builder,
+            point_size,
+            scales,
+            offsets,
+        }
+    }
+
+    /// Compresses & indexes one point.
+    pub fn compress_one(&mut self, input: &[u8]) -> std::io::Result<()> {
+        self.compressor.compress_one(input)?;
+        let (x, y) = point_xy(input, &self.scales, &self.offsets);
+        self.builder.add_point(x, y);
+        Ok(())
+    }
+
+    /// Compresses & indexes many points.
+    pub fn compress_many(&mut self, input: &[u8]) -> std::io::Result<()> {
+        self.compressor.compress_many(input)?;
+        self.builder
+            .add_points(input, self.point_size, self.scales, self.offsets);
+        Ok(())
+    }
+
+    /// Must be called when you have compressed all your points,
+    /// see [LasZipCompressor::done].
+    pub fn done(&mut self) -> std::io::Result<()> {
+        self.compressor.done()
+    }
+
+    /// Returns the index of the points compressed so far.
+    pub fn index(&self) -> LaxIndex {
+        self.builder.build()
+    }
+
+    /// Returns the compressor used.
+    pub fn compressor(&self) -> &LasZipCompressor<'a, W> {
+        &self.compressor
+    }
+
+    /// Consumes the compressor and returns the output.
+    pub fn into_inner(self) -> W {
+        self.compressor.into_inner()
+    }
+
+    /// Returns a mutable reference to the output.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.compressor.get_mut()
+    }
+
+    /// Returns a reference to the output.
+    pub fn get(&self) -> &W {
+        self.compressor.get()
+    }
+}
@@ -67,7 +67,7 @@
 //! use laz::{LasZipError, LazVlr, LasZipDecompressor};
 //! use std::fs::File;
 //!
-//! # fn read_first_point(path: &str, out: &mut [u8]) -> std::io::Result<()> {
+//! # fn read_first_point(path: &str, out: &mut [u8]) -> laz::Result<()> {
 //! #    let mut reader = laz::las::file::SimpleReader::new(File::open(path)?)?;
 //! #    out.copy_from_slice(reader.read_next().unwrap()?);
 //! #    Ok(())
@@ -108,12 +108,14 @@
 //! - [`par_decompress_buffer`]
 
 pub use errors::LasZipError;
+#[cfg(feature = "async")]
+pub use laszip::asynchronous::{AsyncLasZipCompressor, AsyncLasZipDecompressor};
 #[cfg(feature = "parallel")]
 pub use laszip::parallel::{
-    par_compress_buffer, par_decompress, par_decompress_buffer, ParLasZipCompressor,
+    par_compress_buffer, par_decompress, par_decompress_buffer, par_rechunk, ParLasZipCompressor,
     ParLasZipDecompressor,
 };
-pub use laszip::{compress_buffer, decompress_buffer};
+pub use laszip::{compress_buffer, decompress_buffer, extract_chunks, merge, rechunk};
 pub use laszip::{
     LasZipCompressor, LasZipDecompressor, LazCompressor, LazDecompressor, LazItem,
     LazItemRecordBuilder, LazItemType, LazVlr, LazVlrBuilder,
@@ -129,8 +131,10 @@ pub mod packers;
 pub mod record;
 
 mod byteslice;
+pub mod copc;
 pub mod errors;
 pub mod las;
+pub mod lax;
 
 #[cfg(test)]
 mod test;
@@ -8,6 +8,8 @@ use crate::byteslice::{ChunksIrregular, ChunksIrregularMut};
 use crate::decoders;
 use crate::encoders;
 use crate::las;
+use crate::las::point6::DecompressionSelector;
+use crate::las::PointColumns;
 use crate::laszip::{LazItem, LazItemType};
 use crate::LasZipError;
 
@@ -41,6 +43,11 @@ pub trait FieldDecompressor<R: Read> {
         decoder: &mut decoders::ArithmeticDecoder<R>,
         buf: &mut [u8],
     ) -> std::io::Result<()>;
+
+    /// Appends the field of the point last decompressed by `decompress_with` to the `columns`.
+    ///
+    /// Fields that have no column (e.g. wave packets) have nothing to do.
+    fn push_last_to_columns(&self, _columns: &mut PointColumns) {}
 }
 
 /// Trait to be implemented by FieldCompressors that works with layers.
@@ -71,6 +78,12 @@ pub trait LayeredFieldDecompressor<R: Read> {
     fn read_layers_sizes(&mut self, src: &mut R) -> std::io::Result<()>;
     /// Read the layers from the `src`.
     fn read_layers(&mut self, src: &mut R) -> std::io::Result<()>;
+
+    /// Appends the field of the point last decompressed by `decompress_field_with`
+    /// to the `columns`.
+    ///
+    /// Fields that have no column (e.g. wave packets) have nothing to do.
+    fn push_last_to_columns(&self, _columns: &mut PointColumns) {}
 }
 
 /// Trait describing the interface needed to _decompress_ a point record
@@ -114,6 +127,13 @@ pub trait RecordDecompressor<R> {
         Ok(out.len())
     }
 
+    /// Appends the fields of the point last decompressed by `decompress_next`
+    /// to the `columns`, straight from the field decompressors.
+    ///
+    /// The first point is not compressed, so the field decompressors
+    /// may not hold its fields: it has to be transposed from its record instead.
+    fn push_last_to_columns(&self, columns: &mut PointColumns);
+
     /// Resets the `RecordDecompressor` to its initial state
     fn reset(&mut self);
 
@@ -196,6 +216,9 @@ impl<'a, R: Read> RecordDecompressor<R> for SequentialPointRecordDecompressor<'a
                     LazItemType::RGB12 => {
                         self.add_field_decompressor(las::v1::LasRGBDecompressor::default())
                     }
+                    LazItemType::WavePacket13 => {
+                        self.add_field_decompressor(las::v1::LasWavepacketDecompressor::default())
+                    }
                     LazItemType::Byte(_) => self.add_field_decompressor(
                         las::v1::LasExtraByteDecompressor::new(record_item.size as usize),
                     ),
@@ -216,6 +239,9 @@ impl<'a, R: Read> RecordDecompressor<R> for SequentialPointRecordDecompressor<'a
                     LazItemType::RGB12 => {
                         self.add_field_decompressor(las::v2::LasRGBDecompressor::default())
                     }
+                    LazItemType::WavePacket13 => {
+                        self.add_field_decompressor(las::v2::LasWavepacketDecompressor::default())
+                    }
                     LazItemType::Byte(_) => self.add_field_decompressor(
                         las::v2::LasExtraByteDecompressor::new(record_item.size as usize),
                     ),
@@ -267,6 +293,12 @@ impl<'a, R: Read> RecordDecompressor<R> for SequentialPointRecordDecompressor<'a
         Ok(())
     }
 
+    fn push_last_to_columns(&self, columns: &mut PointColumns) {
+        for field_decompressor in &self.field_decompressors {
+            field_decompressor.push_last_to_columns(columns);
+        }
+    }
+
     fn reset(&mut self) {
         self.decoder.reset();
         self.is_first_decompression = true;
@@ -315,12 +347,20 @@ pub struct LayeredPointRecordDecompressor<'a, R: Read + Seek> {
     fields_sizes: Vec<usize>,
     record_size: usize,
     context: usize,
+    selection: DecompressionSelector,
 }
 
 impl<'a, R: Read + Seek> LayeredPointRecordDecompressor<'a, R> {
     /// Creates a new instance.
     /// The `input` is where layers will be read to later be decompressed
     pub fn new(input: R) -> Self {
+        Self::selective(input, DecompressionSelector::decompress_all())
+    }
+
+    /// Creates a new instance that will only decompress the layers in the `selection`.
+    ///
+    /// The selection is used by the field decompressors added by `set_fields_from`.
+    pub fn selective(input: R, selection: DecompressionSelector) -> Self {
         Self {
             field_decompressors: vec![],
             input,
@@ -328,6 +368,7 @@ impl<'a, R: Read + Seek> LayeredPointRecordDecompressor<'a, R> {
             fields_sizes: vec![],
             record_size: 0,
             context: 0,
+            selection,
         }
     }
 
@@ -348,19 +389,60 @@ impl<'a, R: Read + Seek> RecordDecompressor<R> for LayeredPointRecordDecompresso
         for record_item in laz_items {
             match record_item.version {
                 3 => match record_item.item_type {
-                    LazItemType::Point14 => {
-                        self.add_field_decompressor(las::v3::LasPoint6Decompressor::default())
+                    LazItemType::Point14 => self.add_field_decompressor(
+                        las::v3::LasPoint6Decompressor::selective(self.selection),
+                    ),
+                    LazItemType::RGB14 => self.add_field_decompressor(
+                        las::v3::LasRGBDecompressor::selective(self.selection),
+                    ),
+                    LazItemType::RGBNIR14 => {
+                        self.add_field_decompressor(las::v3::LasRGBDecompressor::selective(
+                            self.selection,
+                        ));
+                        self.add_field_decompressor(las::v3::LasNIRDecompressor::selective(
+                            self.selection,
+                        ));
                     }
-                    LazItemType::RGB14 => {
-                        self.add_field_decompressor(las::v3::LasRGBDecompressor::default())
+                    LazItemType::WavePacket14 => self.add_field_decompressor(
+                        las::v3::LasWavepacketDecompressor::selective(self.selection),
+                    ),
+                    LazItemType::Byte14(count) => {
+                        self.add_field_decompressor(las::v3::LasExtraByteDecompressor::selective(
+                            count as usize,
+                            self.selection,
+                        ))
+                    }
+                    _ => {
+                        return Err(LasZipError::UnsupportedLazItemVersion(
+                            record_item.item_type,
+                            record_item.version,
+                        ));
                     }
+                },
+                4 => match record_item.item_type {
+                    LazItemType::Point14 => self.add_field_decompressor(
+                        las::v4::LasPoint6Decompressor::selective(self.selection),
+                    ),
+                    LazItemType::RGB14 => self.add_field_decompressor(
+                        las::v4::LasRGBDecompressor::selective(self.selection),
+                    ),
                     LazItemType::RGBNIR14 => {
-                        self.add_field_decompressor(las::v3::LasRGBDecompressor::default());
-                        self.add_field_decompressor(las::v3::LasNIRDecompressor::default());
+                        self.add_field_decompressor(las::v4::LasRGBDecompressor::selective(
+                            self.selection,
+                        ));
+                        self.add_field_decompressor(las::v4::LasNIRDecompressor::selective(
+                            self.selection,
+                        ));
                     }
-                    LazItemType::Byte14(count) => self.add_field_decompressor(
-                        las::v3::LasExtraByteDecompressor::new(count as usize),
+                    LazItemType::WavePacket14 => self.add_field_decompressor(
+                        las::v4::LasWavepacketDecompressor::selective(self.selection),
                     ),
+                    LazItemType::Byte14(count) => {
+                        self.add_field_decompressor(las::v4::LasExtraByteDecompressor::selective(
+                            count as usize,
+                            self.selection,
+                        ))
+                    }
                     _ => {
                         return Err(LasZipError::UnsupportedLazItemVersion(
                             record_item.item_type,
@@ -418,6 +500,12 @@ impl<'a, R: Read + Seek> RecordDecompressor<R> for LayeredPointRecordDecompresso
         Ok(())
     }
 
+    fn push_last_to_columns(&self, columns: &mut PointColumns) {
+        for field_decompressor in &self.field_decompressors {
+            field_decompressor.push_last_to_columns(columns);
+        }
+    }
+
     fn reset(&mut self) {
         self.is_first_decompression = true;
         self.field_decompressors.clear();
@@ -583,6 +671,9 @@ impl<'a, W: Write> RecordCompressor<W> for SequentialPointRecordCompressor<'a, W
                     LazItemType::RGB12 => {
                         self.add_field_compressor(las::v1::LasRGBCompressor::default())
                     }
+                    LazItemType::WavePacket13 => {
+                        self.add_field_compressor(las::v1::LasWavepacketCompressor::default())
+                    }
                     LazItemType::Byte(_) => self.add_field_compressor(
                         las::v1::LasExtraByteCompressor::new(record_item.size as usize),
                     ),
@@ -603,6 +694,9 @@ impl<'a, W: Write> RecordCompressor<W> for SequentialPointRecordCompressor<'a, W
                     LazItemType::RGB12 => {
                         self.add_field_compressor(las::v2::LasRGBCompressor::default())
                     }
+                    LazItemType::WavePacket13 => {
+                        self.add_field_compressor(las::v2::LasWavepacketCompressor::default())
+                    }
                     LazItemType::Byte(_) => self.add_field_compressor(
                         las::v2::LasExtraByteCompressor::new(record_item.size as usize),
                     ),
@@ -722,6 +816,9 @@ impl<'a, W: Write> RecordCompressor<W> for LayeredPointRecordCompressor<'a, W> {
                         self.add_field_compressor(las::v3::LasRGBCompressor::default());
                         self.add_field_compressor(las::v3::LasNIRCompressor::default());
                     }
+                    LazItemType::WavePacket14 => {
+                        self.add_field_compressor(las::v3::LasWavepacketCompressor::default())
+                    }
                     LazItemType::Byte14(n) => {
                         self.add_field_compressor(las::v3::LasExtraByteCompressor::new(n as usize));
                     }
@@ -732,6 +829,30 @@ impl<'a, W: Write> RecordCompressor<W> for LayeredPointRecordCompressor<'a, W> {
                         ));
                     }
                 },
+                4 => match item.item_type {
+                    LazItemType::Point14 => {
+                        self.add_field_compressor(las::v4::LasPoint6Compressor::default())
+                    }
+                    LazItemType::RGB14 => {
+                        self.add_field_compressor(las::v4::LasRGBCompressor::default())
+                    }
+                    LazItemType::RGBNIR14 => {
+                        self.add_field_compressor(las::v4::LasRGBCompressor::default());
+                        self.add_field_compressor(las::v4::LasNIRCompressor::default());
+                    }
+                    LazItemType::WavePacket14 => {
+                        self.add_field_compressor(las::v4::LasWavepacketCompressor::default())
+                    }
+                    LazItemType::Byte14(n) => {
+                        self.add_field_compressor(las::v4::LasExtraByteCompressor::new(n as usize));
+                    }
+                    _ => {
+                        return Err(LasZipError::UnsupportedLazItemVersion(
+                            item.item_type,
+                            item.version,
+                        ));
+                    }
+                },
                 _ => {
                     return Err(LasZipError::UnsupportedLazItemVersion(
                         item.item_type,
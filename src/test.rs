@@ -6,6 +6,10 @@ use crate::las::gps::{v2::GpsTimeCompressor, v2::GpsTimeDecompressor, GpsTime};
 use crate::las::point0::{v2::LasPoint0Compressor, v2::LasPoint0Decompressor, Point0};
 use crate::las::rgb::{v2::LasRGBCompressor, v2::LasRGBDecompressor, RGB};
 use crate::las::v1;
+use crate::las::wavepacket::{
+    v1::LasWavepacketCompressor, v1::LasWavepacketDecompressor, Wavepacket,
+};
+use crate::laszip::{LazItem, LazItemRecordBuilder, LazItemType, LazVlr};
 use crate::packers::Packable;
 use crate::record::{FieldCompressor, FieldDecompressor};
 use crate::record::{
@@ -144,6 +148,358 @@ fn test_gps_time() {
     }
 }
 
+fn wavepacket_for_index(i: u64) -> Wavepacket {
+    // Cycle through the different ways the offset can be encoded:
+    // unchanged, previous offset + packet size, 32 bit difference, 64 bit value
+    let offset_to_data = match i % 4 {
+        0 => 1000 * i,
+        1 => 1000 * (i - 1),
+        2 => 1000 * (i - 2) + 256,
+        _ => (i << 40) + 17,
+    };
+    Wavepacket {
+        descriptor_index: (i % 256) as u8,
+        offset_to_data,
+        packet_size: 256,
+        return_point: (i as f32 * 0.5).to_bits(),
+        x_t: (i as f32 / 3.0).to_bits(),
+        y_t: (-(i as f32) / 7.0).to_bits(),
+        z_t: (i as f32 * 2.0).to_bits(),
+    }
+}
+
+#[test]
+fn test_wavepacket() {
+    let mut compressor = SequentialPointRecordCompressor::new(Cursor::new(Vec::<u8>::new()));
+    compressor.add_field_compressor(LasWavepacketCompressor::default());
+
+    let n = 10000;
+
+    let mut buf = [0u8; Wavepacket::SIZE];
+    for i in 0..n {
+        wavepacket_for_index(i).pack_into(&mut buf);
+        compressor.compress_next(&buf).unwrap();
+    }
+    compressor.done().unwrap();
+
+    let compressed_data = compressor.into_inner().into_inner();
+
+    let mut decompressor = SequentialPointRecordDecompressor::new(Cursor::new(compressed_data));
+    decompressor.add_field_decompressor(LasWavepacketDecompressor::default());
+
+    for i in 0..n {
+        decompressor.decompress_next(&mut buf).unwrap();
+        let wavepacket = Wavepacket::unpack_from(&buf);
+        assert_eq!(wavepacket, wavepacket_for_index(i));
+    }
+}
+
+/// Generates `n` points for the layered `vlr` items, and returns them
+/// with their compressed data.
+fn compress_layered_points(vlr: &LazVlr, n: u64) -> (Vec<u8>, Cursor<Vec<u8>>) {
+    use crate::LasZipCompressor;
+
+    let point_size = vlr.items_size() as usize;
+    let mut points = vec![0u8; point_size * n as usize];
+    for (i, point) in points.chunks_exact_mut(point_size).enumerate() {
+        let i = i as u64;
+        let mut offset = 0;
+        for item in vlr.items() {
+            let field = &mut point[offset..offset + item.size() as usize];
+            match item.item_type() {
+                LazItemType::Point14 => {
+                    (i as i32).pack_into(&mut field[0..4]);
+                    (i as i32 * 2).pack_into(&mut field[4..8]);
+                    (i as i32 * 3).pack_into(&mut field[8..12]);
+                    (i as u16).pack_into(&mut field[12..14]);
+                    field[14] = 0x11;
+                    // switch scanner channel every 100 points to use the different contexts
+                    field[15] = (((i / 100) % 4) as u8) << 4;
+                    field[16] = (i % 32) as u8;
+                    GpsTime::from(i as f64 * 0.25).pack_into(&mut field[22..30]);
+                }
+                LazItemType::RGB14 | LazItemType::RGBNIR14 => {
+                    for (j, value) in field.chunks_exact_mut(2).enumerate() {
+                        ((i * (j as u64 + 1)) as u16).pack_into(value);
+                    }
+                }
+                LazItemType::WavePacket14 => {
+                    // descriptors are only changing in the second half of the points
+                    // so that the first chunks have an empty wave packet layer
+                    wavepacket_for_index(i.saturating_sub(n / 2)).pack_into(field);
+                }
+                LazItemType::Byte14(_) => {
+                    for (j, byte) in field.iter_mut().enumerate() {
+                        *byte = (i / (j as u64 + 1)) as u8;
+                    }
+                }
+                _ => unreachable!("not a layered item"),
+            }
+            offset += item.size() as usize;
+        }
+    }
+
+    let mut compressor = LasZipCompressor::new(Cursor::new(Vec::<u8>::new()), vlr.clone()).unwrap();
+    compressor.compress_many(&points).unwrap();
+    compressor.done().unwrap();
+    let mut compressed_data = compressor.into_inner();
+    compressed_data.set_position(0);
+    (points, compressed_data)
+}
+
+/// Compresses & decompresses generated points made of layered items
+fn layered_items_round_trip(laz_items: Vec<LazItem>) {
+    use crate::{LasZipDecompressor, LazVlrBuilder};
+
+    let vlr = LazVlrBuilder::new(laz_items)
+        .with_fixed_chunk_size(1000)
+        .build();
+    let (points, compressed_data) = compress_layered_points(&vlr, 5000);
+
+    let mut decompressor = LasZipDecompressor::new(compressed_data, vlr).unwrap();
+    let mut decompressed = vec![0u8; points.len()];
+    decompressor.decompress_many(&mut decompressed).unwrap();
+    assert_eq!(decompressed, points);
+}
+
+#[test]
+fn test_point_9_wavepacket() {
+    let laz_items = LazItemRecordBuilder::default_for_point_format_id(9, 0).unwrap();
+    assert_eq!(laz_items.iter().map(|item| item.size()).sum::<u16>(), 59);
+    layered_items_round_trip(laz_items);
+}
+
+#[test]
+fn test_point_10_version_4() {
+    use crate::las::Point10;
+    layered_items_round_trip(LazItemRecordBuilder::version_3_of::<Point10>(3));
+    layered_items_round_trip(LazItemRecordBuilder::version_4_of::<Point10>(3));
+}
+
+#[test]
+fn test_version_3_and_4_context_switch() {
+    use crate::las::Point10;
+    use crate::{LasZipCompressor, LasZipDecompressor, LazVlrBuilder};
+
+    // Points of the 4 scanner channels (so contexts) are randomly interleaved,
+    // and the RGB, NIR, wave packet and extra bytes of a channel never change.
+    // When switching back to an already used context, LASzip's version 3 still
+    // predicts from the last item of the previous context, whereas the version 4
+    // predicts from the last item of the context, so it compresses these items
+    // much better.
+    let n = 2000;
+    let point_size = 70;
+    let mut points = vec![0u8; point_size * n];
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    for point in points.chunks_exact_mut(point_size) {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let channel = (state % 4) as u8;
+        let value = u64::from(channel + 1) * 0x1234_5678_9ABC_DEF1;
+        point[14] = 0x11;
+        point[15] = channel << 4;
+        point[30..38].copy_from_slice(&value.to_le_bytes());
+        wavepacket_for_index(value >> 24).pack_into(&mut point[38..67]);
+        point[67..70].copy_from_slice(&value.to_le_bytes()[..3]);
+    }
+
+    let compress = |laz_items: Vec<LazItem>| {
+        let vlr = LazVlrBuilder::new(laz_items)
+            .with_fixed_chunk_size(1000)
+            .build();
+        let mut compressor =
+            LasZipCompressor::new(Cursor::new(Vec::<u8>::new()), vlr.clone()).unwrap();
+        compressor.compress_many(&points).unwrap();
+        compressor.done().unwrap();
+        (vlr, compressor.into_inner().into_inner())
+    };
+    let decompress = |vlr: LazVlr, data: Vec<u8>| {
+        let mut decompressor = LasZipDecompressor::new(Cursor::new(data), vlr).unwrap();
+        let mut decompressed = vec![0u8; points.len()];
+        decompressor
+            .decompress_many(&mut decompressed)
+            .map(|_| decompressed)
+            .ok()
+    };
+
+    let (v3_vlr, v3_data) = compress(LazItemRecordBuilder::version_3_of::<Point10>(3));
+    let (v4_vlr, v4_data) = compress(LazItemRecordBuilder::version_4_of::<Point10>(3));
+    assert!(
+        v4_data.len() * 2 < v3_data.len(),
+        "version 4: {} bytes, version 3: {} bytes",
+        v4_data.len(),
+        v3_data.len()
+    );
+    assert_eq!(decompress(v3_vlr, v3_data.clone()), Some(points.clone()));
+    assert_eq!(decompress(v4_vlr.clone(), v4_data), Some(points.clone()));
+
+    // Reading version 3 data as version 4 uses the wrong predictions
+    assert_ne!(decompress(v4_vlr, v3_data), Some(points.clone()));
+}
+
+#[test]
+fn test_selective_decompression() {
+    use crate::las::point6::DecompressionSelector;
+    use crate::las::Point10;
+    use crate::{LasZipDecompressor, LazVlrBuilder};
+
+    for laz_items in vec![
+        LazItemRecordBuilder::version_3_of::<Point10>(3),
+        LazItemRecordBuilder::version_4_of::<Point10>(3),
+    ] {
+        let vlr = LazVlrBuilder::new(laz_items)
+            .with_fixed_chunk_size(1000)
+            .build();
+        let (mut points, compressed_data) = compress_layered_points(&vlr, 5000);
+
+        let selection = DecompressionSelector::xy_returns_channel()
+            .z()
+            .classification()
+            .nir();
+        let mut decompressor =
+            LasZipDecompressor::selective(compressed_data, vlr.clone(), selection).unwrap();
+        let mut decompressed = vec![0u8; points.len()];
+        decompressor.decompress_many(&mut decompressed).unwrap();
+
+        for point in points.chunks_exact_mut(vlr.items_size() as usize) {
+            // intensity
+            point[12..14].fill(0);
+            // flags, except the scanner channel
+            point[15] &= 0b0011_0000;
+            // user data, scan angle, point source id, gps time
+            point[17..30].fill(0);
+            // rgb
+            point[30..36].fill(0);
+            // wave packet & extra bytes
+            point[38..].fill(0);
+        }
+        assert_eq!(decompressed, points);
+    }
+}
+
+/// Transposes the points of `compress_layered_points` for the Point10 format with 3 extra bytes.
+fn layered_points_columns(points: &[u8], point_size: usize) -> crate::las::PointColumns {
+    let mut columns = crate::las::PointColumns::default();
+    columns.num_extra_bytes = 3;
+    for point in points.chunks_exact(point_size) {
+        columns.x.push(i32::unpack_from(&point[0..4]));
+        columns.y.push(i32::unpack_from(&point[4..8]));
+        columns.z.push(i32::unpack_from(&point[8..12]));
+        columns.intensity.push(u16::unpack_from(&point[12..14]));
+        columns.classification.push(point[16]);
+        columns
+            .gps_time
+            .push(GpsTime::unpack_from(&point[22..30]).into());
+        columns.rgb.push(RGB::unpack_from(&point[30..36]));
+        columns.nir.push(u16::unpack_from(&point[36..38]));
+        columns.extra_bytes.extend_from_slice(&point[67..70]);
+    }
+    columns
+}
+
+#[test]
+fn test_decompress_columns_of_layered_points() {
+    use crate::las::point6::DecompressionSelector;
+    use crate::las::{Point10, PointColumns};
+    use crate::{LasZipDecompressor, LazVlrBuilder};
+
+    for laz_items in vec![
+        LazItemRecordBuilder::version_3_of::<Point10>(3),
+        LazItemRecordBuilder::version_4_of::<Point10>(3),
+    ] {
+        let vlr = LazVlrBuilder::new(laz_items)
+            .with_fixed_chunk_size(1000)
+            .build();
+        let point_size = vlr.items_size() as usize;
+        let (mut points, compressed_data) = compress_layered_points(&vlr, 5000);
+        let expected = layered_points_columns(&points, point_size);
+
+        let mut decompressor =
+            LasZipDecompressor::new(compressed_data.clone(), vlr.clone()).unwrap();
+        let mut columns = PointColumns::default();
+        decompressor.decompress_columns(5000, &mut columns).unwrap();
+        assert_eq!(columns, expected);
+
+        #[cfg(feature = "parallel")]
+        {
+            // The first call ends in the middle of a chunk, so the second one starts with the rest
+            let mut decompressor =
+                crate::ParLasZipDecompressor::new(compressed_data.clone(), vlr.clone()).unwrap();
+            let mut columns = PointColumns::default();
+            decompressor.decompress_columns(1500, &mut columns).unwrap();
+            decompressor.decompress_columns(3500, &mut columns).unwrap();
+            assert_eq!(columns, expected);
+        }
+
+        // The fields of the layers that are not decompressed are 0
+        let selection = DecompressionSelector::xy_returns_channel()
+            .z()
+            .classification()
+            .nir();
+        let mut decompressor =
+            LasZipDecompressor::selective(compressed_data, vlr.clone(), selection).unwrap();
+        let mut columns = PointColumns::default();
+        decompressor.decompress_columns(5000, &mut columns).unwrap();
+        for point in points.chunks_exact_mut(point_size) {
+            point[12..14].fill(0);
+            point[22..36].fill(0);
+            point[67..70].fill(0);
+        }
+        assert_eq!(columns, layered_points_columns(&points, point_size));
+    }
+}
+
+#[test]
+fn test_seek_out_of_bounds_layered() {
+    use crate::las::Point10;
+    use crate::laszip::ChunkTable;
+    use crate::{LasZipDecompressor, LasZipError, LazVlrBuilder};
+
+    let vlr = LazVlrBuilder::new(LazItemRecordBuilder::version_3_of::<Point10>(3))
+        .with_fixed_chunk_size(1000)
+        .build();
+    let (points, mut compressed_data) = compress_layered_points(&vlr, 4500);
+    let point_size = vlr.items_size() as usize;
+
+    let chunk_table = ChunkTable::read_from(&mut compressed_data, &vlr).unwrap();
+    assert_eq!(chunk_table.len(), 5);
+    assert_eq!(chunk_table[4].point_count, 500);
+    compressed_data.set_position(0);
+
+    let mut decompressor = LasZipDecompressor::new(compressed_data, vlr).unwrap();
+    assert!(matches!(
+        decompressor.seek(4500),
+        Err(LasZipError::PointIndexOutOfBounds {
+            requested: 4500,
+            total: 4500
+        })
+    ));
+
+    let mut point = vec![0u8; point_size];
+    decompressor.seek(4499).unwrap();
+    decompressor.decompress_one(&mut point).unwrap();
+    assert_eq!(point, &points[4499 * point_size..]);
+}
+
+#[test]
+fn test_rebuild_chunk_table_layered() {
+    use crate::las::Point10;
+    use crate::laszip::ChunkTable;
+    use crate::LazVlrBuilder;
+
+    let vlr = LazVlrBuilder::new(LazItemRecordBuilder::version_3_of::<Point10>(3))
+        .with_fixed_chunk_size(1000)
+        .build();
+    let (_, mut compressed_data) = compress_layered_points(&vlr, 4500);
+
+    let expected_chunk_table = ChunkTable::read_from(&mut compressed_data, &vlr).unwrap();
+    compressed_data.set_position(0);
+    let chunk_table = ChunkTable::rebuild(&mut compressed_data, &vlr, 4500).unwrap();
+    assert_eq!(chunk_table.as_ref(), expected_chunk_table.as_ref());
+    assert_eq!(compressed_data.position(), ChunkTable::OFFSET_SIZE as u64);
+}
+
 const LAS_HEADER_SIZE: u64 = 227;
 const NUM_POINTS: usize = 1065;
 const VLR_HEADER_SIZE: u64 = 54;
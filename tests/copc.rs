@@ -0,0 +1,338 @@
+use std::io::{Cursor, Seek, SeekFrom, Write};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use laz::copc::{Bounds, CopcInfo, CopcReader, CopcWriter, VoxelKey};
+use laz::laszip::ChunkTable;
+use laz::{LasZipCompressor, LasZipError, LazItemRecordBuilder, LazVlr, LazVlrBuilder};
+
+const POINT_SIZE: usize = 30;
+const HEADER_SIZE: usize = 375;
+
+fn key(level: i32, x: i32, y: i32, z: i32) -> VoxelKey {
+    VoxelKey { level, x, y, z }
+}
+
+/// Point format 6 points, with coordinates in the voxel of the key
+fn points_of_node(key: VoxelKey, count: usize) -> Vec<u8> {
+    let side = 1024 >> key.level;
+    let mut points = Vec::with_capacity(count * POINT_SIZE);
+    for i in 0..count as i32 {
+        points.write_i32::<LittleEndian>(key.x * side + i).unwrap();
+        points
+            .write_i32::<LittleEndian>(key.y * side + 2 * i)
+            .unwrap();
+        points
+            .write_i32::<LittleEndian>(key.z * side + 3 * i)
+            .unwrap();
+        points.write_u16::<LittleEndian>(i as u16 * 10).unwrap();
+        points.write_u8(0x11).unwrap(); // return 1 of 1
+        points.write_u8(0).unwrap();
+        points.write_u8(2).unwrap(); // classification
+        points.write_u8(0).unwrap();
+        points.write_i16::<LittleEndian>(0).unwrap();
+        points.write_u16::<LittleEndian>(key.level as u16).unwrap();
+        points.write_f64::<LittleEndian>(f64::from(i)).unwrap();
+    }
+    points
+}
+
+fn write_vlr<W: Write>(dst: &mut W, user_id: &str, record_id: u16, data: &[u8]) {
+    dst.write_u16::<LittleEndian>(0).unwrap();
+    let mut user_id_bytes = [0u8; 16];
+    user_id_bytes[..user_id.len()].copy_from_slice(user_id.as_bytes());
+    dst.write_all(&user_id_bytes).unwrap();
+    dst.write_u16::<LittleEndian>(record_id).unwrap();
+    dst.write_u16::<LittleEndian>(data.len() as u16).unwrap();
+    dst.write_all(&[0u8; 32]).unwrap();
+    dst.write_all(data).unwrap();
+}
+
+fn write_entry<W: Write>(dst: &mut W, key: VoxelKey, offset: u64, byte_size: i32, count: i32) {
+    for v in [key.level, key.x, key.y, key.z] {
+        dst.write_i32::<LittleEndian>(v).unwrap();
+    }
+    dst.write_u64::<LittleEndian>(offset).unwrap();
+    dst.write_i32::<LittleEndian>(byte_size).unwrap();
+    dst.write_i32::<LittleEndian>(count).unwrap();
+}
+
+/// Creates a COPC file whose octree has the `nodes`,
+/// the nodes at level 2 are stored in a child hierarchy page.
+fn create_copc_file(nodes: &[(VoxelKey, usize)]) -> Vec<u8> {
+    let vlr = LazVlrBuilder::new(LazItemRecordBuilder::default_for_point_format_id(6, 0).unwrap())
+        .with_variable_chunk_size()
+        .build();
+    let mut laz_vlr_data = Vec::<u8>::new();
+    vlr.write_to(&mut laz_vlr_data).unwrap();
+    let offset_to_points = HEADER_SIZE + 54 + CopcInfo::SIZE + 54 + laz_vlr_data.len();
+
+    let mut file = Cursor::new(vec![0u8; offset_to_points]);
+    file.seek(SeekFrom::End(0)).unwrap();
+    {
+        let mut compressor = LasZipCompressor::new(&mut file, vlr.clone()).unwrap();
+        for (key, count) in nodes {
+            compressor
+                .compress_many(&points_of_node(*key, *count))
+                .unwrap();
+            compressor.finish_current_chunk().unwrap();
+        }
+        compressor.done().unwrap();
+    }
+    file.seek(SeekFrom::Start(offset_to_points as u64)).unwrap();
+    let chunk_table = ChunkTable::read_from(&mut file, &vlr).unwrap();
+
+    // Hierarchy EVLR
+    let mut root_page = Vec::<u8>::new();
+    let mut child_page = Vec::<u8>::new();
+    let mut chunk_offset = offset_to_points as u64 + 8;
+    for ((key, count), entry) in nodes.iter().zip(chunk_table.as_ref()) {
+        let page = if key.level == 2 {
+            &mut child_page
+        } else {
+            &mut root_page
+        };
+        write_entry(
+            page,
+            *key,
+            chunk_offset,
+            entry.byte_count as i32,
+            *count as i32,
+        );
+        chunk_offset += entry.byte_count;
+    }
+    let evlr_start = file.get_ref().len() as u64;
+    let root_page_offset = evlr_start + 60;
+    let child_page_offset = root_page_offset + root_page.len() as u64 + 32;
+    write_entry(
+        &mut root_page,
+        key(2, 0, 0, 0),
+        child_page_offset,
+        child_page.len() as i32,
+        -1,
+    );
+    file.seek(SeekFrom::End(0)).unwrap();
+    file.write_all(&[0u8; 60]).unwrap();
+    file.write_all(&root_page).unwrap();
+    file.write_all(&child_page).unwrap();
+
+    // Header & VLRs
+    let num_points = nodes.iter().map(|(_, count)| *count as u64).sum::<u64>();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file.write_all(b"LASF").unwrap();
+    file.seek(SeekFrom::Start(24)).unwrap();
+    file.write_all(&[1, 4]).unwrap();
+    file.seek(SeekFrom::Start(94)).unwrap();
+    file.write_u16::<LittleEndian>(HEADER_SIZE as u16).unwrap();
+    file.write_u32::<LittleEndian>(offset_to_points as u32)
+        .unwrap();
+    file.write_u32::<LittleEndian>(2).unwrap();
+    file.write_u8(6 | 0x80).unwrap();
+    file.write_u16::<LittleEndian>(POINT_SIZE as u16).unwrap();
+    file.seek(SeekFrom::Start(247)).unwrap();
+    file.write_u64::<LittleEndian>(num_points).unwrap();
+
+    file.seek(SeekFrom::Start(HEADER_SIZE as u64)).unwrap();
+    let mut info_data = Vec::<u8>::new();
+    for v in [512.0f64, 512.0, 512.0, 512.0, 10.0] {
+        info_data.write_f64::<LittleEndian>(v).unwrap();
+    }
+    info_data
+        .write_u64::<LittleEndian>(root_page_offset)
+        .unwrap();
+    info_data
+        .write_u64::<LittleEndian>(root_page.len() as u64)
+        .unwrap();
+    info_data.resize(CopcInfo::SIZE, 0);
+    write_vlr(
+        &mut file,
+        CopcInfo::USER_ID,
+        CopcInfo::RECORD_ID,
+        &info_data,
+    );
+    write_vlr(&mut file, LazVlr::USER_ID, LazVlr::RECORD_ID, &laz_vlr_data);
+    assert_eq!(file.position(), offset_to_points as u64);
+
+    file.into_inner()
+}
+
+#[test]
+fn test_copc_reader() {
+    let nodes = [
+        (key(0, 0, 0, 0), 10),
+        (key(1, 0, 0, 0), 5),
+        (key(1, 1, 1, 1), 7),
+        (key(2, 0, 0, 0), 4),
+        (key(2, 3, 3, 3), 6),
+        (key(2, 3, 2, 3), 0),
+    ];
+    let data = create_copc_file(&nodes);
+    let mut reader = CopcReader::new(Cursor::new(data)).unwrap();
+
+    assert_eq!(reader.info().halfsize, 512.0);
+    assert!(reader.vlr().uses_variable_size_chunks());
+    assert_eq!(reader.nodes().len(), nodes.len());
+    assert!(reader
+        .nodes()
+        .windows(2)
+        .all(|pair| pair[0].key.level <= pair[1].key.level));
+    assert!(reader.node(&key(3, 0, 0, 0)).is_none());
+
+    for (key, count) in nodes.iter() {
+        let node = *reader.node(key).unwrap();
+        assert_eq!(node.point_count, *count as u64);
+        let points = reader.decompress_node(&node).unwrap();
+        assert_eq!(points, points_of_node(*key, *count));
+    }
+
+    assert_eq!(
+        reader.info().bounds_of(&key(2, 3, 3, 3)),
+        Bounds {
+            min: [768.0, 768.0, 768.0],
+            max: [1024.0, 1024.0, 1024.0]
+        }
+    );
+    let bounds = Bounds {
+        min: [0.0, 0.0, 0.0],
+        max: [100.0, 100.0, 100.0],
+    };
+    let keys = |max_level| {
+        reader
+            .query(&bounds, max_level)
+            .iter()
+            .map(|node| node.key)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(keys(0), vec![key(0, 0, 0, 0)]);
+    assert_eq!(keys(1), vec![key(0, 0, 0, 0), key(1, 0, 0, 0)]);
+    assert_eq!(
+        keys(i32::MAX),
+        vec![key(0, 0, 0, 0), key(1, 0, 0, 0), key(2, 0, 0, 0)]
+    );
+
+    #[cfg(feature = "parallel")]
+    {
+        let query = reader.query(&reader.info().bounds(), 2);
+        let points = reader.par_decompress_nodes(&query).unwrap();
+        let expected_points = query
+            .iter()
+            .flat_map(|node| points_of_node(node.key, node.point_count as usize))
+            .collect::<Vec<u8>>();
+        assert_eq!(points, expected_points);
+    }
+}
+
+#[test]
+fn test_copc_reader_not_copc() {
+    let data = std::fs::read("tests/data/point10.laz").unwrap();
+    assert!(matches!(
+        CopcReader::new(Cursor::new(data)),
+        Err(LasZipError::MissingVlr {
+            user_id: CopcInfo::USER_ID,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_copc_reader_hierarchy_cycle() {
+    let nodes = [(key(0, 0, 0, 0), 10), (key(2, 0, 0, 0), 4)];
+    let mut data = create_copc_file(&nodes);
+    // Make the entry of the child page, the last one of the root page,
+    // point to the root page
+    let info_offset = HEADER_SIZE + 54;
+    let mut file = Cursor::new(&mut data);
+    file.seek(SeekFrom::Start(info_offset as u64 + 40)).unwrap();
+    let root_page_offset = file.read_u64::<LittleEndian>().unwrap();
+    let root_page_size = file.read_u64::<LittleEndian>().unwrap();
+    file.seek(SeekFrom::Start(root_page_offset + root_page_size - 32 + 16))
+        .unwrap();
+    file.write_u64::<LittleEndian>(root_page_offset).unwrap();
+    file.write_i32::<LittleEndian>(root_page_size as i32)
+        .unwrap();
+
+    match CopcReader::new(Cursor::new(data)) {
+        Err(LasZipError::IoError(error)) => {
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidData)
+        }
+        _ => panic!("the hierarchy cycle was not detected"),
+    }
+}
+
+#[test]
+fn test_copc_writer_round_trip() {
+    const NUM_POINTS: usize = 3_000;
+    const SCALE: f64 = 0.01;
+    // Simple linear congruential generator, to get spread out coordinates
+    let mut state = 12345u32;
+    let mut next = || {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        (state >> 8) % 100_000
+    };
+    let mut points = Vec::<u8>::with_capacity(NUM_POINTS * POINT_SIZE);
+    for i in 0..NUM_POINTS {
+        let mut point = points_of_node(key(0, 0, 0, 0), 1);
+        point[0..4].copy_from_slice(&(next() as i32).to_le_bytes());
+        point[4..8].copy_from_slice(&(next() as i32).to_le_bytes());
+        point[8..12].copy_from_slice(&((next() % 5_000) as i32).to_le_bytes());
+        point[22..30].copy_from_slice(&(i as f64).to_le_bytes());
+        points.extend_from_slice(&point);
+    }
+
+    let mut writer = CopcWriter::new(
+        Cursor::new(Vec::<u8>::new()),
+        6,
+        0,
+        [SCALE; 3],
+        [0.0, 0.0, 0.0],
+    )
+    .unwrap();
+    writer.set_max_points_per_node(200);
+    writer.write_points(&points[..1_000 * POINT_SIZE]);
+    writer.write_points(&points[1_000 * POINT_SIZE..]);
+    writer.done().unwrap();
+    assert!(writer.done().is_err());
+    let data = writer.into_inner().into_inner();
+
+    let mut reader = CopcReader::new(Cursor::new(data)).unwrap();
+    assert_eq!(reader.header().num_points, NUM_POINTS as u64);
+    assert_eq!(reader.header().point_format_id, 6 | 0x80);
+    assert_eq!(reader.info().gpstime_minimum, 0.0);
+    assert_eq!(reader.info().gpstime_maximum, (NUM_POINTS - 1) as f64);
+    assert_eq!(reader.header().num_evlrs, 1);
+    assert_eq!(reader.vlr().number_of_special_evlrs(), 1);
+    assert_eq!(
+        reader.vlr().offset_to_special_evlrs() as u64,
+        reader.header().start_of_first_evlr
+    );
+    assert!(reader.nodes().iter().all(|node| node.point_count <= 200));
+    assert!(reader.nodes().iter().any(|node| node.key.level >= 2));
+    // Every node, except the root, has its parent in the octree
+    for node in reader.nodes() {
+        if let Some(parent) = node.key.parent() {
+            assert!(reader.node(&parent).is_some());
+        }
+    }
+
+    let mut read_points = Vec::<Vec<u8>>::with_capacity(NUM_POINTS);
+    for node in reader.nodes().to_vec() {
+        let bounds = reader.info().bounds_of(&node.key);
+        let node_points = reader.decompress_node(&node).unwrap();
+        assert_eq!(node_points.len(), node.point_count as usize * POINT_SIZE);
+        for point in node_points.chunks_exact(POINT_SIZE) {
+            for i in 0..3 {
+                let mut coord = [0u8; 4];
+                coord.copy_from_slice(&point[i * 4..(i + 1) * 4]);
+                let coord = f64::from(i32::from_le_bytes(coord)) * SCALE;
+                assert!(bounds.min[i] <= coord && coord <= bounds.max[i]);
+            }
+            read_points.push(point.to_vec());
+        }
+    }
+    let mut expected_points = points
+        .chunks_exact(POINT_SIZE)
+        .map(|point| point.to_vec())
+        .collect::<Vec<_>>();
+    expected_points.sort();
+    read_points.sort();
+    assert_eq!(read_points, expected_points);
+}
@@ -0,0 +1,143 @@
+use std::fs::File;
+
+use laz::las::file::{
+    read_vlrs, ExtraBytesDataType, ExtraBytesDescriptor, ExtraBytesVlr, LasHeader, SimpleReader,
+};
+use laz::LasZipError;
+
+fn read_extra_bytes_vlr(path: &str) -> (LasHeader, ExtraBytesVlr) {
+    let mut file = File::open(path).unwrap();
+    let header = LasHeader::read_from(&mut file).unwrap();
+    let vlrs = read_vlrs(&mut file, &header).unwrap();
+    let extra_bytes_vlr = ExtraBytesVlr::from_vlrs(&vlrs).unwrap().unwrap();
+    (header, extra_bytes_vlr)
+}
+
+#[test]
+fn test_read_extra_bytes_vlr() {
+    let (header, vlr) = read_extra_bytes_vlr("tests/data/extra-bytes.las");
+    assert_eq!(
+        vlr.num_extra_bytes(),
+        usize::from(header.num_extra_bytes().unwrap())
+    );
+
+    let names = vlr
+        .descriptors()
+        .iter()
+        .map(|d| d.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Colors", "Reserved", "Flags", "Intensity", "Time"]);
+    let descriptors = vlr.descriptors();
+    assert_eq!(descriptors[0].data_type, ExtraBytesDataType::U16);
+    assert_eq!(descriptors[0].num_elements, 3);
+    assert_eq!(
+        descriptors[1].data_type,
+        ExtraBytesDataType::Undocumented(7)
+    );
+    assert_eq!(descriptors[2].data_type, ExtraBytesDataType::I8);
+    assert_eq!(descriptors[2].num_elements, 2);
+    assert_eq!(descriptors[3].data_type, ExtraBytesDataType::U32);
+    assert_eq!(descriptors[3].description, "Brightness");
+    assert_eq!(descriptors[4].data_type, ExtraBytesDataType::U64);
+    assert!(descriptors.iter().all(|d| d.scale.is_none()));
+
+    let fields = vlr.fields(header.point_format_id).unwrap();
+    let offsets = fields.iter().map(|f| f.offset()).collect::<Vec<_>>();
+    assert_eq!(offsets, [34, 40, 47, 49, 53]);
+}
+
+#[test]
+fn test_extra_bytes_values() {
+    let (header, vlr) = read_extra_bytes_vlr("tests/data/extra-bytes.las");
+    let (laz_header, laz_vlr) = read_extra_bytes_vlr("tests/data/extra-bytes.laz");
+    assert_eq!(laz_vlr, vlr);
+
+    let intensity = vlr.field("Intensity", header.point_format_id).unwrap();
+    let colors = vlr.field("Colors", header.point_format_id).unwrap();
+    let reserved = vlr.field("Reserved", header.point_format_id).unwrap();
+    assert!(vlr.field("Unknown", header.point_format_id).is_none());
+
+    let mut las_reader =
+        SimpleReader::new(File::open("tests/data/extra-bytes.las").unwrap()).unwrap();
+    let mut laz_reader =
+        SimpleReader::new(File::open("tests/data/extra-bytes.laz").unwrap()).unwrap();
+    assert_eq!(laz_header.point_format_id & 0x3f, header.point_format_id);
+    for _ in 0..header.num_points {
+        let point = las_reader.read_next().unwrap().unwrap().to_vec();
+        let laz_point = laz_reader.read_next().unwrap().unwrap();
+
+        let raw_intensity = u32::from_le_bytes([point[49], point[50], point[51], point[52]]);
+        assert_eq!(intensity.value(&point), Some(f64::from(raw_intensity)));
+        assert_eq!(intensity.value(laz_point), intensity.value(&point));
+        for i in 0..3 {
+            let raw_color = u16::from_le_bytes([point[34 + 2 * i], point[35 + 2 * i]]);
+            assert_eq!(colors.value_at(&point, i), Some(f64::from(raw_color)));
+        }
+        assert_eq!(colors.value_at(&point, 3), None);
+        assert_eq!(reserved.value(&point), None);
+    }
+}
+
+#[test]
+fn test_extra_bytes_vlr_round_trip() {
+    let mut reflectance = ExtraBytesDescriptor::new("reflectance", ExtraBytesDataType::I16);
+    reflectance.description = "Reflectance in dB".to_string();
+    reflectance.scale = Some(0.01);
+    reflectance.offset = Some(-5.0);
+    reflectance.no_data = Some(-32768.0);
+    reflectance.min = Some(-3000.0);
+    reflectance.max = Some(1000.0);
+    let mut deviation = ExtraBytesDescriptor::new("deviation", ExtraBytesDataType::U8);
+    deviation.no_data = Some(255.0);
+    let mut normals = ExtraBytesDescriptor::new("normals", ExtraBytesDataType::F32);
+    normals.num_elements = 3;
+    let vlr = ExtraBytesVlr::new(vec![
+        reflectance,
+        deviation,
+        normals,
+        ExtraBytesDescriptor::new("unknown", ExtraBytesDataType::Undocumented(3)),
+    ]);
+    assert_eq!(vlr.num_extra_bytes(), 2 + 1 + 12 + 3);
+
+    let las_vlr = vlr.to_vlr();
+    assert_eq!(las_vlr.user_id(), ExtraBytesVlr::USER_ID);
+    assert_eq!(las_vlr.record_id(), ExtraBytesVlr::RECORD_ID);
+    assert_eq!(las_vlr.data().len(), 4 * ExtraBytesDescriptor::SIZE);
+    let read_vlr = ExtraBytesVlr::read_from(las_vlr.data()).unwrap();
+    assert_eq!(read_vlr, vlr);
+
+    // A point of format 6 (30 bytes) followed by the extra bytes
+    let mut point = vec![0u8; 30 + vlr.num_extra_bytes()];
+    point[30..32].copy_from_slice(&250i16.to_le_bytes());
+    point[32] = 255;
+    point[37..41].copy_from_slice(&1.5f32.to_le_bytes());
+    let reflectance = read_vlr.field("reflectance", 6).unwrap();
+    assert_eq!(reflectance.raw_value_at(&point, 0), Some(250.0));
+    assert_eq!(reflectance.value(&point), Some(-2.5));
+    let deviation = read_vlr.field("deviation", 6).unwrap();
+    assert_eq!(deviation.value(&point), None);
+    assert_eq!(deviation.raw_value_at(&point, 0), Some(255.0));
+    let normals = read_vlr.field("normals", 6).unwrap();
+    assert_eq!(normals.value_at(&point, 1), Some(1.5));
+    // The point is too small
+    assert_eq!(normals.value_at(&point[..40], 1), None);
+    // The point format is not known
+    assert!(read_vlr.field("normals", 11).is_none());
+}
+
+#[test]
+fn test_extra_bytes_vlr_errors() {
+    let mut data = Vec::new();
+    ExtraBytesDescriptor::new("a", ExtraBytesDataType::U8)
+        .write_to(&mut data)
+        .unwrap();
+    assert!(matches!(
+        ExtraBytesVlr::read_from(&data[..100]),
+        Err(LasZipError::IoError(_))
+    ));
+    data[2] = 31;
+    assert!(matches!(
+        ExtraBytesVlr::read_from(&data),
+        Err(LasZipError::UnknownExtraBytesDataType(31))
+    ));
+}
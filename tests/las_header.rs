@@ -0,0 +1,215 @@
+use std::fs::File;
+use std::io::{Cursor, Read, Seek};
+
+use laz::las::file::LasHeader;
+use laz::LasZipError;
+
+fn header_of_version(minor: u8) -> LasHeader {
+    let mut header = LasHeader {
+        file_source_id: 42,
+        guid: [7u8; 16],
+        minor,
+        creation_day: 200,
+        creation_year: 2021,
+        header_size: LasHeader::size_of_version(minor) + 10,
+        offset_to_points: u32::from(LasHeader::size_of_version(minor)) + 100,
+        num_vlrs: 1,
+        point_format_id: 1,
+        point_size: 28,
+        scales: [0.01, 0.01, 0.001],
+        offsets: [100.0, 200.0, 0.0],
+        mins: [101.0, 202.0, -3.0],
+        maxs: [111.0, 222.0, 33.0],
+        ..Default::default()
+    };
+    header.system_identifier[..5].copy_from_slice(b"tests");
+    let mut points_by_return = [0u64; 15];
+    points_by_return[..3].copy_from_slice(&[10, 5, 2]);
+    header.set_point_counts(17, points_by_return).unwrap();
+    if minor >= 3 {
+        header.start_of_waveform = 1234;
+    }
+    if minor >= 4 {
+        header.start_of_first_evlr = 5678;
+        header.num_evlrs = 2;
+    }
+    header
+}
+
+#[test]
+fn test_read_las_header() {
+    let mut las_file = File::open("tests/data/point10.las").unwrap();
+    let header = LasHeader::read_from(&mut las_file).unwrap();
+    assert_eq!(las_file.stream_position().unwrap(), 227);
+    assert_eq!(header.file_signature, *b"LASF");
+    assert_eq!((header.major, header.minor), (1, 2));
+    assert_eq!(header.header_size, 227);
+    assert_eq!(header.point_format_id, 0);
+    assert_eq!(header.point_size, 20);
+    assert_eq!(header.num_points, 1065);
+    assert_eq!(header.num_points, u64::from(header.legacy_num_points));
+    assert_eq!(header.points_by_return[5..], [0u64; 10]);
+    for (count, legacy_count) in header
+        .points_by_return
+        .iter()
+        .zip(&header.legacy_points_by_return)
+    {
+        assert_eq!(*count, u64::from(*legacy_count));
+    }
+
+    las_file
+        .seek(std::io::SeekFrom::Start(u64::from(header.offset_to_points)))
+        .unwrap();
+    let mut points = vec![0u8; header.num_points as usize * header.point_size as usize];
+    las_file.read_exact(&mut points).unwrap();
+    for point in points.chunks_exact(usize::from(header.point_size)) {
+        for i in 0..3 {
+            let raw = i32::from_le_bytes([
+                point[4 * i],
+                point[4 * i + 1],
+                point[4 * i + 2],
+                point[4 * i + 3],
+            ]);
+            let coord = f64::from(raw) * header.scales[i] + header.offsets[i];
+            assert!(header.mins[i] - header.scales[i] <= coord);
+            assert!(coord <= header.maxs[i] + header.scales[i]);
+        }
+    }
+
+    let mut laz_file = File::open("tests/data/point10.laz").unwrap();
+    let laz_header = LasHeader::read_from(&mut laz_file).unwrap();
+    assert_eq!(laz_header.point_format_id, 0x80);
+    assert_eq!(laz_header.num_points, header.num_points);
+    assert_eq!(laz_header.mins, header.mins);
+    assert_eq!(laz_header.maxs, header.maxs);
+}
+
+#[test]
+fn test_las_header_round_trip() {
+    for minor in 0..=4 {
+        let header = header_of_version(minor);
+        let mut output = Cursor::new(Vec::<u8>::new());
+        header.write_to(&mut output).unwrap();
+        assert_eq!(output.get_ref().len(), usize::from(header.header_size));
+
+        output.set_position(0);
+        let read_header = LasHeader::read_from(&mut output).unwrap();
+        assert_eq!(read_header, header);
+        assert_eq!(read_header.system_identifier(), "tests");
+        assert_eq!(read_header.generating_software(), "laz-rs");
+        assert_eq!(output.position(), u64::from(header.header_size));
+    }
+}
+
+#[test]
+fn test_las_header_point_counts() {
+    let mut header = header_of_version(4);
+    let mut points_by_return = [1u64; 15];
+    header.set_point_counts(15, points_by_return).unwrap();
+    assert_eq!(header.legacy_num_points, 0);
+    assert_eq!(header.legacy_points_by_return, [0; 5]);
+
+    points_by_return[5..].copy_from_slice(&[0; 10]);
+    header.set_point_counts(5, points_by_return).unwrap();
+    assert_eq!(header.legacy_num_points, 5);
+    assert_eq!(header.legacy_points_by_return, [1; 5]);
+
+    header.point_format_id = 6 | 0x80;
+    header.set_point_counts(5, points_by_return).unwrap();
+    assert_eq!(header.legacy_num_points, 0);
+    assert_eq!(header.num_points, 5);
+
+    // Older versions only have the legacy fields
+    let mut header = header_of_version(2);
+    header.set_point_counts(15, [1u64; 15]).unwrap();
+    assert_eq!(header.legacy_num_points, 15);
+    assert_eq!(header.legacy_points_by_return, [1; 5]);
+    assert!(header
+        .set_point_counts(u64::from(u32::MAX) + 1, [0; 15])
+        .is_err());
+}
+
+#[test]
+fn test_las_header_errors() {
+    let mut output = Cursor::new(Vec::<u8>::new());
+
+    let mut header = header_of_version(2);
+    header.file_signature = *b"LASG";
+    assert!(matches!(
+        header.write_to(&mut output),
+        Err(LasZipError::InvalidFileSignature(_))
+    ));
+    let mut data = Cursor::new(Vec::<u8>::new());
+    header_of_version(2).write_to(&mut data).unwrap();
+    data.get_mut()[3] = b'G';
+    data.set_position(0);
+    assert!(matches!(
+        LasHeader::read_from(&mut data),
+        Err(LasZipError::InvalidFileSignature(signature)) if &signature == b"LASG"
+    ));
+
+    let mut header = header_of_version(2);
+    header.minor = 5;
+    assert!(matches!(
+        header.write_to(&mut output),
+        Err(LasZipError::UnsupportedLasVersion { major: 1, minor: 5 })
+    ));
+
+    let mut header = header_of_version(4);
+    header.header_size = 227;
+    assert!(matches!(
+        header.write_to(&mut output),
+        Err(LasZipError::InvalidHeaderSize {
+            header_size: 227,
+            expected: 375
+        })
+    ));
+
+    let mut header = header_of_version(2);
+    header.offset_to_points = 100;
+    assert!(matches!(
+        header.write_to(&mut output),
+        Err(LasZipError::InvalidOffsetToPoints { .. })
+    ));
+
+    // A truncated header is an io error
+    let mut data = Cursor::new(Vec::<u8>::new());
+    header_of_version(3).write_to(&mut data).unwrap();
+    data.get_mut().truncate(200);
+    data.set_position(0);
+    assert!(matches!(
+        LasHeader::read_from(&mut data),
+        Err(LasZipError::IoError(_))
+    ));
+}
+
+/// The deprecated QuickHeader must keep reading the headers it used to read
+#[test]
+#[allow(deprecated)]
+fn test_quick_header() {
+    let mut las_file = File::open("tests/data/point10.las").unwrap();
+    let header = laz::las::file::QuickHeader::read_from(&mut las_file).unwrap();
+    assert_eq!(las_file.stream_position().unwrap(), 227);
+    assert_eq!((header.major, header.minor), (1, 2));
+    assert_eq!(header.point_size, 20);
+    assert_eq!(header.num_points, 1065);
+    assert_eq!(header.num_extra_bytes(), 0);
+
+    // Versions not supported by LasHeader are still read
+    let mut data = Cursor::new(Vec::<u8>::new());
+    header_of_version(2).write_to(&mut data).unwrap();
+    data.get_mut()[25] = 5;
+    let mut header = laz::las::file::QuickHeader::read_from(&mut data).unwrap();
+    assert_eq!((header.major, header.minor), (1, 5));
+    assert_eq!(header.num_points, 17);
+
+    for (point_format_id, point_size) in [(4, 57), (5, 63), (9, 59), (10, 67)] {
+        header.point_format_id = point_format_id;
+        header.point_size = point_size + 3;
+        assert_eq!(header.num_extra_bytes(), 3);
+    }
+    header.point_size = 20;
+    assert_eq!(header.num_extra_bytes(), 0);
+    header.point_format_id = 11;
+    assert_eq!(header.num_extra_bytes(), 0);
+}
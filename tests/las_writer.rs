@@ -0,0 +1,249 @@
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use laz::las::file::{
+    read_evlrs, read_header_and_vlrs, Evlr, LasHeader, LasWriter, SimpleReader, Vlr,
+};
+use laz::{LazItemRecordBuilder, LazVlr, LazVlrBuilder};
+
+fn read_las_file(path: &str) -> (LasHeader, Vec<u8>) {
+    let mut reader = SimpleReader::new(File::open(path).unwrap()).unwrap();
+    let mut points = Vec::new();
+    reader.read_to_end(&mut points).unwrap();
+    (reader.header.clone(), points)
+}
+
+/// Checks that the header written has the counts & bounds of the source one,
+/// and that the points can be read back.
+fn check_written_file(data: Vec<u8>, expected_header: &LasHeader, expected_points: &[u8]) {
+    let mut reader = SimpleReader::new(Cursor::new(data)).unwrap();
+    let header = reader.header.clone();
+    assert_eq!(header.num_points, expected_header.num_points);
+    assert_eq!(header.points_by_return, expected_header.points_by_return);
+    assert_eq!(
+        header.legacy_points_by_return,
+        expected_header.legacy_points_by_return
+    );
+    for i in 0..3 {
+        assert!((header.mins[i] - expected_header.mins[i]).abs() <= header.scales[i]);
+        assert!((header.maxs[i] - expected_header.maxs[i]).abs() <= header.scales[i]);
+    }
+    let mut points = Vec::new();
+    reader.read_to_end(&mut points).unwrap();
+    assert_eq!(points, expected_points);
+}
+
+#[test]
+fn test_las_writer_compressed() {
+    let (header, points) = read_las_file("tests/data/point-time-color.las");
+    let items = LazItemRecordBuilder::default_for_point_format_id(3, 0).unwrap();
+    let laz_vlr = LazVlrBuilder::new(items).with_fixed_chunk_size(100).build();
+    let vlrs = [Vlr::new("tests", 1, "a test vlr", vec![1, 2, 3])];
+
+    let mut writer =
+        LasWriter::new_compressed(Cursor::new(Vec::new()), header.clone(), &vlrs, laz_vlr).unwrap();
+    let point_size = usize::from(header.point_size);
+    writer.write_point(&points[..point_size]).unwrap();
+    writer.write_points(&points[point_size..]).unwrap();
+    writer.done().unwrap();
+    assert_eq!(writer.header().point_format_id, 3 | 0x80);
+    assert_eq!(writer.header().num_vlrs, 2);
+    let data = writer.into_inner().into_inner();
+
+    let mut cursor = Cursor::new(data.clone());
+    let (written_header, written_laz_vlr) = read_header_and_vlrs(&mut cursor).unwrap();
+    assert!(written_laz_vlr.is_some());
+    cursor.seek(SeekFrom::Start(227)).unwrap();
+    let first_vlr = Vlr::read_from(&mut cursor).unwrap();
+    assert_eq!(first_vlr.user_id(), "tests");
+    assert_eq!(first_vlr.data(), &[1, 2, 3]);
+    assert_eq!(
+        written_header.offset_to_points as usize,
+        227 + 2 * Vlr::HEADER_SIZE + 3 + laz_vlr_size(&written_laz_vlr.unwrap())
+    );
+
+    check_written_file(data, &header, &points);
+}
+
+fn laz_vlr_size(laz_vlr: &LazVlr) -> usize {
+    let mut data = Vec::new();
+    laz_vlr.write_to(&mut data).unwrap();
+    data.len()
+}
+
+#[test]
+fn test_las_writer_uncompressed() {
+    let (header, points) = read_las_file("tests/data/point10.las");
+
+    // The LasZip VLR of the given VLRs must not be written
+    let mut laz_file = File::open("tests/data/point10.laz").unwrap();
+    let (_, laz_vlr) = read_header_and_vlrs(&mut laz_file).unwrap();
+    let mut laz_vlr_data = Vec::new();
+    laz_vlr.unwrap().write_to(&mut laz_vlr_data).unwrap();
+    let vlrs = [Vlr::new(
+        LazVlr::USER_ID,
+        LazVlr::RECORD_ID,
+        LazVlr::DESCRIPTION,
+        laz_vlr_data,
+    )];
+
+    let mut writer = LasWriter::new(Cursor::new(Vec::new()), header.clone(), &vlrs).unwrap();
+    writer.write_points(&points).unwrap();
+    writer.done().unwrap();
+    assert_eq!(writer.header().num_vlrs, 0);
+    assert_eq!(writer.header().offset_to_points, 227);
+    let data = writer.into_inner().into_inner();
+    assert_eq!(data.len(), 227 + points.len());
+
+    let mut original = Vec::new();
+    File::open("tests/data/point10.las")
+        .unwrap()
+        .read_to_end(&mut original)
+        .unwrap();
+    assert_eq!(data[227..], original[header.offset_to_points as usize..]);
+
+    check_written_file(data, &header, &points);
+}
+
+#[test]
+fn test_las_writer_invalid_points_buffer() {
+    let header = LasHeader::default();
+    let mut writer = LasWriter::new(Cursor::new(Vec::new()), header, &[]).unwrap();
+    assert!(writer.write_points(&[0u8; 21]).is_err());
+    writer.done().unwrap();
+    assert_eq!(writer.header().num_points, 0);
+    assert!(writer.done().is_err());
+}
+
+#[test]
+fn test_las_writer_invalid_point_size() {
+    for point_size in [0, 14, 19] {
+        let header = LasHeader {
+            point_size,
+            ..Default::default()
+        };
+        assert!(matches!(
+            LasWriter::new(Cursor::new(Vec::new()), header, &[]),
+            Err(laz::LasZipError::PointSizeTooSmall { minimum: 20, .. })
+        ));
+    }
+    let header = LasHeader {
+        point_format_id: 11,
+        ..Default::default()
+    };
+    assert!(matches!(
+        LasWriter::new(Cursor::new(Vec::new()), header, &[]),
+        Err(laz::LasZipError::UnsupportedPointFormat(11))
+    ));
+}
+
+#[test]
+fn test_las_writer_evlrs() {
+    const NUM_POINTS: usize = 500;
+    const POINT_SIZE: usize = 30;
+    let mut points = vec![0u8; NUM_POINTS * POINT_SIZE];
+    for (i, point) in points.chunks_exact_mut(POINT_SIZE).enumerate() {
+        point[0..4].copy_from_slice(&(i as i32 * 3).to_le_bytes());
+        point[4..8].copy_from_slice(&(-(i as i32)).to_le_bytes());
+        point[8..12].copy_from_slice(&(i as i32 % 7).to_le_bytes());
+        // return number & number of returns
+        let return_number = (i % 12 + 1) as u8;
+        point[14] = return_number | (12 << 4);
+    }
+    let header = LasHeader {
+        minor: 4,
+        header_size: LasHeader::size_of_version(4),
+        point_format_id: 6,
+        point_size: POINT_SIZE as u16,
+        scales: [0.5, 0.5, 0.5],
+        ..Default::default()
+    };
+    let items = LazItemRecordBuilder::default_for_point_format_id(6, 0).unwrap();
+    let laz_vlr = LazVlrBuilder::new(items).with_fixed_chunk_size(100).build();
+
+    let mut writer =
+        LasWriter::new_compressed(Cursor::new(Vec::new()), header, &[], laz_vlr).unwrap();
+    writer.write_points(&points).unwrap();
+    writer
+        .push_evlr(Evlr::new("tests", 1, "first", vec![1u8; 70_000]))
+        .unwrap();
+    writer
+        .push_evlr(Evlr::new("tests", 2, "second", vec![2, 3]))
+        .unwrap();
+    writer.done().unwrap();
+    let data = writer.into_inner().into_inner();
+
+    let mut cursor = Cursor::new(data.clone());
+    let (header, laz_vlr) = read_header_and_vlrs(&mut cursor).unwrap();
+    let laz_vlr = laz_vlr.unwrap();
+    assert_eq!(header.num_points, NUM_POINTS as u64);
+    assert_eq!(header.legacy_num_points, 0);
+    assert_eq!(
+        header.points_by_return[..12],
+        [42, 42, 42, 42, 42, 42, 42, 42, 41, 41, 41, 41]
+    );
+    assert_eq!(header.mins, [0.0, -0.5 * (NUM_POINTS - 1) as f64, 0.0]);
+    assert_eq!(header.maxs, [1.5 * (NUM_POINTS - 1) as f64, 0.0, 3.0]);
+    assert_eq!(header.num_evlrs, 2);
+    assert_eq!(laz_vlr.number_of_special_evlrs(), 2);
+    assert_eq!(
+        laz_vlr.offset_to_special_evlrs() as u64,
+        header.start_of_first_evlr
+    );
+
+    let evlrs = read_evlrs(&mut cursor, &header).unwrap();
+    assert_eq!(evlrs.len(), 2);
+    assert_eq!(evlrs[0].user_id(), "tests");
+    assert_eq!(evlrs[0].record_id(), 1);
+    assert_eq!(evlrs[0].data(), &vec![1u8; 70_000][..]);
+    assert_eq!(evlrs[1].record_id(), 2);
+    assert_eq!(evlrs[1].data(), &[2, 3]);
+    assert_eq!(cursor.position(), data.len() as u64);
+
+    let mut reader = SimpleReader::new(Cursor::new(data)).unwrap();
+    let mut read_points = Vec::new();
+    reader.read_to_end(&mut read_points).unwrap();
+    assert_eq!(read_points, points);
+}
+
+#[test]
+fn test_las_writer_more_than_5_returns_in_las_1_2() {
+    let (header, points) = read_las_file("tests/data/point-time.las");
+    assert_eq!((header.minor, header.point_format_id), (2, 1));
+    let point_size = usize::from(header.point_size);
+    let mut points = points[..2 * point_size].to_vec();
+    points[point_size + 14] = (points[point_size + 14] & !0b111) | 6;
+
+    let mut writer = LasWriter::new(Cursor::new(Vec::new()), header, &[]).unwrap();
+    writer.write_points(&points).unwrap();
+    writer.done().unwrap();
+    assert_eq!(writer.header().legacy_num_points, 2);
+
+    let mut reader = SimpleReader::new(Cursor::new(writer.into_inner().into_inner())).unwrap();
+    let mut read_points = Vec::new();
+    reader.read_to_end(&mut read_points).unwrap();
+    assert_eq!(read_points, points);
+}
+
+#[test]
+fn test_las_writer_evlrs_need_las_1_4() {
+    let mut writer = LasWriter::new(Cursor::new(Vec::new()), LasHeader::default(), &[]).unwrap();
+    assert!(writer.push_evlr(Evlr::new("tests", 1, "", vec![])).is_err());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_las_writer_par_compressed() {
+    let (header, points) = read_las_file("tests/data/point-time.las");
+    let items = LazItemRecordBuilder::default_for_point_format_id(1, 0).unwrap();
+    let laz_vlr = LazVlrBuilder::new(items).with_fixed_chunk_size(100).build();
+
+    let mut writer =
+        LasWriter::new_par_compressed(Cursor::new(Vec::new()), header.clone(), &[], laz_vlr)
+            .unwrap();
+    writer.write_points(&points).unwrap();
+    writer.done().unwrap();
+    let data = writer.into_inner().into_inner();
+
+    check_written_file(data, &header, &points);
+}
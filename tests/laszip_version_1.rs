@@ -1,6 +1,8 @@
 use laz::las::file::SimpleReader;
+use laz::las::{Point3, PointColumns};
+use laz::{LasZipDecompressor, LazItemRecordBuilder, LazVlrBuilder};
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, Cursor, Read};
 
 #[test]
 fn test_version_1_point_wise() {
@@ -23,3 +25,46 @@ fn test_version_1_point_wise() {
         assert_eq!(las_point, laz_point, "Point {} are not equal", i);
     }
 }
+
+#[test]
+fn test_version_1_decompress_columns() {
+    let mut las_file =
+        SimpleReader::new(File::open("./tests/data/point-time-color.las").unwrap()).unwrap();
+    let num_points = las_file.header.num_points as usize;
+    let mut points = Vec::new();
+    las_file.read_to_end(&mut points).unwrap();
+
+    let mut expected = PointColumns::default();
+    for point in points.chunks_exact(34) {
+        let u16_at = |i: usize| u16::from_le_bytes([point[i], point[i + 1]]);
+        let i32_at =
+            |i: usize| i32::from_le_bytes([point[i], point[i + 1], point[i + 2], point[i + 3]]);
+        expected.x.push(i32_at(0));
+        expected.y.push(i32_at(4));
+        expected.z.push(i32_at(8));
+        expected.intensity.push(u16_at(12));
+        expected.classification.push(point[15] & 0x1F);
+        let mut gps_time = [0u8; 8];
+        gps_time.copy_from_slice(&point[20..28]);
+        expected.gps_time.push(f64::from_le_bytes(gps_time));
+        expected.rgb.push(laz::las::rgb::RGB {
+            red: u16_at(28),
+            green: u16_at(30),
+            blue: u16_at(32),
+        });
+    }
+
+    let vlr = LazVlrBuilder::new(LazItemRecordBuilder::version_1_of::<Point3>(0))
+        .with_fixed_chunk_size(500)
+        .build();
+    let mut compressed = Cursor::new(Vec::<u8>::new());
+    laz::compress_buffer(&mut compressed, &points, vlr.clone()).unwrap();
+    compressed.set_position(0);
+
+    let mut decompressor = LasZipDecompressor::new(compressed, vlr).unwrap();
+    let mut columns = PointColumns::default();
+    decompressor
+        .decompress_columns(num_points, &mut columns)
+        .unwrap();
+    assert_eq!(columns, expected);
+}
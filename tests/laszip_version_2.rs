@@ -1,8 +1,10 @@
 use std::fs::File;
 use std::io::{Cursor, Read, Seek, SeekFrom};
 
+use laz::laszip::{ChunkDecoder, ChunkTable};
 use laz::{
-    LasZipCompressor, LasZipDecompressor, LazItemRecordBuilder, LazItemType, LazVlr, LazVlrBuilder,
+    compress_buffer, LasZipCompressor, LasZipDecompressor, LasZipError, LazItemRecordBuilder,
+    LazItemType, LazVlr, LazVlrBuilder,
 };
 
 fn loop_test_on_buffer_(las_path: &str, laz_path: &str) {
@@ -124,6 +126,9 @@ fn test_seek() {
         LazVlr::read_from(&mut vlr_data).unwrap(),
     )
     .unwrap();
+    decompressor
+        .set_point_count(las_header.num_points as u64)
+        .unwrap();
 
     let mut decompression_buf = vec![0u8; las_header.point_size as usize];
     let mut buf = vec![0u8; las_header.point_size as usize];
@@ -155,28 +160,156 @@ fn test_seek() {
     // stream to a point that is beyond the number of points compressed
     // BUT the point index fall into the last chunk index
     let point_idx = las_header.num_points as u64 + 1;
-    las_file
-        .seek(SeekFrom::Start(
-            las_header.offset_to_points as u64 + (point_idx * las_header.point_size as u64) as u64,
-        ))
-        .unwrap();
-    decompressor.seek(point_idx as u64).unwrap();
-
-    assert!(decompressor.decompress_one(&mut decompression_buf).is_err());
-    assert!(las_file.read_exact(&mut buf).is_err());
+    assert!(matches!(
+        decompressor.seek(point_idx),
+        Err(LasZipError::PointIndexOutOfBounds { requested, total })
+            if requested == point_idx && total == las_header.num_points as u64
+    ));
 
     // stream to a point that is beyond the number of points compressed
     // and that does not belong to the last chunk
     let point_idx = las_header.num_points as u64 + 36;
+    assert!(matches!(
+        decompressor.seek(point_idx),
+        Err(LasZipError::PointIndexOutOfBounds { requested, total })
+            if requested == point_idx && total == las_header.num_points as u64
+    ));
+
+    // The last point can still be reached
+    let point_idx = las_header.num_points as u64 - 1;
     las_file
         .seek(SeekFrom::Start(
-            las_header.offset_to_points as u64 + (point_idx * las_header.point_size as u64) as u64,
+            las_header.offset_to_points as u64 + point_idx * las_header.point_size as u64,
         ))
         .unwrap();
-    decompressor.seek(point_idx as u64).unwrap();
+    decompressor.seek(point_idx).unwrap();
+    decompressor.decompress_one(&mut decompression_buf).unwrap();
+    las_file.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, &decompression_buf);
+}
+
+/// Points of the same value compress so well that decompressing a point-wise chunk
+/// past its last point does not fail, so the number of points of the last
+/// chunk has to come from the point count.
+#[test]
+fn test_seek_in_partial_point_wise_chunk() {
+    for (laz_items, point_size) in [
+        (
+            LazItemRecordBuilder::default_version_of::<laz::las::Point0>(0),
+            20,
+        ),
+        (
+            LazItemRecordBuilder::default_version_of::<laz::las::Point3>(0),
+            34,
+        ),
+    ] {
+        let vlr = LazVlrBuilder::new(laz_items)
+            .with_fixed_chunk_size(1000)
+            .build();
+        let points = vec![7u8; 1500 * point_size];
+        let mut data = Cursor::new(Vec::<u8>::new());
+        compress_buffer(&mut data, &points, vlr.clone()).unwrap();
+
+        data.set_position(0);
+        let mut chunk_table = ChunkTable::read_from(&mut data, &vlr).unwrap();
+        assert_eq!(chunk_table[1].point_count, 1000);
+        chunk_table.set_total_point_count(&vlr, 1500).unwrap();
+        assert_eq!(chunk_table[1].point_count, 500);
+        assert!(chunk_table.set_total_point_count(&vlr, 2001).is_err());
 
-    assert!(decompressor.decompress_one(&mut decompression_buf).is_err());
-    assert!(las_file.read_exact(&mut buf).is_err());
+        let mut point = vec![0u8; point_size];
+        data.set_position(0);
+        let mut decompressor = LasZipDecompressor::new(&mut data, vlr.clone()).unwrap();
+        decompressor.set_point_count(1500).unwrap();
+        assert!(matches!(
+            decompressor.seek(1502),
+            Err(LasZipError::PointIndexOutOfBounds {
+                requested: 1502,
+                total: 1500
+            })
+        ));
+        decompressor.seek(1499).unwrap();
+        decompressor.decompress_one(&mut point).unwrap();
+        assert_eq!(point, &points[..point_size]);
+
+        #[cfg(feature = "parallel")]
+        {
+            drop(decompressor);
+            data.set_position(0);
+            let mut decompressor = laz::ParLasZipDecompressor::new(&mut data, vlr).unwrap();
+            decompressor.set_point_count(1500).unwrap();
+            assert!(matches!(
+                decompressor.seek(1502),
+                Err(LasZipError::PointIndexOutOfBounds {
+                    requested: 1502,
+                    total: 1500
+                })
+            ));
+            decompressor.seek(1499).unwrap();
+            decompressor.decompress_many(&mut point).unwrap();
+            assert_eq!(point, &points[..point_size]);
+        }
+    }
+}
+
+/// Compresses the first `num_points` points of the LAS file in one fixed-size chunk
+/// that is not complete, and returns them with the compressed data.
+fn compress_first_points(las_path: &str, num_points: usize) -> (Vec<u8>, Cursor<Vec<u8>>, LazVlr) {
+    let mut las_file = File::open(las_path).unwrap();
+    let (las_header, _) = laz::las::file::read_header_and_vlrs(&mut las_file).unwrap();
+    let mut points = vec![0u8; num_points * las_header.point_size as usize];
+    las_file.read_exact(&mut points).unwrap();
+    let vlr = LazVlr::from_laz_items(
+        LazItemRecordBuilder::default_for_point_format_id(
+            las_header.point_format_id,
+            las_header.num_extra_bytes().unwrap(),
+        )
+        .unwrap(),
+    );
+    let mut data = Cursor::new(Vec::<u8>::new());
+    compress_buffer(&mut data, &points, vlr.clone()).unwrap();
+    data.set_position(0);
+    (points, data, vlr)
+}
+
+/// Without the point count, the end of the last point-wise chunk
+/// is found by decompressing it.
+#[test]
+fn test_seek_without_point_count() {
+    let (points, mut data, vlr) = compress_first_points("tests/data/point-time.las", 5);
+    let point_size = vlr.items_size() as usize;
+    let mut point = vec![0u8; point_size];
+
+    let mut decompressor = LasZipDecompressor::new(&mut data, vlr.clone()).unwrap();
+    for requested in [10, 5] {
+        assert!(matches!(
+            decompressor.seek(requested),
+            Err(LasZipError::PointIndexOutOfBounds { requested: r, total: 5 }) if r == requested
+        ));
+    }
+    decompressor.seek(4).unwrap();
+    decompressor.decompress_one(&mut point).unwrap();
+    assert_eq!(point, &points[4 * point_size..]);
+    let mut four_points = vec![0u8; 4 * point_size];
+    decompressor.seek(1).unwrap();
+    decompressor.decompress_many(&mut four_points).unwrap();
+    assert_eq!(four_points, &points[point_size..]);
+
+    #[cfg(feature = "parallel")]
+    {
+        drop(decompressor);
+        data.set_position(0);
+        let mut decompressor = laz::ParLasZipDecompressor::new(&mut data, vlr).unwrap();
+        for requested in [10, 5] {
+            assert!(matches!(
+                decompressor.seek(requested),
+                Err(LasZipError::PointIndexOutOfBounds { requested: r, total: 5 }) if r == requested
+            ));
+        }
+        decompressor.seek(4).unwrap();
+        decompressor.decompress_many(&mut point).unwrap();
+        assert_eq!(point, &points[4 * point_size..]);
+    }
 }
 
 #[cfg(feature = "parallel")]
@@ -193,6 +326,9 @@ fn test_parallel_seek() {
         LazVlr::read_from(&mut vlr_data).unwrap(),
     )
     .unwrap();
+    decompressor
+        .set_point_count(las_header.num_points as u64)
+        .unwrap();
     let point_size = las_header.point_size as usize;
     let mut decompression_buf = vec![0u8; point_size];
     let mut buf = vec![0u8; point_size];
@@ -228,30 +364,931 @@ fn test_parallel_seek() {
     // stream to a point that is beyond the number of points compressed
     // BUT the point index fall into the last chunk index
     let point_idx = las_header.num_points as u64 + 1;
-    las_file
-        .seek(SeekFrom::Start(
-            las_header.offset_to_points as u64 + (point_idx * las_header.point_size as u64) as u64,
-        ))
-        .unwrap();
-    decompressor.seek(point_idx as u64).unwrap();
-
-    assert!(decompressor
-        .decompress_many(&mut decompression_buf)
-        .is_err());
-    assert!(las_file.read_exact(&mut buf).is_err());
+    assert!(matches!(
+        decompressor.seek(point_idx),
+        Err(LasZipError::PointIndexOutOfBounds { requested, total })
+            if requested == point_idx && total == las_header.num_points as u64
+    ));
 
     // stream to a point that is beyond the number of points compressed
     // and that does not belong to the last chunk
     let point_idx = las_header.num_points as u64 + 36;
+    assert!(matches!(
+        decompressor.seek(point_idx),
+        Err(LasZipError::PointIndexOutOfBounds { requested, total })
+            if requested == point_idx && total == las_header.num_points as u64
+    ));
+
+    // The last point can still be reached
+    let point_idx = las_header.num_points as u64 - 1;
     las_file
         .seek(SeekFrom::Start(
-            las_header.offset_to_points as u64 + (point_idx * las_header.point_size as u64) as u64,
+            las_header.offset_to_points as u64 + point_idx * las_header.point_size as u64,
         ))
         .unwrap();
-    decompressor.seek(point_idx as u64).unwrap();
-
-    assert!(decompressor
+    decompressor.seek(point_idx).unwrap();
+    decompressor
         .decompress_many(&mut decompression_buf)
-        .is_err());
-    assert!(las_file.read_exact(&mut buf).is_err());
+        .unwrap();
+    las_file.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, &decompression_buf);
+}
+
+#[test]
+fn test_decompress_columns() {
+    let mut las_file = File::open("tests/data/extra-bytes.las").unwrap();
+    let (las_header, _) = laz::las::file::read_header_and_vlrs(&mut las_file).unwrap();
+    let mut laz_file = File::open("tests/data/extra-bytes.laz").unwrap();
+    let (_, laz_vlr) = laz::las::file::read_header_and_vlrs(&mut laz_file).unwrap();
+    let laz_vlr = laz_vlr.expect("Expected  a laz vlr in the laz file");
+    let num_extra_bytes = las_header.point_size as usize - 34;
+
+    let mut expected = laz::las::PointColumns::default();
+    expected.num_extra_bytes = num_extra_bytes;
+    let mut point = vec![0u8; las_header.point_size as usize];
+    for _ in 0..las_header.num_points {
+        las_file.read_exact(&mut point).unwrap();
+        let u16_at = |i: usize| u16::from_le_bytes([point[i], point[i + 1]]);
+        let i32_at =
+            |i: usize| i32::from_le_bytes([point[i], point[i + 1], point[i + 2], point[i + 3]]);
+        expected.x.push(i32_at(0));
+        expected.y.push(i32_at(4));
+        expected.z.push(i32_at(8));
+        expected.intensity.push(u16_at(12));
+        expected.classification.push(point[15] & 0x1F);
+        let mut gps_time = [0u8; 8];
+        gps_time.copy_from_slice(&point[20..28]);
+        expected.gps_time.push(f64::from_le_bytes(gps_time));
+        expected.rgb.push(laz::las::rgb::RGB {
+            red: u16_at(28),
+            green: u16_at(30),
+            blue: u16_at(32),
+        });
+        expected.extra_bytes.extend_from_slice(&point[34..]);
+    }
+
+    let mut decompressor = LasZipDecompressor::new(&mut laz_file, laz_vlr).unwrap();
+    let mut columns = laz::las::PointColumns::default();
+    // Decompress in two calls to check that points are appended
+    let half = las_header.num_points as usize / 2;
+    decompressor.decompress_columns(half, &mut columns).unwrap();
+    decompressor
+        .decompress_columns(las_header.num_points as usize - half, &mut columns)
+        .unwrap();
+
+    assert_eq!(columns.len(), las_header.num_points as usize);
+    assert!(columns.nir.is_empty());
+    assert_eq!(columns, expected);
+}
+
+#[test]
+fn test_decompress_range() {
+    const POINT_SIZE: usize = 20;
+    let (mut las_file, mut compressed_data_stream, mut vlr_data) =
+        create_data_with_small_chunk_size();
+    las_file.seek(SeekFrom::Start(0)).unwrap();
+    let (las_header, _) = laz::las::file::read_header_and_vlrs(&mut las_file).unwrap();
+    let mut expected_points = vec![0u8; las_header.num_points as usize * POINT_SIZE];
+    las_file.read_exact(&mut expected_points).unwrap();
+    let vlr = LazVlr::read_from(&mut vlr_data).unwrap();
+    let num_points = las_header.num_points as u64;
+
+    // Ranges within a chunk, across chunks, and at the end of the last (incomplete) chunk
+    let ranges = [
+        (0, 1),
+        (45, 10),
+        (100, 200),
+        (num_points - 5, 5),
+        (0, num_points),
+    ];
+
+    {
+        let mut decompressor =
+            LasZipDecompressor::new(&mut compressed_data_stream, vlr.clone()).unwrap();
+        decompressor.set_point_count(num_points).unwrap();
+        for (start, count) in ranges.iter().copied() {
+            let mut points = vec![0u8; count as usize * POINT_SIZE];
+            decompressor
+                .decompress_range(start, count, &mut points)
+                .unwrap();
+            assert_eq!(
+                points.as_slice(),
+                &expected_points
+                    [start as usize * POINT_SIZE..(start + count) as usize * POINT_SIZE]
+            );
+        }
+        let mut points = vec![0u8; 6 * POINT_SIZE];
+        assert!(matches!(
+            decompressor.decompress_range(num_points - 5, 6, &mut points),
+            Err(LasZipError::PointIndexOutOfBounds { requested, total })
+                if requested == num_points && total == num_points
+        ));
+        assert!(matches!(
+            decompressor.decompress_range(0, 7, &mut points),
+            Err(LasZipError::IoError(e)) if e.kind() == std::io::ErrorKind::InvalidInput
+        ));
+
+        // Decompression continues after the range
+        let mut points = vec![0u8; 10 * POINT_SIZE];
+        decompressor.decompress_range(95, 10, &mut points).unwrap();
+        decompressor.decompress_many(&mut points).unwrap();
+        assert_eq!(
+            points.as_slice(),
+            &expected_points[105 * POINT_SIZE..115 * POINT_SIZE]
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    {
+        compressed_data_stream.set_position(0);
+        let mut decompressor =
+            laz::ParLasZipDecompressor::new(&mut compressed_data_stream, vlr).unwrap();
+        decompressor.set_point_count(num_points).unwrap();
+        let mut first_point = vec![0u8; POINT_SIZE];
+        decompressor.decompress_many(&mut first_point).unwrap();
+        for (start, count) in ranges.iter().copied() {
+            let mut points = vec![0u8; count as usize * POINT_SIZE];
+            decompressor
+                .decompress_range(start, count, &mut points)
+                .unwrap();
+            assert_eq!(
+                points.as_slice(),
+                &expected_points
+                    [start as usize * POINT_SIZE..(start + count) as usize * POINT_SIZE]
+            );
+        }
+        let mut points = vec![0u8; 6 * POINT_SIZE];
+        assert!(matches!(
+            decompressor.decompress_range(num_points - 5, 6, &mut points),
+            Err(LasZipError::PointIndexOutOfBounds { requested, total })
+                if requested == num_points && total == num_points
+        ));
+        assert!(matches!(
+            decompressor.decompress_range(0, 7, &mut points),
+            Err(LasZipError::IoError(e)) if e.kind() == std::io::ErrorKind::InvalidInput
+        ));
+
+        // Decompression continues after the range
+        let mut points = vec![0u8; 10 * POINT_SIZE];
+        decompressor.decompress_range(95, 10, &mut points).unwrap();
+        decompressor.decompress_many(&mut points).unwrap();
+        assert_eq!(
+            points.as_slice(),
+            &expected_points[105 * POINT_SIZE..115 * POINT_SIZE]
+        );
+    }
+}
+
+/// Ranges in the last point-wise chunk, without the point count.
+#[test]
+fn test_decompress_range_without_point_count() {
+    let (points, mut data, vlr) = compress_first_points("tests/data/point-time.las", 5);
+    let point_size = vlr.items_size() as usize;
+    let range_points =
+        |start: usize, count: usize| &points[start * point_size..][..count * point_size];
+
+    let mut decompressor = LasZipDecompressor::new(&mut data, vlr.clone()).unwrap();
+    let mut out = vec![0u8; 3 * point_size];
+    decompressor.decompress_range(0, 3, &mut out).unwrap();
+    assert_eq!(out, range_points(0, 3));
+
+    #[cfg(feature = "parallel")]
+    {
+        drop(decompressor);
+        data.set_position(0);
+        let mut decompressor = laz::ParLasZipDecompressor::new(&mut data, vlr).unwrap();
+        for (start, count) in [(0, 3), (2, 2), (0, 5), (4, 1)] {
+            let mut out = vec![0u8; count * point_size];
+            decompressor
+                .decompress_range(start as u64, count as u64, &mut out)
+                .unwrap();
+            assert_eq!(out, range_points(start, count));
+        }
+
+        // Decompression continues after the range, with the points that were decompressed
+        let mut out = vec![0u8; 2 * point_size];
+        decompressor
+            .decompress_range(0, 1, &mut out[..point_size])
+            .unwrap();
+        decompressor.decompress_many(&mut out).unwrap();
+        assert_eq!(out, range_points(1, 2));
+
+        let mut out = vec![0u8; 4 * point_size];
+        assert!(matches!(
+            decompressor.decompress_range(3, 4, &mut out),
+            Err(LasZipError::PointIndexOutOfBounds {
+                requested: 6,
+                total: 5
+            })
+        ));
+    }
+}
+
+#[test]
+fn test_chunk_decoder() {
+    const POINT_SIZE: usize = 20;
+    let (mut las_file, compressed_data_stream, mut vlr_data) = create_data_with_small_chunk_size();
+    las_file.seek(SeekFrom::Start(0)).unwrap();
+    let (las_header, _) = laz::las::file::read_header_and_vlrs(&mut las_file).unwrap();
+    let mut expected_points = vec![0u8; las_header.num_points as usize * POINT_SIZE];
+    las_file.read_exact(&mut expected_points).unwrap();
+    let vlr = LazVlr::read_from(&mut vlr_data).unwrap();
+    let num_points = las_header.num_points as u64;
+
+    let data_start = 0u64;
+    let mut file = compressed_data_stream;
+    let mut chunk_table = ChunkTable::read_from(&mut file, &vlr).unwrap();
+    chunk_table.set_total_point_count(&vlr, num_points).unwrap();
+
+    let decoder = ChunkDecoder::new(vlr).unwrap();
+    for range in [0..1, 45..55, 100..300, num_points - 5..num_points] {
+        let chunks = chunk_table
+            .chunk_byte_ranges(data_start, range.clone())
+            .unwrap();
+        assert_eq!(chunks.first().unwrap().index, range.start as usize / 50);
+        assert_eq!(chunks.last().unwrap().index, (range.end - 1) as usize / 50);
+        if range.start == 0 {
+            assert_eq!(chunks[0].byte_offset, ChunkTable::OFFSET_SIZE as u64);
+        }
+
+        let mut points = Vec::<u8>::new();
+        for chunk in &chunks {
+            let start = chunk.byte_offset as usize;
+            let compressed = &file.get_ref()[start..start + chunk.byte_len as usize];
+            points.extend(decoder.decode(&chunk.entry(), compressed).unwrap());
+        }
+        let first_point = chunks[0].first_point as usize;
+        assert_eq!(
+            points.as_slice(),
+            &expected_points[first_point * POINT_SIZE..][..points.len()]
+        );
+    }
+
+    assert!(chunk_table
+        .chunk_byte_ranges(data_start, 10..10)
+        .unwrap()
+        .is_empty());
+    assert!(matches!(
+        chunk_table.chunk_byte_ranges(data_start, 0..num_points + 1),
+        Err(LasZipError::PointIndexOutOfBounds { requested, total })
+            if requested == num_points && total == num_points
+    ));
+}
+
+#[test]
+fn test_rebuild_chunk_table() {
+    const POINT_SIZE: usize = 20;
+    let (mut las_file, mut compressed_data_stream, mut vlr_data) =
+        create_data_with_small_chunk_size();
+    las_file.seek(SeekFrom::Start(0)).unwrap();
+    let (las_header, _) = laz::las::file::read_header_and_vlrs(&mut las_file).unwrap();
+    let mut expected_points = vec![0u8; las_header.num_points as usize * POINT_SIZE];
+    las_file.read_exact(&mut expected_points).unwrap();
+    let vlr = LazVlr::read_from(&mut vlr_data).unwrap();
+    let num_points = las_header.num_points as u64;
+
+    let mut expected_chunk_table =
+        ChunkTable::read_from(&mut compressed_data_stream, &vlr).unwrap();
+    expected_chunk_table
+        .set_total_point_count(&vlr, num_points)
+        .unwrap();
+    let chunks_end = ChunkTable::OFFSET_SIZE
+        + expected_chunk_table
+            .as_ref()
+            .iter()
+            .map(|entry| entry.byte_count as usize)
+            .sum::<usize>();
+
+    // Simulate a writer that could not write the chunk table
+    let mut data = compressed_data_stream.into_inner();
+    data.truncate(chunks_end);
+    data[..ChunkTable::OFFSET_SIZE].copy_from_slice(&(-1i64).to_le_bytes());
+    let mut file = Cursor::new(data);
+
+    let chunk_table = ChunkTable::rebuild(&mut file, &vlr, num_points).unwrap();
+    assert_eq!(chunk_table.as_ref(), expected_chunk_table.as_ref());
+    assert_eq!(file.position(), ChunkTable::OFFSET_SIZE as u64);
+
+    file.set_position(0);
+    let chunk_table = ChunkTable::rebuild_and_write(&mut file, &vlr, num_points).unwrap();
+    assert_eq!(chunk_table.as_ref(), expected_chunk_table.as_ref());
+
+    file.set_position(0);
+    let mut chunk_table = ChunkTable::read_from(&mut file, &vlr).unwrap();
+    chunk_table.set_total_point_count(&vlr, num_points).unwrap();
+    assert_eq!(chunk_table.as_ref(), expected_chunk_table.as_ref());
+
+    file.set_position(0);
+    let mut decompressor = LasZipDecompressor::new(&mut file, vlr).unwrap();
+    decompressor.set_point_count(num_points).unwrap();
+    let mut points = vec![0u8; expected_points.len()];
+    decompressor.decompress_many(&mut points).unwrap();
+    assert_eq!(points, expected_points);
+    decompressor.seek(num_points - 1).unwrap();
+}
+
+#[test]
+fn test_decompress_recoverable() {
+    const POINT_SIZE: usize = 20;
+    const CHUNK_SIZE: usize = 50;
+    let (mut las_file, mut compressed_data_stream, mut vlr_data) =
+        create_data_with_small_chunk_size();
+    las_file.seek(SeekFrom::Start(0)).unwrap();
+    let (las_header, _) = laz::las::file::read_header_and_vlrs(&mut las_file).unwrap();
+    let mut expected_points = vec![0u8; las_header.num_points as usize * POINT_SIZE];
+    las_file.read_exact(&mut expected_points).unwrap();
+    let vlr = LazVlr::read_from(&mut vlr_data).unwrap();
+    let num_points = las_header.num_points as u64;
+
+    let chunk_table = ChunkTable::read_from(&mut compressed_data_stream, &vlr).unwrap();
+    let chunk_offsets = chunk_table
+        .as_ref()
+        .iter()
+        .scan(ChunkTable::OFFSET_SIZE as u64, |offset, entry| {
+            let chunk_offset = *offset;
+            *offset += entry.byte_count;
+            Some(chunk_offset)
+        })
+        .collect::<Vec<u64>>();
+    let data = compressed_data_stream.into_inner();
+
+    // Complete data
+    let mut decompressor = LasZipDecompressor::new(Cursor::new(&data), vlr.clone()).unwrap();
+    let mut points = Vec::<u8>::new();
+    let report = decompressor
+        .decompress_recoverable(num_points, &mut points)
+        .unwrap();
+    assert!(report.is_complete());
+    assert_eq!(report.points_recovered, num_points);
+    assert_eq!(report.last_good_chunk, Some(chunk_table.len() - 1));
+    assert_eq!(points, expected_points);
+
+    // Truncated in the middle of a chunk, and at the end of a chunk
+    for truncated_len in [chunk_offsets[5] + 10, chunk_offsets[5]] {
+        let truncated_data = &data[..truncated_len as usize];
+        let mut decompressor =
+            LasZipDecompressor::new(Cursor::new(truncated_data), vlr.clone()).unwrap();
+        let mut points = Vec::<u8>::new();
+        let report = decompressor
+            .decompress_recoverable(num_points, &mut points)
+            .unwrap();
+        assert!(!report.is_complete());
+        assert_eq!(report.points_recovered, 5 * CHUNK_SIZE as u64);
+        assert_eq!(report.last_good_chunk, Some(4));
+        assert_eq!(report.damage_offset, Some(chunk_offsets[5]));
+        assert_eq!(points, &expected_points[..5 * CHUNK_SIZE * POINT_SIZE]);
+    }
+
+    #[cfg(feature = "parallel")]
+    {
+        let mut decompressor =
+            laz::ParLasZipDecompressor::new(Cursor::new(&data), vlr.clone()).unwrap();
+        decompressor.set_point_count(num_points).unwrap();
+        let mut points = Vec::<u8>::new();
+        let report = decompressor
+            .decompress_recoverable(10, &mut points)
+            .unwrap();
+        assert!(report.is_complete());
+        assert_eq!(report.last_good_chunk, Some(0));
+        let report = decompressor
+            .decompress_recoverable(num_points - 10, &mut points)
+            .unwrap();
+        assert!(report.is_complete());
+        assert_eq!(report.points_recovered, num_points - 10);
+        assert_eq!(report.last_good_chunk, Some(chunk_table.len() - 1));
+        assert_eq!(points, expected_points);
+
+        // Remove the last chunk, but keep the chunk table,
+        // found using the offset written at the end
+        let last_chunk_offset = *chunk_offsets.last().unwrap() as usize;
+        let mut chunk_table_offset = [0u8; ChunkTable::OFFSET_SIZE];
+        chunk_table_offset.copy_from_slice(&data[..ChunkTable::OFFSET_SIZE]);
+        let chunk_table_offset = i64::from_le_bytes(chunk_table_offset) as usize;
+        let mut damaged_data = data[..last_chunk_offset].to_vec();
+        damaged_data[..ChunkTable::OFFSET_SIZE].copy_from_slice(&(-1i64).to_le_bytes());
+        let new_chunk_table_offset = damaged_data.len() as i64;
+        damaged_data.extend_from_slice(&data[chunk_table_offset..]);
+        damaged_data.extend_from_slice(&new_chunk_table_offset.to_le_bytes());
+
+        let mut decompressor =
+            laz::ParLasZipDecompressor::new(Cursor::new(&damaged_data), vlr).unwrap();
+        let mut points = Vec::<u8>::new();
+        let report = decompressor
+            .decompress_recoverable(num_points, &mut points)
+            .unwrap();
+        let num_recovered_chunks = chunk_table.len() - 1;
+        assert_eq!(
+            report.points_recovered,
+            (num_recovered_chunks * CHUNK_SIZE) as u64
+        );
+        assert_eq!(report.last_good_chunk, Some(num_recovered_chunks - 1));
+        assert_eq!(report.damage_offset, Some(last_chunk_offset as u64));
+        assert_eq!(
+            points,
+            &expected_points[..num_recovered_chunks * CHUNK_SIZE * POINT_SIZE]
+        );
+    }
+}
+
+/// Intact point-wise data must not be reported as damaged without the point count.
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_decompress_recoverable_without_point_count() {
+    let (points, data, vlr) = compress_first_points("tests/data/point-time.las", 5);
+    let mut decompressor = laz::ParLasZipDecompressor::new(data, vlr).unwrap();
+    let mut out = Vec::<u8>::new();
+    let report = decompressor.decompress_recoverable(5, &mut out).unwrap();
+    assert!(report.is_complete());
+    assert_eq!(report.points_recovered, 5);
+    assert_eq!(report.last_good_chunk, Some(0));
+    assert_eq!(out, points);
+}
+
+#[test]
+fn test_open_for_append() {
+    const POINT_SIZE: usize = 20;
+    let mut las_file = File::open("tests/data/point10.las").unwrap();
+    let (las_header, _) = laz::las::file::read_header_and_vlrs(&mut las_file).unwrap();
+    las_file
+        .seek(SeekFrom::Start(las_header.offset_to_points as u64))
+        .unwrap();
+    let mut points = vec![0u8; las_header.num_points as usize * POINT_SIZE];
+    las_file.read_exact(&mut points).unwrap();
+    let num_points = las_header.num_points as usize;
+
+    let vlr = LazVlrBuilder::new(
+        LazItemRecordBuilder::new()
+            .add_item(LazItemType::Point10)
+            .build(),
+    )
+    .with_fixed_chunk_size(50)
+    .build();
+
+    let mut expected_data = Cursor::new(Vec::<u8>::new());
+    compress_buffer(&mut expected_data, &points, vlr.clone()).unwrap();
+
+    // Pretend there is a 10 bytes header before the points
+    let mut data = Cursor::new(vec![42u8; 10]);
+    data.seek(SeekFrom::End(0)).unwrap();
+    compress_buffer(&mut data, &points[..130 * POINT_SIZE], vlr.clone()).unwrap();
+    // Last chunk is not full, and then the first appended chunk is not full
+    for (point_count, appended_points) in [
+        (130, &points[130 * POINT_SIZE..140 * POINT_SIZE]),
+        (140, &points[140 * POINT_SIZE..]),
+    ] {
+        data.seek(SeekFrom::Start(10)).unwrap();
+        let mut compressor =
+            LasZipCompressor::open_for_append(&mut data, vlr.clone(), point_count).unwrap();
+        compressor.compress_many(appended_points).unwrap();
+        compressor.done().unwrap();
+    }
+    // Appending no points does not change the data
+    data.seek(SeekFrom::Start(10)).unwrap();
+    {
+        let mut compressor =
+            LasZipCompressor::open_for_append(&mut data, vlr.clone(), num_points as u64).unwrap();
+        compressor.done().unwrap();
+    }
+
+    // Same compressed chunks & chunk table as if all the points were compressed at once
+    let expected_data = expected_data.into_inner();
+    assert_eq!(&data.get_ref()[..10], &[42u8; 10]);
+    assert_eq!(data.get_ref().len(), expected_data.len() + 10);
+    assert_eq!(&data.get_ref()[18..], &expected_data[8..]);
+
+    data.seek(SeekFrom::Start(10)).unwrap();
+    let mut decompressor = LasZipDecompressor::new(&mut data, vlr).unwrap();
+    let mut decompressed_points = vec![0u8; num_points * POINT_SIZE];
+    decompressor
+        .decompress_many(&mut decompressed_points)
+        .unwrap();
+    assert_eq!(decompressed_points, points);
+}
+
+/// Points of the same value compress so well that decompressing a point-wise chunk
+/// past its last point does not fail, appending must not rely on that.
+#[test]
+fn test_open_for_append_after_partial_point_wise_chunk() {
+    for (laz_items, point_size) in [
+        (
+            LazItemRecordBuilder::default_version_of::<laz::las::Point0>(0),
+            20,
+        ),
+        (
+            LazItemRecordBuilder::default_version_of::<laz::las::Point3>(0),
+            34,
+        ),
+    ] {
+        let vlr = LazVlrBuilder::new(laz_items)
+            .with_fixed_chunk_size(1000)
+            .build();
+        let points = vec![7u8; 1500 * point_size];
+        // Same points, but with a different X
+        let mut appended_points = vec![7u8; 10 * point_size];
+        for (i, point) in appended_points.chunks_exact_mut(point_size).enumerate() {
+            point[..4].copy_from_slice(&(i as i32).to_le_bytes());
+        }
+
+        let mut data = Cursor::new(Vec::<u8>::new());
+        compress_buffer(&mut data, &points, vlr.clone()).unwrap();
+        data.set_position(0);
+        {
+            let mut compressor =
+                LasZipCompressor::open_for_append(&mut data, vlr.clone(), 1500).unwrap();
+            compressor.compress_many(&appended_points).unwrap();
+            compressor.done().unwrap();
+        }
+
+        data.set_position(0);
+        let mut decompressor = LasZipDecompressor::new(&mut data, vlr).unwrap();
+        let mut decompressed_points = vec![0u8; 1510 * point_size];
+        decompressor
+            .decompress_many(&mut decompressed_points)
+            .unwrap();
+        assert_eq!(&decompressed_points[..1500 * point_size], points.as_slice());
+        assert_eq!(
+            &decompressed_points[1500 * point_size..],
+            appended_points.as_slice()
+        );
+    }
+}
+
+#[test]
+fn test_merge() {
+    const POINT_SIZE: usize = 20;
+    let mut las_file = File::open("tests/data/point10.las").unwrap();
+    let (las_header, _) = laz::las::file::read_header_and_vlrs(&mut las_file).unwrap();
+    las_file
+        .seek(SeekFrom::Start(las_header.offset_to_points as u64))
+        .unwrap();
+    let mut points = vec![0u8; las_header.num_points as usize * POINT_SIZE];
+    las_file.read_exact(&mut points).unwrap();
+
+    let items = LazItemRecordBuilder::new()
+        .add_item(LazItemType::Point10)
+        .build();
+    let vlr = LazVlrBuilder::new(items.clone())
+        .with_fixed_chunk_size(50)
+        .build();
+    let compress = |points: &[u8], vlr: &LazVlr| {
+        let mut data = Cursor::new(Vec::<u8>::new());
+        compress_buffer(&mut data, points, vlr.clone()).unwrap();
+        data.set_position(0);
+        data
+    };
+    let merge_and_decompress = |splits: &[usize]| {
+        let mut sources = vec![];
+        let mut start = 0;
+        for end in splits
+            .iter()
+            .copied()
+            .chain(Some(points.len() / POINT_SIZE))
+        {
+            let source_points = &points[start * POINT_SIZE..end * POINT_SIZE];
+            sources.push((
+                compress(source_points, &vlr),
+                vlr.clone(),
+                (end - start) as u64,
+            ));
+            start = end;
+        }
+        let mut merged = Cursor::new(Vec::<u8>::new());
+        let merged_vlr = laz::merge(sources, &mut merged).unwrap();
+        let merged = merged.into_inner();
+
+        {
+            let mut decompressor =
+                LasZipDecompressor::new(Cursor::new(&merged), merged_vlr.clone()).unwrap();
+            let mut decompressed_points = vec![0u8; points.len()];
+            decompressor
+                .decompress_many(&mut decompressed_points)
+                .unwrap();
+            assert_eq!(decompressed_points, points);
+        }
+        (merged_vlr, merged)
+    };
+
+    // All the chunks before the last one are complete, fixed-size chunks are kept
+    // and the data is the same as if all the points were compressed at once
+    let (merged_vlr, merged_data) = merge_and_decompress(&[100, 150]);
+    assert_eq!(merged_vlr, vlr);
+    assert_eq!(merged_data, compress(&points, &vlr).into_inner());
+
+    // Incomplete chunks in the middle require variable-size chunks
+    let (merged_vlr, merged_data) = merge_and_decompress(&[30, 170]);
+    assert!(merged_vlr.uses_variable_size_chunks());
+    let chunk_table = ChunkTable::read_from(Cursor::new(merged_data), &merged_vlr).unwrap();
+    let point_counts = chunk_table
+        .as_ref()
+        .iter()
+        .map(|entry| entry.point_count)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        point_counts,
+        vec![
+            30, 50, 50, 40, 50, 50, 50, 50, 50, 50, 50, 50, 50, 50, 50, 50, 50, 50, 50, 50, 50, 45
+        ]
+    );
+
+    // The partial last chunks of compressible points are not overcounted
+    let big_chunks_vlr = LazVlrBuilder::new(items.clone())
+        .with_fixed_chunk_size(1000)
+        .build();
+    let same_points = vec![7u8; 1500 * POINT_SIZE];
+    let sources = vec![
+        (
+            compress(&same_points, &big_chunks_vlr),
+            big_chunks_vlr.clone(),
+            1500,
+        ),
+        (
+            compress(&same_points, &big_chunks_vlr),
+            big_chunks_vlr.clone(),
+            1500,
+        ),
+    ];
+    let mut merged = Cursor::new(Vec::<u8>::new());
+    let merged_vlr = laz::merge(sources, &mut merged).unwrap();
+    merged.set_position(0);
+    let chunk_table = ChunkTable::read_from(&mut merged, &merged_vlr).unwrap();
+    let point_counts = chunk_table
+        .as_ref()
+        .iter()
+        .map(|entry| entry.point_count)
+        .collect::<Vec<_>>();
+    assert_eq!(point_counts, vec![1000, 500, 1000, 500]);
+
+    // Items must be the same
+    let other_vlr = LazVlrBuilder::new(
+        LazItemRecordBuilder::new()
+            .add_item(LazItemType::Point10)
+            .add_item(LazItemType::GpsTime)
+            .build(),
+    )
+    .build();
+    let other_points = vec![0u8; 28];
+    let sources = vec![
+        (
+            compress(&points, &vlr),
+            vlr.clone(),
+            (points.len() / POINT_SIZE) as u64,
+        ),
+        (compress(&other_points, &other_vlr), other_vlr, 1),
+    ];
+    assert!(matches!(
+        laz::merge(sources, Cursor::new(Vec::<u8>::new())),
+        Err(LasZipError::IncompatibleLazVlr { index: 1 })
+    ));
+}
+
+#[test]
+fn test_extract_chunks() {
+    const POINT_SIZE: usize = 20;
+    let mut las_file = File::open("tests/data/point10.las").unwrap();
+    let (las_header, _) = laz::las::file::read_header_and_vlrs(&mut las_file).unwrap();
+    las_file
+        .seek(SeekFrom::Start(las_header.offset_to_points as u64))
+        .unwrap();
+    let mut points = vec![0u8; las_header.num_points as usize * POINT_SIZE];
+    las_file.read_exact(&mut points).unwrap();
+    let num_points = las_header.num_points as usize;
+
+    let vlr = LazVlrBuilder::new(
+        LazItemRecordBuilder::new()
+            .add_item(LazItemType::Point10)
+            .build(),
+    )
+    .with_fixed_chunk_size(50)
+    .build();
+    let mut data = Cursor::new(Vec::<u8>::new());
+    compress_buffer(&mut data, &points, vlr.clone()).unwrap();
+    let last_chunk = num_points / 50;
+
+    let chunk_points = |chunk_idx: usize| {
+        let end = ((chunk_idx + 1) * 50).min(num_points);
+        &points[chunk_idx * 50 * POINT_SIZE..end * POINT_SIZE]
+    };
+    let extract_and_decompress = |chunk_indices: &[usize]| {
+        let mut extracted = Cursor::new(Vec::<u8>::new());
+        let (extracted_vlr, point_count) = laz::extract_chunks(
+            Cursor::new(data.get_ref()),
+            &vlr,
+            num_points as u64,
+            chunk_indices,
+            &mut extracted,
+        )
+        .unwrap();
+        let expected_points = chunk_indices
+            .iter()
+            .flat_map(|&chunk_idx| chunk_points(chunk_idx).iter().copied())
+            .collect::<Vec<u8>>();
+        assert_eq!(point_count as usize * POINT_SIZE, expected_points.len());
+
+        extracted.set_position(0);
+        let mut decompressor = LasZipDecompressor::new(extracted, extracted_vlr.clone()).unwrap();
+        let mut decompressed_points = vec![0u8; expected_points.len()];
+        decompressor
+            .decompress_many(&mut decompressed_points)
+            .unwrap();
+        assert_eq!(decompressed_points, expected_points);
+        extracted_vlr
+    };
+
+    // Contiguous chunks, including the incomplete last one
+    assert_eq!(extract_and_decompress(&[3, 4, 5]), vlr);
+    assert_eq!(extract_and_decompress(&[19, 20, last_chunk]), vlr);
+    // Arbitrary order, the incomplete last chunk is not the last one extracted
+    assert_eq!(extract_and_decompress(&[7, 2, 11]), vlr);
+    assert!(extract_and_decompress(&[last_chunk, 0]).uses_variable_size_chunks());
+
+    assert!(matches!(
+        laz::extract_chunks(
+            Cursor::new(data.get_ref()),
+            &vlr,
+            num_points as u64,
+            &[0, last_chunk + 1],
+            Cursor::new(Vec::<u8>::new()),
+        ),
+        Err(LasZipError::ChunkIndexOutOfBounds { requested, total })
+            if requested == last_chunk + 1 && total == last_chunk + 1
+    ));
+
+    // The partial last chunk of compressible points is not overcounted
+    for (laz_items, point_size) in [
+        (
+            LazItemRecordBuilder::default_version_of::<laz::las::Point0>(0),
+            20,
+        ),
+        (
+            LazItemRecordBuilder::default_version_of::<laz::las::Point3>(0),
+            34,
+        ),
+    ] {
+        let vlr = LazVlrBuilder::new(laz_items)
+            .with_fixed_chunk_size(1000)
+            .build();
+        let mut data = Cursor::new(Vec::<u8>::new());
+        compress_buffer(&mut data, &vec![7u8; 1500 * point_size], vlr.clone()).unwrap();
+        data.set_position(0);
+        let mut extracted = Cursor::new(Vec::<u8>::new());
+        let (extracted_vlr, point_count) =
+            laz::extract_chunks(&mut data, &vlr, 1500, &[1, 0], &mut extracted).unwrap();
+        assert_eq!(point_count, 1500);
+        assert!(extracted_vlr.uses_variable_size_chunks());
+        extracted.set_position(0);
+        let chunk_table = ChunkTable::read_from(&mut extracted, &extracted_vlr).unwrap();
+        assert_eq!(chunk_table[0].point_count, 500);
+    }
+}
+
+#[test]
+fn test_rechunk() {
+    const POINT_SIZE: usize = 20;
+    let mut las_file = File::open("tests/data/point10.las").unwrap();
+    let (las_header, _) = laz::las::file::read_header_and_vlrs(&mut las_file).unwrap();
+    las_file
+        .seek(SeekFrom::Start(las_header.offset_to_points as u64))
+        .unwrap();
+    let mut points = vec![0u8; las_header.num_points as usize * POINT_SIZE];
+    las_file.read_exact(&mut points).unwrap();
+
+    let items = LazItemRecordBuilder::new()
+        .add_item(LazItemType::Point10)
+        .build();
+    let small_chunks_vlr = LazVlrBuilder::new(items.clone())
+        .with_fixed_chunk_size(50)
+        .build();
+    let big_chunks_vlr = LazVlrBuilder::new(items.clone())
+        .with_fixed_chunk_size(200)
+        .build();
+    let variable_chunks_vlr = LazVlrBuilder::new(items.clone())
+        .with_variable_chunk_size()
+        .build();
+    let compress = |vlr: &LazVlr| {
+        let mut data = Cursor::new(Vec::<u8>::new());
+        compress_buffer(&mut data, &points, vlr.clone()).unwrap();
+        data.into_inner()
+    };
+    let small_chunks_data = compress(&small_chunks_vlr);
+    let big_chunks_data = compress(&big_chunks_vlr);
+    let num_points = (points.len() / POINT_SIZE) as u64;
+
+    let check_variable_chunks = |data: Vec<u8>| {
+        let chunk_table = ChunkTable::read_from(Cursor::new(&data), &variable_chunks_vlr).unwrap();
+        assert_eq!(chunk_table.len(), points.len() / POINT_SIZE / 50 + 1);
+        let mut decompressed_points = vec![0u8; points.len()];
+        laz::decompress_buffer(&data, &mut decompressed_points, variable_chunks_vlr.clone())
+            .unwrap();
+        assert_eq!(decompressed_points, points);
+    };
+
+    {
+        let mut rechunked = Cursor::new(Vec::<u8>::new());
+        laz::rechunk(
+            Cursor::new(&small_chunks_data),
+            small_chunks_vlr.clone(),
+            num_points,
+            &mut rechunked,
+            big_chunks_vlr.clone(),
+        )
+        .unwrap();
+        assert_eq!(rechunked.into_inner(), big_chunks_data);
+
+        let mut rechunked = Cursor::new(Vec::<u8>::new());
+        laz::rechunk(
+            Cursor::new(&small_chunks_data),
+            small_chunks_vlr.clone(),
+            num_points,
+            &mut rechunked,
+            variable_chunks_vlr.clone(),
+        )
+        .unwrap();
+        check_variable_chunks(rechunked.into_inner());
+    }
+
+    #[cfg(feature = "parallel")]
+    {
+        let mut rechunked = Cursor::new(Vec::<u8>::new());
+        laz::par_rechunk(
+            Cursor::new(&small_chunks_data),
+            small_chunks_vlr.clone(),
+            num_points,
+            &mut rechunked,
+            big_chunks_vlr.clone(),
+        )
+        .unwrap();
+        assert_eq!(rechunked.into_inner(), big_chunks_data);
+
+        let mut rechunked = Cursor::new(Vec::<u8>::new());
+        laz::par_rechunk(
+            Cursor::new(&small_chunks_data),
+            small_chunks_vlr.clone(),
+            num_points,
+            &mut rechunked,
+            variable_chunks_vlr.clone(),
+        )
+        .unwrap();
+        check_variable_chunks(rechunked.into_inner());
+    }
+
+    // The partial last chunk of compressible points is not overcounted
+    let big_chunks_vlr = LazVlrBuilder::new(items.clone())
+        .with_fixed_chunk_size(1000)
+        .build();
+    let mut same_points_data = Cursor::new(Vec::<u8>::new());
+    compress_buffer(
+        &mut same_points_data,
+        &vec![7u8; 1500 * POINT_SIZE],
+        big_chunks_vlr.clone(),
+    )
+    .unwrap();
+    let same_points_data = same_points_data.into_inner();
+    let check_same_points_chunks = |data: Vec<u8>| {
+        let chunk_table = ChunkTable::read_from(Cursor::new(&data), &variable_chunks_vlr).unwrap();
+        let point_counts = chunk_table
+            .as_ref()
+            .iter()
+            .map(|entry| entry.point_count)
+            .collect::<Vec<_>>();
+        assert_eq!(point_counts, vec![1000, 500]);
+    };
+    let mut rechunked = Cursor::new(Vec::<u8>::new());
+    laz::rechunk(
+        Cursor::new(&same_points_data),
+        big_chunks_vlr.clone(),
+        1500,
+        &mut rechunked,
+        variable_chunks_vlr.clone(),
+    )
+    .unwrap();
+    check_same_points_chunks(rechunked.into_inner());
+    #[cfg(feature = "parallel")]
+    {
+        let mut rechunked = Cursor::new(Vec::<u8>::new());
+        laz::par_rechunk(
+            Cursor::new(&same_points_data),
+            big_chunks_vlr,
+            1500,
+            &mut rechunked,
+            variable_chunks_vlr.clone(),
+        )
+        .unwrap();
+        check_same_points_chunks(rechunked.into_inner());
+    }
+
+    let other_vlr = LazVlrBuilder::new(
+        LazItemRecordBuilder::new()
+            .add_item(LazItemType::Point10)
+            .add_item(LazItemType::GpsTime)
+            .build(),
+    )
+    .build();
+    assert!(laz::rechunk(
+        Cursor::new(&small_chunks_data),
+        small_chunks_vlr,
+        num_points,
+        Cursor::new(Vec::<u8>::new()),
+        other_vlr,
+    )
+    .is_err());
 }
@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use laz::lax::{
+    chunks_of_intervals, decompress_intervals, sort_points_by_cell, LaxIndex, LaxIndexBuilder,
+    LaxIndexingCompressor,
+};
+use laz::{compress_buffer, LasZipCompressor, LasZipDecompressor, LazVlr, LazVlrBuilder};
+
+const LEVELS: u32 = 3;
+
+/// Reads the scales & offsets of the las file.
+fn read_transforms(path: &str) -> ([f64; 3], [f64; 3]) {
+    let mut las_file = File::open(path).unwrap();
+    // they are at the same position in all the header versions
+    las_file.seek(SeekFrom::Start(131)).unwrap();
+    let mut transforms = [0f64; 6];
+    las_file
+        .read_f64_into::<LittleEndian>(&mut transforms)
+        .unwrap();
+    let [sx, sy, sz, ox, oy, oz] = transforms;
+    ([sx, sy, sz], [ox, oy, oz])
+}
+
+/// Reads the points of the las file & returns them with their x, y coordinates.
+fn read_points(path: &str) -> (Vec<u8>, usize, Vec<[f64; 2]>) {
+    let mut las_file = File::open(path).unwrap();
+    let (header, _) = laz::las::file::read_header_and_vlrs(&mut las_file).unwrap();
+    let point_size = header.point_size as usize;
+    let mut points = vec![0u8; header.num_points as usize * point_size];
+    las_file.read_exact(&mut points).unwrap();
+
+    let (scales, offsets) = read_transforms(path);
+    let coords = points
+        .chunks_exact(point_size)
+        .map(|point| {
+            let x = i32::from_le_bytes([point[0], point[1], point[2], point[3]]);
+            let y = i32::from_le_bytes([point[4], point[5], point[6], point[7]]);
+            [
+                f64::from(x) * scales[0] + offsets[0],
+                f64::from(y) * scales[1] + offsets[1],
+            ]
+        })
+        .collect();
+    (points, point_size, coords)
+}
+
+/// Index of the cell of the deepest level containing the point.
+fn cell_index(coords: [f64; 2], bounds: [f32; 4]) -> i32 {
+    let [mut min_x, mut max_x, mut min_y, mut max_y] = bounds.map(f64::from);
+    let mut level_index = 0;
+    for _ in 0..LEVELS {
+        level_index <<= 2;
+        let mid_x = (min_x + max_x) / 2.0;
+        let mid_y = (min_y + max_y) / 2.0;
+        if coords[0] < mid_x {
+            max_x = mid_x;
+        } else {
+            min_x = mid_x;
+            level_index |= 1;
+        }
+        if coords[1] < mid_y {
+            max_y = mid_y;
+        } else {
+            min_y = mid_y;
+            level_index |= 2;
+        }
+    }
+    let level_offset = (0..LEVELS).map(|level| 4i32.pow(level)).sum::<i32>();
+    level_offset + level_index
+}
+
+/// Creates the .lax file the way lasindex does, without merging cells.
+fn create_lax_file(coords: &[[f64; 2]]) -> Vec<u8> {
+    // like lasindex, the extent is rounded to integers so that it is exact in f32
+    let min_x = coords.iter().map(|c| c[0]).fold(f64::MAX, f64::min).floor() as f32;
+    let max_x = coords.iter().map(|c| c[0]).fold(f64::MIN, f64::max).ceil() as f32 + 1.0;
+    let min_y = coords.iter().map(|c| c[1]).fold(f64::MAX, f64::min).floor() as f32;
+    let max_y = coords.iter().map(|c| c[1]).fold(f64::MIN, f64::max).ceil() as f32 + 1.0;
+    let bounds = [min_x, max_x, min_y, max_y];
+
+    // inclusive intervals, as in the file
+    let mut cells = BTreeMap::<i32, Vec<(u32, u32)>>::new();
+    for (i, c) in coords.iter().enumerate() {
+        let intervals = cells.entry(cell_index(*c, bounds)).or_default();
+        match intervals.last_mut() {
+            Some(last) if last.1 + 1 == i as u32 => last.1 = i as u32,
+            _ => intervals.push((i as u32, i as u32)),
+        }
+    }
+
+    let mut lax = Cursor::new(Vec::<u8>::new());
+    lax.write_all(b"LASX").unwrap();
+    lax.write_u32::<LittleEndian>(0).unwrap();
+    lax.write_all(b"LASS").unwrap();
+    lax.write_u32::<LittleEndian>(0).unwrap();
+    lax.write_all(b"LASQ").unwrap();
+    lax.write_u32::<LittleEndian>(0).unwrap();
+    lax.write_u32::<LittleEndian>(LEVELS).unwrap();
+    lax.write_u32::<LittleEndian>(0).unwrap();
+    lax.write_u32::<LittleEndian>(0).unwrap();
+    for value in bounds.iter() {
+        lax.write_f32::<LittleEndian>(*value).unwrap();
+    }
+    lax.write_all(b"LASV").unwrap();
+    lax.write_u32::<LittleEndian>(0).unwrap();
+    lax.write_u32::<LittleEndian>(cells.len() as u32).unwrap();
+    for (index, intervals) in cells {
+        let point_count: u32 = intervals.iter().map(|(start, end)| end - start + 1).sum();
+        lax.write_i32::<LittleEndian>(index).unwrap();
+        lax.write_u32::<LittleEndian>(intervals.len() as u32)
+            .unwrap();
+        lax.write_u32::<LittleEndian>(point_count).unwrap();
+        for (start, end) in intervals {
+            lax.write_u32::<LittleEndian>(start).unwrap();
+            lax.write_u32::<LittleEndian>(end).unwrap();
+        }
+    }
+    lax.into_inner()
+}
+
+fn compressed_point10(points: &[u8]) -> (Cursor<Vec<u8>>, LazVlr) {
+    let mut laz_file = File::open("tests/data/point10.laz").unwrap();
+    let (_, laz_vlr) = laz::las::file::read_header_and_vlrs(&mut laz_file).unwrap();
+    let vlr = LazVlrBuilder::new(laz_vlr.unwrap().items().clone())
+        .with_fixed_chunk_size(50)
+        .build();
+    let mut compressed = Cursor::new(Vec::<u8>::new());
+    compress_buffer(&mut compressed, points, vlr.clone()).unwrap();
+    compressed.set_position(0);
+    (compressed, vlr)
+}
+
+#[test]
+fn test_lax_index_query() {
+    let (points, point_size, coords) = read_points("tests/data/point10.las");
+    let index = LaxIndex::read_from(Cursor::new(create_lax_file(&coords))).unwrap();
+    assert_eq!(index.quadtree.levels, LEVELS);
+    let num_points_in_cells: u32 = index.cells.iter().map(|cell| cell.point_count).sum();
+    assert_eq!(num_points_in_cells as usize, coords.len());
+    for cell in &index.cells {
+        let b = index.quadtree.cell_bounds(cell.index).unwrap();
+        for interval in &cell.intervals {
+            for c in &coords[interval.start as usize..interval.end as usize] {
+                assert!(b[0] <= c[0] && c[0] <= b[2] && b[1] <= c[1] && c[1] <= b[3]);
+            }
+        }
+    }
+
+    let q = index.quadtree;
+    let (min_x, min_y) = (f64::from(q.min_x), f64::from(q.min_y));
+    let mid_x = (f64::from(q.min_x) + f64::from(q.max_x)) / 2.0;
+    let mid_y = (f64::from(q.min_y) + f64::from(q.max_y)) / 2.0;
+    let rect = [min_x, min_y, mid_x - 1.0, mid_y - 1.0];
+    let intervals = index.query(rect[0], rect[1], rect[2], rect[3]);
+    assert!(intervals.windows(2).all(|w| w[0].end < w[1].start));
+
+    let in_intervals = |i: u64| intervals.iter().any(|interval| interval.contains(&i));
+    for (i, c) in coords.iter().enumerate() {
+        if rect[0] <= c[0] && c[0] <= rect[2] && rect[1] <= c[1] && c[1] <= rect[3] {
+            assert!(in_intervals(i as u64));
+        }
+    }
+    let expected = (0..coords.len() as u64)
+        .filter(|i| in_intervals(*i))
+        .flat_map(|i| points[i as usize * point_size..][..point_size].to_vec())
+        .collect::<Vec<u8>>();
+    assert!(!expected.is_empty());
+    assert!(expected.len() < points.len());
+
+    let (compressed, vlr) = compressed_point10(&points);
+    let mut decompressor = LasZipDecompressor::new(compressed, vlr).unwrap();
+    let chunk_table = decompressor.chunk_table().unwrap().clone();
+    let chunks = chunks_of_intervals(&chunk_table, &intervals).unwrap();
+    assert!(chunks.len() < chunk_table.len());
+
+    let mut decompressed = Vec::new();
+    decompress_intervals(&mut decompressor, &intervals, &mut decompressed).unwrap();
+    assert_eq!(decompressed, expected);
+}
+
+#[test]
+fn test_lax_out_of_bounds_intervals() {
+    let (points, _, _) = read_points("tests/data/point10.las");
+    let (compressed, vlr) = compressed_point10(&points);
+    let num_points = points.len() as u64 / u64::from(vlr.items_size());
+    let mut decompressor = LasZipDecompressor::new(compressed, vlr).unwrap();
+    decompressor.set_point_count(num_points).unwrap();
+    let intervals: [Range<u64>; 1] = [num_points - 1..num_points + 1];
+    let result = decompress_intervals(&mut decompressor, &intervals, &mut Vec::new());
+    assert!(matches!(
+        result,
+        Err(laz::LasZipError::PointIndexOutOfBounds { .. })
+    ));
+}
+
+#[test]
+fn test_lax_invalid_signature() {
+    let result = LaxIndex::read_from(Cursor::new(b"LASF\0\0\0\0".to_vec()));
+    assert!(result.is_err());
+}
+
+fn builder_for(coords: &[[f64; 2]]) -> LaxIndexBuilder {
+    let min_x = coords.iter().map(|c| c[0]).fold(f64::MAX, f64::min);
+    let max_x = coords.iter().map(|c| c[0]).fold(f64::MIN, f64::max);
+    let min_y = coords.iter().map(|c| c[1]).fold(f64::MAX, f64::min);
+    let max_y = coords.iter().map(|c| c[1]).fold(f64::MIN, f64::max);
+    LaxIndexBuilder::new(min_x, min_y, max_x, max_y)
+}
+
+#[test]
+fn test_lax_index_builder() {
+    let path = "tests/data/point10.las";
+    let (points, point_size, coords) = read_points(path);
+    let (scales, offsets) = read_transforms(path);
+    let mut builder = builder_for(&coords);
+    builder.add_points(&points, point_size, scales, offsets);
+    let index = builder.build();
+    assert!(index.cells.len() > 1);
+    let num_points_in_cells: u32 = index.cells.iter().map(|cell| cell.point_count).sum();
+    assert_eq!(num_points_in_cells as usize, coords.len());
+
+    let mut lax = Cursor::new(Vec::<u8>::new());
+    index.write_to(&mut lax).unwrap();
+    lax.set_position(0);
+    assert_eq!(LaxIndex::read_from(&mut lax).unwrap(), index);
+
+    let q = index.quadtree;
+    let mid_x = (f64::from(q.min_x) + f64::from(q.max_x)) / 2.0;
+    let mid_y = (f64::from(q.min_y) + f64::from(q.max_y)) / 2.0;
+    let intervals = index.query(mid_x - 50.0, mid_y - 50.0, mid_x + 50.0, mid_y + 50.0);
+    for (i, c) in coords.iter().enumerate() {
+        if (c[0] - mid_x).abs() <= 50.0 && (c[1] - mid_y).abs() <= 50.0 {
+            assert!(intervals
+                .iter()
+                .any(|interval| interval.contains(&(i as u64))));
+        }
+    }
+
+    let mut laz_file = File::open("tests/data/point10.laz").unwrap();
+    let (header, laz_vlr) = laz::las::file::read_header_and_vlrs(&mut laz_file).unwrap();
+    let mut decompressor = LasZipDecompressor::new(&mut laz_file, laz_vlr.unwrap()).unwrap();
+    let mut builder = builder_for(&coords);
+    builder
+        .add_decompressed(&mut decompressor, header.num_points, scales, offsets)
+        .unwrap();
+    assert_eq!(builder.build(), index);
+}
+
+#[test]
+#[should_panic]
+fn test_lax_index_builder_negative_cell_size() {
+    LaxIndexBuilder::with_cell_size(0.0, 0.0, 100.0, 100.0, -10.0);
+}
+
+#[test]
+#[should_panic]
+fn test_lax_index_builder_infinite_extent() {
+    LaxIndexBuilder::with_cell_size(0.0, 0.0, f64::INFINITY, 100.0, 10.0);
+}
+
+#[test]
+fn test_lax_indexing_compressor() {
+    let path = "tests/data/point10.las";
+    let (mut points, point_size, coords) = read_points(path);
+    let (scales, offsets) = read_transforms(path);
+    let (_, vlr) = compressed_point10(&[]);
+
+    let builder = builder_for(&coords);
+    let quadtree = *builder.quadtree();
+    let mut expected_builder = builder.clone();
+    expected_builder.add_points(&points, point_size, scales, offsets);
+    let unsorted_index = expected_builder.build();
+
+    sort_points_by_cell(&mut points, point_size, &quadtree, scales, offsets);
+    let compressor = LasZipCompressor::new(Cursor::new(Vec::<u8>::new()), vlr.clone()).unwrap();
+    let mut compressor = LaxIndexingCompressor::new(compressor, builder, scales, offsets);
+    let (first, others) = points.split_at(point_size);
+    compressor.compress_one(first).unwrap();
+    compressor.compress_many(others).unwrap();
+    compressor.done().unwrap();
+    let index = compressor.index();
+
+    // Once sorted, the points of a cell are contiguous
+    assert_eq!(index.cells.len(), unsorted_index.cells.len());
+    for (cell, unsorted_cell) in index.cells.iter().zip(&unsorted_index.cells) {
+        assert_eq!(cell.index, unsorted_cell.index);
+        assert_eq!(cell.point_count, unsorted_cell.point_count);
+        assert_eq!(cell.intervals.len(), 1);
+    }
+
+    let mut compressed = Cursor::new(compressor.into_inner().into_inner());
+    compressed.set_position(0);
+    let mut decompressor = LasZipDecompressor::new(compressed, vlr).unwrap();
+    let mut decompressed = vec![0u8; points.len()];
+    decompressor.decompress_many(&mut decompressed).unwrap();
+    assert_eq!(decompressed, points);
+}
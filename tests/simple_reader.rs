@@ -0,0 +1,97 @@
+use std::io::Cursor;
+
+use laz::las::file::{LasHeader, SimpleReader};
+use laz::LasZipError;
+
+/// Offsets of fields of the header
+const NUM_VLRS: usize = 100;
+const POINT_FORMAT_ID: usize = 104;
+const POINT_SIZE: usize = 105;
+
+fn read_file(path: &str) -> Vec<u8> {
+    std::fs::read(path).unwrap()
+}
+
+#[test]
+fn test_simple_reader_compressed_without_laszip_vlr() {
+    let mut data = read_file("tests/data/point10.laz");
+    data[NUM_VLRS..NUM_VLRS + 4].copy_from_slice(&0u32.to_le_bytes());
+    assert!(matches!(
+        SimpleReader::new(Cursor::new(data)),
+        Err(LasZipError::CompressedWithoutLasZipVlr {
+            point_format_id: 0x80
+        })
+    ));
+}
+
+#[test]
+fn test_simple_reader_invalid_point_size() {
+    let mut data = read_file("tests/data/point10.las");
+    data[POINT_SIZE..POINT_SIZE + 2].copy_from_slice(&19u16.to_le_bytes());
+    assert!(matches!(
+        SimpleReader::new(Cursor::new(data)),
+        Err(LasZipError::PointSizeTooSmall {
+            point_format_id: 0,
+            point_size: 19,
+            minimum: 20
+        })
+    ));
+
+    // The point size must also match the items of the LasZip VLR
+    let mut data = read_file("tests/data/point10.laz");
+    data[POINT_SIZE..POINT_SIZE + 2].copy_from_slice(&21u16.to_le_bytes());
+    assert!(matches!(
+        SimpleReader::new(Cursor::new(data)),
+        Err(LasZipError::IoError(_))
+    ));
+}
+
+#[test]
+fn test_simple_reader_unknown_point_format() {
+    let mut data = read_file("tests/data/point10.las");
+    data[POINT_FORMAT_ID] = 11;
+    assert!(matches!(
+        SimpleReader::new(Cursor::new(data)),
+        Err(LasZipError::UnsupportedPointFormat(11))
+    ));
+
+    let header = LasHeader {
+        point_format_id: 11,
+        ..Default::default()
+    };
+    assert!(header.num_extra_bytes().is_err());
+}
+
+#[test]
+fn test_simple_reader_truncated_vlrs() {
+    let mut data = read_file("tests/data/point10.laz");
+    data.truncate(227 + 60);
+    assert!(matches!(
+        SimpleReader::new(Cursor::new(data)),
+        Err(LasZipError::IoError(_))
+    ));
+}
+
+#[test]
+fn test_simple_reader_corrupted_laszip_vlr() {
+    let mut data = read_file("tests/data/point10.laz");
+    // Type of the first item of the LasZip VLR
+    let first_item_type = 227 + 54 + 34;
+    data[first_item_type..first_item_type + 2].copy_from_slice(&999u16.to_le_bytes());
+    assert!(SimpleReader::new(Cursor::new(data)).is_err());
+}
+
+#[test]
+fn test_simple_reader_read_to_end_appends() {
+    let data = read_file("tests/data/point10.laz");
+    let mut reader = SimpleReader::new(Cursor::new(data)).unwrap();
+    let point_size = usize::from(reader.header.point_size);
+    let first_point = reader.read_next().unwrap().unwrap().to_vec();
+
+    let mut points = first_point.clone();
+    let num_read = reader.read_to_end(&mut points).unwrap();
+    assert_eq!(num_read as u64, reader.header.num_points - 1);
+    assert_eq!(points.len(), reader.header.num_points as usize * point_size);
+    assert_eq!(points[..point_size], first_point[..]);
+    assert!(reader.read_next().is_none());
+}
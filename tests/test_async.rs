@@ -0,0 +1,133 @@
+#[cfg(feature = "async")]
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::{BufReader, Cursor};
+
+    use laz::las::file::SimpleReader;
+    use laz::{
+        AsyncLasZipCompressor, AsyncLasZipDecompressor, LasZipCompressor, LasZipDecompressor,
+        LasZipError, LazItemRecordBuilder, LazVlr, LazVlrBuilder,
+    };
+
+    fn read_las_points(path: &str) -> (Vec<u8>, u8) {
+        let las_file = BufReader::new(File::open(path).unwrap());
+        let mut las_reader = SimpleReader::new(las_file).unwrap();
+        let mut points = Vec::<u8>::new();
+        las_reader.read_to_end(&mut points).unwrap();
+        (points, las_reader.header.point_format_id)
+    }
+
+    fn vlr_for_point_format(point_format_id: u8, chunk_size: Option<u32>) -> LazVlr {
+        let builder = LazVlrBuilder::new(
+            LazItemRecordBuilder::default_for_point_format_id(point_format_id, 0).unwrap(),
+        );
+        match chunk_size {
+            Some(chunk_size) => builder.with_fixed_chunk_size(chunk_size),
+            None => builder.with_variable_chunk_size(),
+        }
+        .build()
+    }
+
+    /// The async compressor must produce the same bytes as the sync one
+    /// and the async decompressor must give back the points.
+    #[tokio::test]
+    async fn test_async_fixed_size_chunks() {
+        let (points, point_format_id) = read_las_points("tests/data/point-time-color.las");
+        let vlr = vlr_for_point_format(point_format_id, Some(7));
+        let point_size = vlr.items_size() as usize;
+        let num_points = points.len() / point_size;
+
+        let mut expected_output = Cursor::new(Vec::<u8>::new());
+        {
+            let mut compressor = LasZipCompressor::new(&mut expected_output, vlr.clone()).unwrap();
+            compressor.compress_many(&points).unwrap();
+            compressor.done().unwrap();
+        }
+
+        let mut compressor =
+            AsyncLasZipCompressor::new(Cursor::new(Vec::<u8>::new()), vlr.clone()).unwrap();
+        // Compress in batches that do not match the chunk size
+        for batch in points.chunks(3 * point_size) {
+            compressor.compress_many(batch).await.unwrap();
+        }
+        compressor.done().await.unwrap();
+        let mut compressed_output = compressor.into_inner();
+        assert_eq!(compressed_output.get_ref(), expected_output.get_ref());
+
+        compressed_output.set_position(0);
+        let mut decompressor =
+            AsyncLasZipDecompressor::new(compressed_output, vlr, num_points as u64)
+                .await
+                .unwrap();
+        let mut decompressed_points = vec![0u8; points.len()];
+        for batch in decompressed_points.chunks_mut(5 * point_size) {
+            decompressor.decompress_many(batch).await.unwrap();
+        }
+        assert_eq!(decompressed_points, points);
+
+        let mut point = vec![0u8; point_size];
+        for point_idx in [num_points - 1, 0, 8, 9, 3] {
+            decompressor.seek(point_idx as u64).await.unwrap();
+            decompressor.decompress_one(&mut point).await.unwrap();
+            assert_eq!(point, &points[point_idx * point_size..][..point_size]);
+        }
+        assert!(matches!(
+            decompressor.seek(num_points as u64).await,
+            Err(LasZipError::PointIndexOutOfBounds { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_async_variable_size_chunks() {
+        let (points, point_format_id) = read_las_points("tests/data/point-time-color.las");
+        let vlr = vlr_for_point_format(point_format_id, None);
+        let point_size = vlr.items_size() as usize;
+        let chunk_sizes = [1usize, 5, 3, 4];
+
+        let mut compressor =
+            AsyncLasZipCompressor::new(Cursor::new(Vec::<u8>::new()), vlr.clone()).unwrap();
+        let mut start = 0;
+        for chunk_size in chunk_sizes.iter() {
+            let chunk = &points[start * point_size..(start + chunk_size) * point_size];
+            compressor.compress_many(chunk).await.unwrap();
+            compressor.finish_current_chunk().await.unwrap();
+            start += chunk_size;
+        }
+        compressor.done().await.unwrap();
+        let num_points = start;
+        let compressed_output = compressor.into_inner();
+
+        // Check with the sync decompressor
+        {
+            let mut decompressor =
+                LasZipDecompressor::new(Cursor::new(compressed_output.get_ref()), vlr.clone())
+                    .unwrap();
+            let mut decompressed_points = vec![0u8; num_points * point_size];
+            decompressor
+                .decompress_many(&mut decompressed_points)
+                .unwrap();
+            assert_eq!(decompressed_points, &points[..num_points * point_size]);
+        }
+
+        // Data after the chunk table (e.g. EVLRs) must not be read as part of it
+        let mut compressed_output = compressed_output.into_inner();
+        compressed_output.extend_from_slice(&[0xFFu8; 1024]);
+        let mut decompressor =
+            AsyncLasZipDecompressor::new(Cursor::new(compressed_output), vlr, num_points as u64)
+                .await
+                .unwrap();
+        let mut decompressed_points = vec![0u8; num_points * point_size];
+        decompressor
+            .decompress_many(&mut decompressed_points)
+            .await
+            .unwrap();
+        assert_eq!(decompressed_points, &points[..num_points * point_size]);
+
+        let mut point = vec![0u8; point_size];
+        assert!(decompressor.decompress_one(&mut point).await.is_err());
+        decompressor.seek(6).await.unwrap();
+        decompressor.decompress_one(&mut point).await.unwrap();
+        assert_eq!(point, &points[6 * point_size..7 * point_size]);
+    }
+}
@@ -14,9 +14,10 @@ mod tests {
 
         // Prepare LAZ file decompression
         let mut laz_file = File::open(laz_path).unwrap();
-        let laz_header = laz::las::file::QuickHeader::read_from(&mut laz_file).unwrap();
-        let laz_vlr =
-            laz::las::file::read_vlrs_and_get_laszip_vlr(&mut laz_file, &laz_header).unwrap();
+        let laz_header = laz::las::file::LasHeader::read_from(&mut laz_file).unwrap();
+        let laz_vlr = laz::las::file::read_vlrs_and_get_laszip_vlr(&mut laz_file, &laz_header)
+            .unwrap()
+            .unwrap();
         laz_file
             .seek(SeekFrom::Start(laz_header.offset_to_points as u64))
             .unwrap();
@@ -24,7 +25,7 @@ mod tests {
 
         // Prepare LAS file that is our ground truth
         let mut las_file = File::open(las_path).unwrap();
-        let las_header = laz::las::file::QuickHeader::read_from(&mut las_file).unwrap();
+        let las_header = laz::las::file::LasHeader::read_from(&mut las_file).unwrap();
         las_file
             .seek(SeekFrom::Start(las_header.offset_to_points as u64))
             .unwrap();
@@ -57,9 +58,10 @@ mod tests {
 
         // Prepare LAZ file decompression
         let mut laz_file = File::open(laz_path).unwrap();
-        let laz_header = laz::las::file::QuickHeader::read_from(&mut laz_file).unwrap();
-        let laz_vlr =
-            laz::las::file::read_vlrs_and_get_laszip_vlr(&mut laz_file, &laz_header).unwrap();
+        let laz_header = laz::las::file::LasHeader::read_from(&mut laz_file).unwrap();
+        let laz_vlr = laz::las::file::read_vlrs_and_get_laszip_vlr(&mut laz_file, &laz_header)
+            .unwrap()
+            .unwrap();
         laz_file
             .seek(SeekFrom::Start(laz_header.offset_to_points as u64))
             .unwrap();
@@ -67,7 +69,7 @@ mod tests {
 
         // Prepare LAS file that is our ground truth
         let mut las_file = File::open(las_path).unwrap();
-        let las_header = laz::las::file::QuickHeader::read_from(&mut las_file).unwrap();
+        let las_header = laz::las::file::LasHeader::read_from(&mut las_file).unwrap();
         las_file
             .seek(SeekFrom::Start(las_header.offset_to_points as u64))
             .unwrap();
@@ -91,4 +93,34 @@ mod tests {
             assert_eq!(point, expected_point);
         }
     }
+
+    #[test]
+    fn test_par_decompress_columns() {
+        let laz_path = "tests/data/extra-bytes.laz";
+        let mut laz_file = File::open(laz_path).unwrap();
+        let (laz_header, laz_vlr) = laz::las::file::read_header_and_vlrs(&mut laz_file).unwrap();
+        let laz_vlr = laz_vlr.unwrap();
+        let num_points = laz_header.num_points as usize;
+
+        let mut expected = laz::las::PointColumns::default();
+        {
+            let mut decompressor =
+                laz::LasZipDecompressor::new(&mut laz_file, laz_vlr.clone()).unwrap();
+            decompressor
+                .decompress_columns(num_points, &mut expected)
+                .unwrap();
+        }
+
+        laz_file
+            .seek(SeekFrom::Start(laz_header.offset_to_points as u64))
+            .unwrap();
+        let mut decompressor = laz::ParLasZipDecompressor::new(laz_file, laz_vlr).unwrap();
+        let mut columns = laz::las::PointColumns::default();
+        decompressor.decompress_columns(50, &mut columns).unwrap();
+        decompressor
+            .decompress_columns(num_points - 50, &mut columns)
+            .unwrap();
+
+        assert_eq!(columns, expected);
+    }
 }
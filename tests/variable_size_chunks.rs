@@ -2,6 +2,7 @@ use std::fs::File;
 use std::io::{BufReader, Cursor};
 
 use laz::las::file::SimpleReader;
+use laz::laszip::ChunkTable;
 use laz::{LasZipCompressor, LasZipDecompressor, LazItemRecordBuilder, LazVlrBuilder};
 
 fn organize_as_variable_size_chunks(
@@ -148,7 +149,145 @@ fn test_variable_size_chunks_parallel_decompression() {
     // 3. Decompress what we just compressed and see if we get the same points
     // that we gave to the compressor
     compressed_output.set_position(0);
-    let mut decompressor = ParLasZipDecompressor::new(&mut compressed_output, laz_vlr).unwrap();
+    let mut decompressor =
+        ParLasZipDecompressor::new(&mut compressed_output, laz_vlr.clone()).unwrap();
+    let num_points_compressed = chunk_sizes.iter().sum::<usize>();
+    let mut points_out = vec![0u8; point_size * num_points_compressed];
+    decompressor.decompress_many(&mut points_out).unwrap();
+    check_chunks(&points_out, &chunks);
+
+    // The batches of decompress_columns are bounded by the chunks point counts,
+    // not by the chunk size of the vlr (u32::MAX)
+    compressed_output.set_position(0);
+    let mut decompressor =
+        ParLasZipDecompressor::new(&mut compressed_output, laz_vlr.clone()).unwrap();
+    let mut columns = laz::las::PointColumns::default();
+    decompressor
+        .decompress_columns(num_points_compressed, &mut columns)
+        .unwrap();
+    compressed_output.set_position(0);
+    let mut decompressor = LasZipDecompressor::new(&mut compressed_output, laz_vlr).unwrap();
+    let mut expected = laz::las::PointColumns::default();
+    decompressor
+        .decompress_columns(num_points_compressed, &mut expected)
+        .unwrap();
+    assert_eq!(columns, expected);
+    assert_eq!(columns.len(), num_points_compressed);
+}
+
+/// Test that seeking to every point of variable size chunks,
+/// including the first and last point of each chunk, gives the expected point.
+#[test]
+fn test_variable_size_chunks_seek() {
+    let las_file = BufReader::new(File::open("tests/data/point-time-color.las").unwrap());
+    let mut las_reader = SimpleReader::new(las_file).unwrap();
+    let mut las_points_bytes = Vec::<u8>::new();
+    las_reader.read_to_end(&mut las_points_bytes).unwrap();
+
+    let point_size = las_reader.header.point_size as usize;
+    let chunk_sizes = [1, 2, 3, 4, 5, 6, 5, 4, 3, 2, 1];
+    let chunks = organize_as_variable_size_chunks(&las_points_bytes, point_size, &chunk_sizes);
+    let expected_points = chunks.concat();
+    let num_points_compressed = chunk_sizes.iter().sum::<usize>();
+
+    let laz_vlr = LazVlrBuilder::default()
+        .with_point_format(las_reader.header.point_format_id, 0)
+        .unwrap()
+        .with_variable_chunk_size()
+        .build();
+    let mut compressed_output = Cursor::new(Vec::<u8>::new());
+    {
+        let mut compressor =
+            LasZipCompressor::new(&mut compressed_output, laz_vlr.clone()).unwrap();
+        compressor.compress_chunks(&chunks).unwrap();
+        compressor.done().unwrap();
+    }
+
+    compressed_output.set_position(0);
+    let mut point = vec![0u8; point_size];
+    {
+        let mut decompressor =
+            LasZipDecompressor::new(&mut compressed_output, laz_vlr.clone()).unwrap();
+        // Seek backward to also test going back to previous chunks
+        for i in (0..num_points_compressed).rev() {
+            decompressor.seek(i as u64).unwrap();
+            decompressor.decompress_one(&mut point).unwrap();
+            assert_eq!(
+                point.as_slice(),
+                &expected_points[i * point_size..(i + 1) * point_size]
+            );
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    {
+        use laz::ParLasZipDecompressor;
+        compressed_output.set_position(0);
+        let mut decompressor = ParLasZipDecompressor::new(&mut compressed_output, laz_vlr).unwrap();
+        for i in (0..num_points_compressed).rev() {
+            decompressor.seek(i as u64).unwrap();
+            decompressor.decompress_many(&mut point).unwrap();
+            assert_eq!(
+                point.as_slice(),
+                &expected_points[i * point_size..(i + 1) * point_size]
+            );
+        }
+    }
+}
+
+/// Test that appending variable size chunks to existing data
+/// keeps the existing chunks and adds the new ones.
+#[test]
+fn test_variable_size_chunks_append() {
+    let las_file = BufReader::new(File::open("tests/data/point-time-color.las").unwrap());
+    let mut las_reader = SimpleReader::new(las_file).unwrap();
+    let mut las_points_bytes = Vec::<u8>::new();
+    las_reader.read_to_end(&mut las_points_bytes).unwrap();
+
+    let point_size = las_reader.header.point_size as usize;
+    let chunk_sizes = [1, 2, 3, 4, 5, 6, 5, 4, 3, 2, 1];
+    let chunks = organize_as_variable_size_chunks(&las_points_bytes, point_size, &chunk_sizes);
+
+    let laz_vlr = LazVlrBuilder::default()
+        .with_point_format(las_reader.header.point_format_id, 0)
+        .unwrap()
+        .with_variable_chunk_size()
+        .build();
+    let mut compressed_output = Cursor::new(Vec::<u8>::new());
+    {
+        let mut compressor =
+            LasZipCompressor::new(&mut compressed_output, laz_vlr.clone()).unwrap();
+        compressor.compress_chunks(&chunks[..5]).unwrap();
+        compressor.done().unwrap();
+    }
+    // done ends the data with an empty chunk, that appending replaces
+    compressed_output.set_position(0);
+    let chunk_table = ChunkTable::read_from(&mut compressed_output, &laz_vlr).unwrap();
+    assert_eq!(chunk_table.len(), 6);
+    assert_eq!(chunk_table[5].point_count, 0);
+    compressed_output.set_position(0);
+    {
+        let mut compressor = LasZipCompressor::open_for_append(
+            &mut compressed_output,
+            laz_vlr.clone(),
+            chunk_sizes[..5].iter().sum::<usize>() as u64,
+        )
+        .unwrap();
+        compressor.compress_chunks(&chunks[5..]).unwrap();
+        compressor.done().unwrap();
+    }
+
+    compressed_output.set_position(0);
+    let chunk_table = ChunkTable::read_from(&mut compressed_output, &laz_vlr).unwrap();
+    let point_counts = chunk_table
+        .as_ref()
+        .iter()
+        .map(|entry| entry.point_count as usize)
+        .collect::<Vec<usize>>();
+    assert_eq!(point_counts, chunk_sizes);
+
+    compressed_output.set_position(0);
+    let mut decompressor = LasZipDecompressor::new(&mut compressed_output, laz_vlr).unwrap();
     let num_points_compressed = chunk_sizes.iter().sum::<usize>();
     let mut points_out = vec![0u8; point_size * num_points_compressed];
     decompressor.decompress_many(&mut points_out).unwrap();